@@ -0,0 +1,93 @@
+use std::io;
+
+/// CPUs `pid` may run on, one entry per logical CPU. `cpus` is the number
+/// of logical CPUs in the system.
+#[cfg(target_os = "linux")]
+pub fn affinity(pid: u32, cpus: usize) -> io::Result<Vec<bool>> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::sched_getaffinity(
+            pid as libc::pid_t,
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &mut set,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok((0..cpus)
+        .map(|cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+        .collect())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn affinity(_pid: u32, _cpus: usize) -> io::Result<Vec<bool>> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Restricts `pid` to the CPUs set in `mask`. On Linux this only affects
+/// the given thread; see `set_affinity_all_threads`.
+#[cfg(target_os = "linux")]
+pub fn set_affinity(pid: u32, mask: &[bool]) -> io::Result<()> {
+    if !mask.contains(&true) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "at least one CPU must be selected",
+        ));
+    }
+
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for (cpu, _) in mask.iter().enumerate().filter(|(_, allowed)| **allowed) {
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+
+    let result = unsafe {
+        libc::sched_setaffinity(
+            pid as libc::pid_t,
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &set,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_affinity(_pid: u32, _mask: &[bool]) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Thread IDs of `pid`, read from `/proc/<pid>/task`.
+#[cfg(target_os = "linux")]
+pub fn threads(pid: u32) -> io::Result<Vec<u32>> {
+    let mut tids: Vec<u32> = std::fs::read_dir(format!("/proc/{pid}/task"))?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .collect();
+    tids.sort_unstable();
+    Ok(tids)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn threads(_pid: u32) -> io::Result<Vec<u32>> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Applies `mask` to every thread of `pid`. Threads that exit in the
+/// meantime are skipped; the first other failure is returned.
+pub fn set_affinity_all_threads(pid: u32, mask: &[bool]) -> io::Result<()> {
+    for tid in threads(pid)? {
+        match set_affinity(tid, mask) {
+            Ok(()) => {}
+            #[cfg(unix)]
+            Err(err) if err.raw_os_error() == Some(libc::ESRCH) => {}
+            Err(err) => {
+                return Err(io::Error::new(err.kind(), format!("thread {tid}: {err}")));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::process_table::{ProcessColumnKind, ProcessInfo, SortOrder, compare_processes};
+use crate::process_tree::TreeNode;
+
+/// Marks a row that stands in for all processes of one application in apps
+/// mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppGroup {
+    /// Executable path, or the process name when the path is unknown.
+    pub key: String,
+    /// PIDs of the grouped processes, lowest first.
+    pub members: Vec<u32>,
+}
+
+/// What processes are grouped by: the executable path where known, the
+/// name otherwise.
+pub fn group_key(row: &ProcessInfo) -> &str {
+    row.exe
+        .as_deref()
+        .filter(|exe| !exe.is_empty())
+        .unwrap_or(&row.name)
+}
+
+/// Groups `rows` by executable. Apps with several processes get a group row
+/// with their summed usage, followed by the members when the group is in
+/// `expanded` or `expand_all` is set. Apps with a single process keep their
+/// plain row. Groups and members are sorted by the given column.
+pub fn build_groups(
+    rows: &[ProcessInfo],
+    expanded: &HashSet<String>,
+    column: ProcessColumnKind,
+    order: SortOrder,
+    expand_all: bool,
+) -> Vec<ProcessInfo> {
+    let mut apps: HashMap<&str, Vec<&ProcessInfo>> = HashMap::new();
+    for row in rows {
+        apps.entry(group_key(row)).or_default().push(row);
+    }
+
+    let mut entries: Vec<(ProcessInfo, Vec<&ProcessInfo>)> = apps
+        .into_iter()
+        .map(|(key, mut members)| {
+            if members.len() == 1 {
+                return (members[0].clone(), Vec::new());
+            }
+            members.sort_by(|a, b| compare_processes(a, b, column, order));
+            (group_row(key, &members), members)
+        })
+        .collect();
+    entries.sort_by(|a, b| compare_processes(&a.0, &b.0, column, order));
+
+    let mut flattened = Vec::with_capacity(rows.len());
+    for (mut head, members) in entries {
+        let Some(group) = &head.group else {
+            flattened.push(head);
+            continue;
+        };
+
+        let is_expanded = expand_all || expanded.contains(&group.key);
+        head.tree = Some(TreeNode {
+            depth: 0,
+            has_children: true,
+            expanded: is_expanded,
+            subtree_cpu: head.cpu_usage,
+            subtree_memory: head.memory_bytes,
+        });
+        flattened.push(head);
+
+        if is_expanded {
+            flattened.extend(members.into_iter().map(|member| ProcessInfo {
+                tree: Some(TreeNode {
+                    depth: 1,
+                    has_children: false,
+                    expanded: true,
+                    subtree_cpu: member.cpu_usage,
+                    subtree_memory: member.memory_bytes,
+                }),
+                ..member.clone()
+            }));
+        }
+    }
+
+    flattened
+}
+
+/// Row for a whole app. Resource columns are summed over the members; the
+/// rest is taken from the member with the lowest PID.
+fn group_row(key: &str, members: &[&ProcessInfo]) -> ProcessInfo {
+    let leader = members
+        .iter()
+        .min_by_key(|member| member.pid)
+        .expect("groups have members");
+    let mut pids: Vec<u32> = members.iter().map(|member| member.pid).collect();
+    pids.sort_unstable();
+
+    ProcessInfo {
+        name: format!("{} ({})", leader.name, members.len()),
+        cpu_usage: members.iter().map(|member| member.cpu_usage).sum(),
+        memory_bytes: members.iter().map(|member| member.memory_bytes).sum(),
+        disk_read_rate: members.iter().map(|member| member.disk_read_rate).sum(),
+        disk_write_rate: members.iter().map(|member| member.disk_write_rate).sum(),
+        virtual_memory: members.iter().map(|member| member.virtual_memory).sum(),
+        cpu_time: members.iter().map(|member| member.cpu_time).sum(),
+        threads: members.iter().map(|member| member.threads).sum(),
+        open_files: members.iter().map(|member| member.open_files).sum(),
+        group: Some(AppGroup {
+            key: key.to_string(),
+            members: pids,
+        }),
+        tree: None,
+        ..(*leader).clone()
+    }
+}
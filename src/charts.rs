@@ -1,3 +1,6 @@
+use std::time::Instant;
+
+use crate::history::{Bucket, History};
 use crate::ui;
 use plotters::coord::Shift;
 use plotters::prelude::*;
@@ -7,6 +10,9 @@ use plotters_iced2::Chart;
 pub struct ThumbChart<'a> {
     pub data: &'a [f64],
     pub color: RGBColor,
+    pub max_size: f64,
+    /// Optional second series drawn on top of `data`, e.g. TX over RX.
+    pub overlay: Option<(&'a [f64], RGBColor)>,
 }
 
 impl<'a> Chart<ui::Message> for ThumbChart<'a> {
@@ -26,7 +32,7 @@ impl<'a> Chart<ui::Message> for ThumbChart<'a> {
 
         let mut chart = builder
             .margin(2)
-            .build_cartesian_2d(0f64..x_max, 0f64..100f64)
+            .build_cartesian_2d(0f64..x_max, 0f64..self.max_size)
             .expect("failed to build thumb chart");
 
         chart
@@ -44,14 +50,65 @@ impl<'a> Chart<ui::Message> for ThumbChart<'a> {
                 ))
                 .expect("failed to draw thumb series");
         }
+
+        if let Some((overlay, color)) = self.overlay
+            && !overlay.is_empty()
+        {
+            chart
+                .draw_series(LineSeries::new(
+                    overlay.iter().enumerate().map(|(i, &v)| (i as f64, v)),
+                    ShapeStyle::from(color).stroke_width(2),
+                ))
+                .expect("failed to draw thumb overlay");
+        }
     }
 }
 
 pub struct DetailChart<'a> {
-    pub data: &'a [f64],
+    pub data: &'a History,
     pub color: RGBColor,
     pub y_label: &'a str,
     pub max_size: f64,
+    /// Optional second series drawn as a line on top of the filled `data`.
+    pub overlay: Option<(&'a History, RGBColor)>,
+}
+
+impl DetailChart<'_> {
+    /// Places the newest sample of `data` at 0 and older ones at the
+    /// seconds since they were taken, so gaps and uneven ticks show as
+    /// they happened. The older part of the window comes from bucket
+    /// averages.
+    fn points<'b>(&self, history: &'b History) -> impl Iterator<Item = (f64, f64)> + 'b {
+        let newest = self.data.times().last().copied();
+        history
+            .series()
+            .map(move |(time, v)| (seconds_before(newest, time), v))
+    }
+
+    /// Outline of the range each bucket of `data` spanned, for shading
+    /// behind the averages.
+    fn bucket_range(&self) -> Vec<(f64, f64)> {
+        let newest = self.data.times().last().copied();
+        let x = |bucket: &Bucket| seconds_before(newest, bucket.midpoint());
+        let upper = self.data.buckets().map(|b| (x(b), b.max));
+        let lower = self.data.buckets().rev().map(|b| (x(b), b.min));
+        upper.chain(lower).collect()
+    }
+
+    /// Seconds covered by the chart, at least one so the axis has a range.
+    fn span(&self) -> f64 {
+        self.points(self.data)
+            .next()
+            .map_or(0.0, |(x, _)| -x)
+            .max(1.0)
+    }
+}
+
+/// `time` as negative seconds before `newest`.
+fn seconds_before(newest: Option<Instant>, time: Instant) -> f64 {
+    newest.map_or(0.0, |newest| {
+        -newest.saturating_duration_since(time).as_secs_f64()
+    })
 }
 
 impl<'a> Chart<ui::Message> for DetailChart<'a> {
@@ -63,41 +120,54 @@ impl<'a> Chart<ui::Message> for DetailChart<'a> {
     }
 
     fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut builder: ChartBuilder<DB>) {
-        let x_max = if self.data.len() > 1 {
-            (self.data.len() - 1) as f64
-        } else {
-            1.0
-        };
+        let x_min = -self.span();
 
         let mut chart = builder
             .x_label_area_size(30)
             .y_label_area_size(40)
             .margin(10)
-            .build_cartesian_2d(0f64..x_max, 0f64..self.max_size)
+            .build_cartesian_2d(x_min..0f64, 0f64..self.max_size)
             .expect("failed to build detail chart");
 
+        let precision = if x_min > -10.0 { 1 } else { 0 };
+
         chart
             .configure_mesh()
             .label_style(("sans-serif", 12, &WHITE))
             .bold_line_style(WHITE.mix(0.1))
             .light_line_style(WHITE.mix(0.05))
             .axis_style(WHITE.mix(0.3))
-            .x_desc("Time (s)")
+            .x_desc("Seconds ago")
+            .x_label_formatter(&|x| format!("{:.*}", precision, x.abs()))
             .y_desc(self.y_label)
             .draw()
             .expect("failed to draw detail mesh");
 
+        let range = self.bucket_range();
+        if range.len() > 2 {
+            chart
+                .draw_series(std::iter::once(Polygon::new(range, self.color.mix(0.1))))
+                .expect("failed to draw detail range");
+        }
+
         if !self.data.is_empty() {
             chart
                 .draw_series(
-                    AreaSeries::new(
-                        self.data.iter().enumerate().map(|(i, &v)| (i as f64, v)),
-                        0.0,
-                        self.color.mix(0.2),
-                    )
-                    .border_style(ShapeStyle::from(self.color).stroke_width(2)),
+                    AreaSeries::new(self.points(self.data), 0.0, self.color.mix(0.2))
+                        .border_style(ShapeStyle::from(self.color).stroke_width(2)),
                 )
                 .expect("failed to draw detail series");
         }
+
+        if let Some((overlay, color)) = self.overlay
+            && !overlay.is_empty()
+        {
+            chart
+                .draw_series(LineSeries::new(
+                    self.points(overlay),
+                    ShapeStyle::from(color).stroke_width(2),
+                ))
+                .expect("failed to draw detail overlay");
+        }
     }
 }
@@ -0,0 +1,379 @@
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde_json::{Value, json};
+
+use crate::export::{self, ExportFormat, round, text_table};
+use crate::process_table::{ProcessColumnKind, ProcessInfo, SlowColumns, sort_processes};
+use crate::query::Query;
+use crate::sampler::{Sampler, SystemSnapshot};
+use crate::settings::{Settings, Units};
+use crate::utilities::{format_bytes, format_duration};
+
+/// Without a subcommand the window opens; the subcommands print reports
+/// for terminals, SSH sessions and scripts.
+#[derive(Debug, Parser)]
+#[command(
+    name = "task-manager",
+    version,
+    about = "System monitor and process manager"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Print CPU, memory, disk and network usage
+    Snapshot(SnapshotArgs),
+    /// Print the processes using the most resources
+    Top(TopArgs),
+    /// Open the terminal interface, for sessions without a display
+    Tui,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct OutputArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+    /// Same as --format json
+    #[arg(long, conflicts_with_all = ["format", "csv"])]
+    pub json: bool,
+    /// Same as --format csv
+    #[arg(long, conflicts_with = "format")]
+    pub csv: bool,
+    /// Print a new report every SECONDS instead of once
+    #[arg(short, long, value_name = "SECONDS", value_parser = parse_interval)]
+    pub interval: Option<Duration>,
+    /// Stop after this many reports; with --interval alone the reports go
+    /// on until interrupted
+    #[arg(long, requires = "interval")]
+    pub count: Option<u64>,
+}
+
+impl OutputArgs {
+    pub fn format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else if self.csv {
+            OutputFormat::Csv
+        } else {
+            self.format
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SnapshotArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TopArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+    /// Column to sort by, e.g. cpu, mem, disk, pid or name
+    #[arg(short, long, default_value = "cpu")]
+    pub sort: ProcessColumnKind,
+    /// Sort the other way round from the column's usual order
+    #[arg(short, long)]
+    pub reverse: bool,
+    /// Number of processes to print; 0 prints them all
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+    /// Comma separated columns to print
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        default_value = "pid,name,cpu,mem,disk"
+    )]
+    pub columns: Vec<ProcessColumnKind>,
+    /// Only print processes matching a search query, e.g. "user:root cpu>5"
+    #[arg(short, long, value_parser = parse_query)]
+    pub query: Option<Query>,
+}
+
+fn parse_interval(seconds: &str) -> Result<Duration, String> {
+    match seconds.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(Duration::from_secs_f64(seconds)),
+        _ => Err(format!("\"{seconds}\" isn't a positive number of seconds")),
+    }
+}
+
+fn parse_query(query: &str) -> Result<Query, String> {
+    Query::parse(query).map_err(|err| err.to_string())
+}
+
+/// Runs a subcommand and reports failures on stderr. A closed pipe, as
+/// when the output goes through `head`, ends the reports quietly.
+pub fn run(command: CliCommand) -> ExitCode {
+    let units = Settings::load().0.units;
+    let result = match &command {
+        CliCommand::Snapshot(args) => repeat(
+            &args.output,
+            SlowColumns::default(),
+            false,
+            |snapshot, compact| {
+                snapshot_report(snapshot, args.output.format(), units, unix_time(), compact)
+            },
+        ),
+        CliCommand::Top(args) => {
+            let slow = SlowColumns::among(args.columns.iter().copied().chain([args.sort]));
+            repeat(&args.output, slow, true, |snapshot, compact| {
+                top_report(&snapshot.processes, args, units, compact)
+            })
+        }
+        CliCommand::Tui => crate::tui::run(),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("task-manager: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints one report, or one every interval. Repeated JSON reports are
+/// printed one per line so they can be read as JSON Lines.
+fn repeat(
+    output: &OutputArgs,
+    slow_columns: SlowColumns,
+    with_processes: bool,
+    report: impl Fn(&SystemSnapshot, bool) -> String,
+) -> io::Result<()> {
+    let mut sampler = Sampler::new();
+    sampler.slow_columns = slow_columns;
+    let mut stdout = io::stdout().lock();
+    let compact = output.interval.is_some() && output.format() == OutputFormat::Json;
+
+    // CPU usage and throughput are measured between two samples.
+    sampler.wait_until_ready();
+
+    let mut printed = 0;
+    loop {
+        let snapshot = sampler.sample(with_processes);
+        if printed > 0 && !compact {
+            writeln!(stdout)?;
+        }
+        write!(stdout, "{}", report(&snapshot, compact))?;
+        stdout.flush()?;
+        printed += 1;
+
+        match (output.interval, output.count) {
+            (Some(_), Some(count)) if printed >= count => return Ok(()),
+            (Some(interval), _) => std::thread::sleep(interval),
+            (None, _) => return Ok(()),
+        }
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// System-wide usage as a table, a JSON object or `metric,value` CSV rows.
+pub fn snapshot_report(
+    snapshot: &SystemSnapshot,
+    format: OutputFormat,
+    units: Units,
+    timestamp: u64,
+    compact: bool,
+) -> String {
+    let SystemSnapshot {
+        cpu,
+        memory,
+        disks,
+        networks,
+        ..
+    } = snapshot;
+    let memory_usage = memory.usage_percent();
+    let frequency = cpu.frequency() as f64;
+
+    match format {
+        OutputFormat::Table => {
+            let mut out = format!(
+                "CPU     {:.1}% at {frequency:.2} GHz, {} logical processors\n\
+                 Memory  {memory_usage:.1}%, {} of {}\n\
+                 Uptime  {}\n",
+                cpu.usage,
+                cpu.cores.len(),
+                format_bytes(memory.used, units),
+                format_bytes(memory.total, units),
+                format_duration(snapshot.uptime)
+            );
+
+            if !disks.is_empty() {
+                out.push('\n');
+                out += &text_table(
+                    &["Disk", "Mount", "File system", "Used", "Total", "MB/s"].map(String::from),
+                    &disks
+                        .iter()
+                        .map(|d| {
+                            vec![
+                                d.name.clone(),
+                                d.mount_point.display().to_string(),
+                                d.file_system.clone(),
+                                format_bytes(
+                                    d.total_space.saturating_sub(d.available_space),
+                                    units,
+                                ),
+                                format_bytes(d.total_space, units),
+                                format!("{:.2}", d.usage),
+                            ]
+                        })
+                        .collect::<Vec<_>>(),
+                    &[false, false, false, true, true, true],
+                );
+            }
+
+            if !networks.is_empty() {
+                out.push('\n');
+                out += &text_table(
+                    &["Interface", "Receive KB/s", "Send KB/s"].map(String::from),
+                    &networks
+                        .iter()
+                        .map(|n| {
+                            vec![
+                                n.name.clone(),
+                                format!("{:.1}", n.received),
+                                format!("{:.1}", n.transmitted),
+                            ]
+                        })
+                        .collect::<Vec<_>>(),
+                    &[false, true, true],
+                );
+            }
+            out
+        }
+        OutputFormat::Json | OutputFormat::Csv => {
+            let value = json!({
+                "timestamp": timestamp,
+                "uptime_seconds": snapshot.uptime,
+                "cpu": {
+                    "usage_percent": round(cpu.usage as f64),
+                    "frequency_ghz": round(frequency),
+                    "cores": cpu.cores.iter().map(|c| json!({
+                        "name": c.name,
+                        "usage_percent": round(c.usage as f64),
+                        "frequency_ghz": round(c.frequency as f64),
+                    })).collect::<Vec<_>>(),
+                },
+                "memory": {
+                    "used_bytes": memory.used,
+                    "total_bytes": memory.total,
+                    "usage_percent": round(memory_usage),
+                },
+                "disks": disks.iter().map(|d| json!({
+                    "name": d.name,
+                    "mount_point": d.mount_point,
+                    "file_system": d.file_system,
+                    "total_bytes": d.total_space,
+                    "available_bytes": d.available_space,
+                    "throughput_mb_s": round(d.usage),
+                })).collect::<Vec<_>>(),
+                "networks": networks.iter().map(|n| json!({
+                    "name": n.name,
+                    "received_kb_s": round(n.received),
+                    "transmitted_kb_s": round(n.transmitted),
+                    "total_received_bytes": n.total_received,
+                    "total_transmitted_bytes": n.total_transmitted,
+                })).collect::<Vec<_>>(),
+            });
+
+            if format == OutputFormat::Csv {
+                let mut rows = Vec::new();
+                flatten("", &value, &mut rows);
+                let mut out = "metric,value\r\n".to_string();
+                for (metric, value) in rows {
+                    out += &format!("{metric},{value}\r\n");
+                }
+                out
+            } else if compact {
+                value.to_string() + "\n"
+            } else {
+                serde_json::to_string_pretty(&value).expect("JSON values always serialize") + "\n"
+            }
+        }
+    }
+}
+
+/// Turns nested objects and arrays into dotted metric names, with array
+/// entries numbered from 0. Values are quoted where CSV needs it.
+fn flatten(prefix: &str, value: &Value, rows: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten(&join(key), value, rows);
+            }
+        }
+        Value::Array(items) => {
+            for (index, value) in items.iter().enumerate() {
+                flatten(&join(&index.to_string()), value, rows);
+            }
+        }
+        Value::String(text) if text.contains([',', '"', '\n', '\r']) => {
+            rows.push((
+                prefix.to_string(),
+                format!("\"{}\"", text.replace('"', "\"\"")),
+            ));
+        }
+        Value::String(text) => rows.push((prefix.to_string(), text.clone())),
+        Value::Null => rows.push((prefix.to_string(), String::new())),
+        value => rows.push((prefix.to_string(), value.to_string())),
+    }
+}
+
+/// The heaviest processes by the chosen column, printed like the process
+/// table or exported like the window's Export button.
+pub fn top_report(rows: &[ProcessInfo], args: &TopArgs, units: Units, compact: bool) -> String {
+    let mut rows: Vec<ProcessInfo> = rows
+        .iter()
+        .filter(|row| args.query.as_ref().is_none_or(|query| query.matches(row)))
+        .cloned()
+        .collect();
+
+    let order = if args.reverse {
+        args.sort.default_order().toggle()
+    } else {
+        args.sort.default_order()
+    };
+    sort_processes(&mut rows, args.sort, order);
+    if args.limit > 0 {
+        rows.truncate(args.limit);
+    }
+
+    match args.output.format() {
+        OutputFormat::Table => export::to_table(&args.columns, &rows, units),
+        OutputFormat::Csv => export::export(ExportFormat::Csv, &args.columns, &rows, units),
+        OutputFormat::Json if compact => {
+            export::json_rows(&args.columns, &rows, units).to_string() + "\n"
+        }
+        OutputFormat::Json => export::to_json(&args.columns, &rows, units) + "\n",
+    }
+}
@@ -0,0 +1,64 @@
+use std::time::Instant;
+
+use sysinfo::System;
+
+use crate::history::{History, Retention};
+use crate::utilities::mhz_to_ghz;
+
+/// Usage and frequency history for one logical CPU.
+pub struct CoreState {
+    pub name: String,
+    pub usage: f32,
+    pub frequency: f32,
+    pub usage_history: History,
+    pub frequency_history: History,
+}
+
+impl CoreState {
+    pub fn new(name: String, retention: Retention) -> Self {
+        Self {
+            name,
+            usage: 0.0,
+            frequency: 0.0,
+            usage_history: History::new(retention),
+            frequency_history: History::new(retention),
+        }
+    }
+
+    pub fn record(&mut self, usage: f32, frequency_ghz: f32) {
+        self.record_at(Instant::now(), usage, frequency_ghz);
+    }
+
+    pub fn record_at(&mut self, time: Instant, usage: f32, frequency_ghz: f32) {
+        self.usage = usage;
+        self.frequency = frequency_ghz;
+        self.usage_history.push_at(time, usage as f64);
+        self.frequency_history.push_at(time, frequency_ghz as f64);
+    }
+}
+
+/// Pushes a sample for every logical CPU. The core list only changes when
+/// CPUs are hot-plugged, in which case it is rebuilt from scratch.
+pub fn sync_cores(cores: &mut Vec<CoreState>, sys: &System, retention: Retention) {
+    let cpus = sys.cpus();
+
+    if cores.len() != cpus.len() {
+        *cores = cpus
+            .iter()
+            .map(|cpu| CoreState::new(cpu.name().to_string(), retention))
+            .collect();
+    }
+
+    for (core, cpu) in cores.iter_mut().zip(cpus) {
+        core.record(cpu.cpu_usage(), mhz_to_ghz(cpu.frequency()));
+    }
+}
+
+/// Mean frequency across all cores, in GHz.
+pub fn average_frequency(cores: &[CoreState]) -> f32 {
+    if cores.is_empty() {
+        0.0
+    } else {
+        cores.iter().map(|c| c.frequency).sum::<f32>() / cores.len() as f32
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use sysinfo::{Disk, Disks};
+
+use crate::history::{History, Retention};
+use crate::utilities::per_second;
+
+/// Everything the Disk tab needs for a single device. Entries are keyed by
+/// mount point because several volumes can report the same device name.
+pub struct DiskState {
+    pub name: String,
+    pub mount_point: PathBuf,
+    pub file_system: String,
+    pub is_removable: bool,
+    pub total_space: u64,
+    pub available_space: u64,
+    pub usage: f64,
+    pub history: History,
+    /// Time and cumulative read/written counters of the previous sample.
+    prev: Option<(Instant, u64, u64)>,
+}
+
+impl DiskState {
+    pub fn new(name: String, mount_point: PathBuf, retention: Retention) -> Self {
+        Self {
+            name,
+            mount_point,
+            file_system: String::new(),
+            is_removable: false,
+            total_space: 0,
+            available_space: 0,
+            usage: 0.0,
+            history: History::new(retention),
+            prev: None,
+        }
+    }
+
+    pub fn record(&mut self, total_read: u64, total_written: u64) -> f64 {
+        self.record_at(Instant::now(), total_read, total_written)
+    }
+
+    /// Records a new sample from the cumulative read/written counters and
+    /// returns the throughput in MB/s since the previous sample. The first
+    /// sample only primes the counters so the chart doesn't start with a
+    /// spike of everything read since boot.
+    pub fn record_at(&mut self, time: Instant, total_read: u64, total_written: u64) -> f64 {
+        let usage = match self.prev {
+            Some((prev_time, prev_read, prev_written)) => {
+                let delta = total_read.saturating_sub(prev_read)
+                    + total_written.saturating_sub(prev_written);
+                per_second(delta, time.duration_since(prev_time)) / 1_000_000.0
+            }
+            None => 0.0,
+        };
+
+        self.prev = Some((time, total_read, total_written));
+        self.push_usage_at(time, usage);
+        usage
+    }
+
+    /// Records a throughput in MB/s that was worked out elsewhere, as when
+    /// replaying a recorded session.
+    pub fn push_usage_at(&mut self, time: Instant, usage: f64) {
+        self.usage = usage;
+        self.history.push_at(time, usage);
+    }
+
+    fn update_details(&mut self, disk: &Disk) {
+        self.name = disk.name().display().to_string();
+        self.file_system = disk.file_system().to_string_lossy().to_string();
+        self.is_removable = disk.is_removable();
+        self.total_space = disk.total_space();
+        self.available_space = disk.available_space();
+    }
+}
+
+/// Brings `states` in line with the current disk list: new disks get an
+/// entry, disks that went away are dropped and every remaining disk gets a
+/// new usage sample.
+pub fn sync_disks(states: &mut Vec<DiskState>, disks: &Disks, retention: Retention) {
+    states.retain(|s| {
+        disks
+            .list()
+            .iter()
+            .any(|d| d.mount_point() == s.mount_point)
+    });
+
+    for disk in disks.list() {
+        let index = match states
+            .iter()
+            .position(|s| s.mount_point == disk.mount_point())
+        {
+            Some(index) => index,
+            None => {
+                states.push(DiskState::new(
+                    disk.name().display().to_string(),
+                    disk.mount_point().to_path_buf(),
+                    retention,
+                ));
+                states.len() - 1
+            }
+        };
+
+        let state = &mut states[index];
+        state.update_details(disk);
+
+        let usage = disk.usage();
+        state.record(usage.total_read_bytes, usage.total_written_bytes);
+    }
+}
+
+pub fn find_disk<'a>(states: &'a [DiskState], mount_point: &Path) -> Option<&'a DiskState> {
+    states.iter().find(|s| s.mount_point == mount_point)
+}
@@ -0,0 +1,260 @@
+use serde_json::{Map, Value, json};
+
+use crate::process_table::{
+    ProcessColumnKind, ProcessInfo, cell_text, effective_io_priority, shown_cpu, shown_memory,
+};
+use crate::settings::Units;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Csv, ExportFormat::Json];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportFormat::Csv => write!(f, "CSV"),
+            ExportFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+/// Writes `rows` with the given columns in `format`.
+pub fn export(
+    format: ExportFormat,
+    columns: &[ProcessColumnKind],
+    rows: &[ProcessInfo],
+    units: Units,
+) -> String {
+    match format {
+        ExportFormat::Csv => to_csv(columns, rows, units),
+        ExportFormat::Json => to_json(columns, rows, units),
+    }
+}
+
+/// Rounds to two decimals, so exports don't show f32 noise like
+/// 12.300000190734863.
+pub fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// The value behind a cell: numbers as numbers, in bytes, bytes per
+/// second, percent, milliseconds or seconds since the epoch, and `null`
+/// where the platform didn't report one.
+pub fn raw_value(kind: ProcessColumnKind, row: &ProcessInfo) -> Value {
+    match kind {
+        ProcessColumnKind::Pid if row.group.is_some() => Value::Null,
+        ProcessColumnKind::Pid => json!(row.pid),
+        ProcessColumnKind::Name => json!(row.name),
+        ProcessColumnKind::CpuUsage => json!(round(shown_cpu(row) as f64)),
+        ProcessColumnKind::MemoryUsage => json!(shown_memory(row)),
+        ProcessColumnKind::DiskUsage => json!(row.disk_read_rate + row.disk_write_rate),
+        ProcessColumnKind::Nice => json!(row.nice),
+        ProcessColumnKind::IoPriority => json!(effective_io_priority(row).map(|io| io.to_string())),
+        ProcessColumnKind::User => json!(row.user),
+        ProcessColumnKind::Status => json!(row.status),
+        ProcessColumnKind::Threads => json!(row.threads),
+        ProcessColumnKind::VirtualMemory => json!(row.virtual_memory),
+        ProcessColumnKind::StartTime => json!(row.start_time),
+        ProcessColumnKind::CpuTime => json!(row.cpu_time),
+        ProcessColumnKind::CommandLine => json!(row.command_line),
+        ProcessColumnKind::Executable => json!(row.exe),
+        ProcessColumnKind::ParentPid => json!(row.parent_pid),
+        ProcessColumnKind::Session => json!(row.session_id),
+        ProcessColumnKind::DiskRead => json!(row.disk_read_rate),
+        ProcessColumnKind::DiskWrite => json!(row.disk_write_rate),
+        ProcessColumnKind::OpenFiles => json!(row.open_files),
+    }
+}
+
+/// Whether the table shows a column differently from its raw value, with
+/// units or as a date, so exports carry both.
+pub fn has_formatted_value(kind: ProcessColumnKind) -> bool {
+    matches!(
+        kind,
+        ProcessColumnKind::CpuUsage
+            | ProcessColumnKind::MemoryUsage
+            | ProcessColumnKind::DiskUsage
+            | ProcessColumnKind::VirtualMemory
+            | ProcessColumnKind::StartTime
+            | ProcessColumnKind::CpuTime
+            | ProcessColumnKind::DiskRead
+            | ProcessColumnKind::DiskWrite
+    )
+}
+
+/// One CSV column per raw value, named by the column key, plus a
+/// `<key>_formatted` column wherever the table adds units.
+pub fn to_csv(columns: &[ProcessColumnKind], rows: &[ProcessInfo], units: Units) -> String {
+    let mut header = Vec::new();
+    for kind in columns {
+        header.push(kind.key().to_string());
+        if has_formatted_value(*kind) {
+            header.push(format!("{}_formatted", kind.key()));
+        }
+    }
+
+    let mut lines = vec![csv_line(&header)];
+    for row in rows {
+        let mut fields = Vec::new();
+        for kind in columns {
+            fields.push(match raw_value(*kind, row) {
+                Value::Null => String::new(),
+                Value::String(text) => text,
+                value => value.to_string(),
+            });
+            if has_formatted_value(*kind) {
+                fields.push(cell_text(*kind, row, units));
+            }
+        }
+        lines.push(csv_line(&fields));
+    }
+
+    lines.join("\r\n") + "\r\n"
+}
+
+/// Quotes fields that contain separators, quotes or line breaks, as
+/// RFC 4180 describes.
+fn csv_line(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// An array with one object per row, keyed like the CSV header.
+pub fn to_json(columns: &[ProcessColumnKind], rows: &[ProcessInfo], units: Units) -> String {
+    serde_json::to_string_pretty(&json_rows(columns, rows, units))
+        .expect("JSON values always serialize")
+}
+
+/// The array `to_json` writes.
+pub fn json_rows(columns: &[ProcessColumnKind], rows: &[ProcessInfo], units: Units) -> Value {
+    let rows: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            let mut object = Map::new();
+            for kind in columns {
+                object.insert(kind.key().to_string(), raw_value(*kind, row));
+                if has_formatted_value(*kind) {
+                    object.insert(
+                        format!("{}_formatted", kind.key()),
+                        json!(cell_text(*kind, row, units)),
+                    );
+                }
+            }
+            Value::Object(object)
+        })
+        .collect();
+
+    Value::Array(rows)
+}
+
+/// Rows as the table shows them, with a header of column labels, for
+/// pasting into bug reports and spreadsheets. Tabs and line breaks inside
+/// values become spaces.
+pub fn to_tsv(columns: &[ProcessColumnKind], rows: &[&ProcessInfo], units: Units) -> String {
+    let clean = |value: String| value.replace(['\t', '\n', '\r'], " ");
+
+    let mut lines = vec![
+        columns
+            .iter()
+            .map(|kind| kind.label())
+            .collect::<Vec<_>>()
+            .join("\t"),
+    ];
+    for row in rows {
+        lines.push(
+            columns
+                .iter()
+                .map(|kind| clean(cell_text(*kind, row, units)))
+                .collect::<Vec<_>>()
+                .join("\t"),
+        );
+    }
+    lines.join("\n")
+}
+
+/// Rows as the table shows them, padded into aligned columns for a
+/// terminal. Numeric columns are right-aligned.
+pub fn to_table(columns: &[ProcessColumnKind], rows: &[ProcessInfo], units: Units) -> String {
+    let header: Vec<String> = columns
+        .iter()
+        .map(|kind| kind.label().to_string())
+        .collect();
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|kind| cell_text(*kind, row, units).replace(['\t', '\n', '\r'], " "))
+                .collect()
+        })
+        .collect();
+    let right_aligned: Vec<bool> = columns.iter().map(|kind| is_numeric(*kind)).collect();
+
+    text_table(&header, &cells, &right_aligned)
+}
+
+fn is_numeric(kind: ProcessColumnKind) -> bool {
+    !matches!(
+        kind,
+        ProcessColumnKind::Name
+            | ProcessColumnKind::IoPriority
+            | ProcessColumnKind::User
+            | ProcessColumnKind::Status
+            | ProcessColumnKind::StartTime
+            | ProcessColumnKind::CommandLine
+            | ProcessColumnKind::Executable
+    )
+}
+
+/// Pads `header` and `rows` into columns two spaces apart. Trailing
+/// spaces are trimmed so the last column can be as long as it likes.
+pub fn text_table(header: &[String], rows: &[Vec<String>], right_aligned: &[bool]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    std::iter::once(header)
+        .chain(rows.iter().map(Vec::as_slice))
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .zip(right_aligned.iter().chain(std::iter::repeat(&false)))
+                .map(|((cell, &width), &right)| {
+                    if right {
+                        format!("{cell:>width$}")
+                    } else {
+                        format!("{cell:<width$}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            line.trim_end().to_string() + "\n"
+        })
+        .collect()
+}
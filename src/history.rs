@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long samples are kept. Samples younger than `recent` are stored at
+/// full resolution; older ones are folded into `bucket_width` wide
+/// min/avg/max buckets until they are older than `total`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Retention {
+    pub recent: Duration,
+    pub total: Duration,
+    pub bucket_width: Duration,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            recent: Duration::from_secs(5 * 60),
+            total: Duration::from_secs(60 * 60),
+            bucket_width: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Downsampled summary of the samples that fell in one bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub start: Instant,
+    pub end: Instant,
+    pub min: f64,
+    pub max: f64,
+    sum: f64,
+    count: usize,
+}
+
+impl Bucket {
+    fn new(time: Instant, value: f64) -> Self {
+        Self {
+            start: time,
+            end: time,
+            min: value,
+            max: value,
+            sum: value,
+            count: 1,
+        }
+    }
+
+    fn add(&mut self, time: Instant, value: f64) {
+        self.end = time;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn avg(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Where the bucket sits on a time axis.
+    pub fn midpoint(&self) -> Instant {
+        self.start + (self.end - self.start) / 2
+    }
+}
+
+/// Bounded time series used for every chart. Memory use stays flat no
+/// matter how long the app runs: full-resolution samples are capped by
+/// `Retention::recent` and the downsampled tail by `Retention::total`.
+#[derive(Debug, Clone)]
+pub struct History {
+    retention: Retention,
+    /// Samples before `start` have been evicted. They're dropped in bulk
+    /// once they make up half the buffer, so the live samples always sit in
+    /// one slice without rotating a ring buffer on every push.
+    times: Vec<Instant>,
+    values: Vec<f64>,
+    start: usize,
+    buckets: VecDeque<Bucket>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(Retention::default())
+    }
+}
+
+impl History {
+    pub fn new(retention: Retention) -> Self {
+        Self {
+            retention,
+            times: Vec::new(),
+            values: Vec::new(),
+            start: 0,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        self.push_at(Instant::now(), value);
+    }
+
+    pub fn push_at(&mut self, time: Instant, value: f64) {
+        self.times.push(time);
+        self.values.push(value);
+        self.evict(time);
+    }
+
+    pub fn set_retention(&mut self, retention: Retention) {
+        self.retention = retention;
+        if let Some(&now) = self.times.last() {
+            self.evict(now);
+        }
+    }
+
+    pub fn retention(&self) -> Retention {
+        self.retention
+    }
+
+    /// Full-resolution samples, oldest first.
+    pub fn values(&self) -> &[f64] {
+        &self.values[self.start..]
+    }
+
+    /// When each of `values` was taken.
+    pub fn times(&self) -> &[Instant] {
+        &self.times[self.start..]
+    }
+
+    /// Downsampled samples that are older than the recent window, oldest
+    /// first.
+    pub fn buckets(&self) -> impl DoubleEndedIterator<Item = &Bucket> {
+        self.buckets.iter()
+    }
+
+    /// The whole window, oldest first: the average of each bucket at its
+    /// midpoint, then the full-resolution samples.
+    pub fn series(&self) -> impl Iterator<Item = (Instant, f64)> + '_ {
+        self.buckets
+            .iter()
+            .map(|bucket| (bucket.midpoint(), bucket.avg()))
+            .chain(
+                self.times()
+                    .iter()
+                    .copied()
+                    .zip(self.values().iter().copied()),
+            )
+    }
+
+    /// Largest value in the whole window, counting each bucket's maximum.
+    pub fn peak(&self) -> f64 {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.max)
+            .chain(self.values().iter().copied())
+            .fold(0.0, f64::max)
+    }
+
+    pub fn latest(&self) -> Option<f64> {
+        self.values().last().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values().is_empty()
+    }
+
+    fn evict(&mut self, now: Instant) {
+        while let Some(&time) = self.times.get(self.start) {
+            if now.duration_since(time) <= self.retention.recent {
+                break;
+            }
+
+            let value = self.values[self.start];
+            self.start += 1;
+
+            match self.buckets.back_mut() {
+                Some(bucket) if time.duration_since(bucket.start) < self.retention.bucket_width => {
+                    bucket.add(time, value)
+                }
+                _ => self.buckets.push_back(Bucket::new(time, value)),
+            }
+        }
+
+        if self.start > 0 && self.start * 2 >= self.values.len() {
+            self.times.drain(..self.start);
+            self.values.drain(..self.start);
+            self.start = 0;
+        }
+
+        while let Some(bucket) = self.buckets.front() {
+            if now.duration_since(bucket.end) <= self.retention.total {
+                break;
+            }
+            self.buckets.pop_front();
+        }
+    }
+}
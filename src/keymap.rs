@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use iced::keyboard::key::{Named, Physical};
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+/// Something a keyboard shortcut can do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Command {
+    MoveUp,
+    MoveDown,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    SelectAll,
+    EndTask,
+    FocusSearch,
+    ShowProcesses,
+    ShowPerformance,
+    ShowSettings,
+    TogglePause,
+    Refresh,
+    Cancel,
+}
+
+impl Command {
+    pub const ALL: [Command; 15] = [
+        Command::MoveUp,
+        Command::MoveDown,
+        Command::PageUp,
+        Command::PageDown,
+        Command::Home,
+        Command::End,
+        Command::SelectAll,
+        Command::EndTask,
+        Command::FocusSearch,
+        Command::ShowProcesses,
+        Command::ShowPerformance,
+        Command::ShowSettings,
+        Command::TogglePause,
+        Command::Refresh,
+        Command::Cancel,
+    ];
+
+    /// Movement commands also fire with Shift held, which extends the
+    /// selection instead of replacing it.
+    pub fn extends_selection(self) -> bool {
+        matches!(
+            self,
+            Command::MoveUp
+                | Command::MoveDown
+                | Command::PageUp
+                | Command::PageDown
+                | Command::Home
+                | Command::End
+        )
+    }
+
+    fn default_binding(self) -> &'static str {
+        match self {
+            Command::MoveUp => "Up",
+            Command::MoveDown => "Down",
+            Command::PageUp => "PageUp",
+            Command::PageDown => "PageDown",
+            Command::Home => "Home",
+            Command::End => "End",
+            Command::SelectAll => "Ctrl+A",
+            Command::EndTask => "Delete",
+            Command::FocusSearch => "Ctrl+F",
+            Command::ShowProcesses => "Ctrl+1",
+            Command::ShowPerformance => "Ctrl+2",
+            Command::ShowSettings => "Ctrl+3",
+            Command::TogglePause => "Ctrl+P",
+            Command::Refresh => "F5",
+            Command::Cancel => "Escape",
+        }
+    }
+}
+
+impl std::fmt::Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Command::MoveUp => "Previous process",
+            Command::MoveDown => "Next process",
+            Command::PageUp => "Page up",
+            Command::PageDown => "Page down",
+            Command::Home => "First process",
+            Command::End => "Last process",
+            Command::SelectAll => "Select all",
+            Command::EndTask => "End task",
+            Command::FocusSearch => "Search",
+            Command::ShowProcesses => "Processes view",
+            Command::ShowPerformance => "Performance view",
+            Command::ShowSettings => "Settings view",
+            Command::TogglePause => "Pause/resume updates",
+            Command::Refresh => "Refresh now",
+            Command::Cancel => "Close dialog",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// Names used for non-character keys in bindings.
+const NAMED_KEYS: [(&str, Named); 26] = [
+    ("Up", Named::ArrowUp),
+    ("Down", Named::ArrowDown),
+    ("Left", Named::ArrowLeft),
+    ("Right", Named::ArrowRight),
+    ("PageUp", Named::PageUp),
+    ("PageDown", Named::PageDown),
+    ("Home", Named::Home),
+    ("End", Named::End),
+    ("Delete", Named::Delete),
+    ("Insert", Named::Insert),
+    ("Backspace", Named::Backspace),
+    ("Enter", Named::Enter),
+    ("Escape", Named::Escape),
+    ("Tab", Named::Tab),
+    ("F1", Named::F1),
+    ("F2", Named::F2),
+    ("F3", Named::F3),
+    ("F4", Named::F4),
+    ("F5", Named::F5),
+    ("F6", Named::F6),
+    ("F7", Named::F7),
+    ("F8", Named::F8),
+    ("F9", Named::F9),
+    ("F10", Named::F10),
+    ("F11", Named::F11),
+    ("F12", Named::F12),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKey {
+    Named(Named),
+    /// A character key, stored lowercase.
+    Character(char),
+}
+
+/// A key plus the modifiers that must be held, written like `Ctrl+Shift+F`.
+/// `Ctrl` means Cmd on macOS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyBinding {
+    pub key: BindingKey,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub fn matches(&self, key: &Key, physical_key: Physical, modifiers: Modifiers) -> bool {
+        if modifiers.command() != self.ctrl
+            || modifiers.shift() != self.shift
+            || modifiers.alt() != self.alt
+        {
+            return false;
+        }
+
+        match self.key {
+            BindingKey::Named(named) => *key == Key::Named(named),
+            BindingKey::Character(c) => key
+                .to_latin(physical_key)
+                .is_some_and(|pressed| pressed.to_ascii_lowercase() == c),
+        }
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key = parts.pop().filter(|key| !key.is_empty());
+        let Some(key) = key else {
+            return Err(format!("`{s}` has no key"));
+        };
+
+        let mut binding = KeyBinding {
+            key: parse_key(key)?,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        };
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" => binding.ctrl = true,
+                "shift" => binding.shift = true,
+                "alt" => binding.alt = true,
+                _ => return Err(format!("unknown modifier `{modifier}`")),
+            }
+        }
+        Ok(binding)
+    }
+}
+
+fn parse_key(key: &str) -> Result<BindingKey, String> {
+    if let Some((_, named)) = NAMED_KEYS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+    {
+        return Ok(BindingKey::Named(*named));
+    }
+
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_graphic() => {
+            Ok(BindingKey::Character(c.to_ascii_lowercase()))
+        }
+        _ => Err(format!("unknown key `{key}`")),
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(binding: KeyBinding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        match self.key {
+            BindingKey::Named(named) => {
+                let name = NAMED_KEYS
+                    .iter()
+                    .find(|(_, key)| *key == named)
+                    .map_or("?", |(name, _)| name);
+                write!(f, "{name}")
+            }
+            BindingKey::Character(c) => write!(f, "{}", c.to_ascii_uppercase()),
+        }
+    }
+}
+
+/// Which key triggers which command. Stored in the settings as a
+/// `[keymap]` table of command names to bindings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Keymap(BTreeMap<Command, KeyBinding>);
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self(
+            Command::ALL
+                .into_iter()
+                .map(|command| {
+                    let binding = command
+                        .default_binding()
+                        .parse()
+                        .expect("default bindings are valid");
+                    (command, binding)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Keymap {
+    pub fn binding(&self, command: Command) -> Option<KeyBinding> {
+        self.0.get(&command).copied()
+    }
+
+    pub fn set(&mut self, command: Command, binding: KeyBinding) {
+        self.0.insert(command, binding);
+    }
+
+    /// The command bound to a key press, if any. Movement commands also
+    /// match with Shift added.
+    pub fn command(
+        &self,
+        key: &Key,
+        physical_key: Physical,
+        modifiers: Modifiers,
+    ) -> Option<Command> {
+        let unshifted = modifiers.difference(Modifiers::SHIFT);
+        self.0
+            .iter()
+            .find(|(command, binding)| {
+                binding.matches(key, physical_key, modifiers)
+                    || (command.extends_selection()
+                        && !binding.shift
+                        && binding.matches(key, physical_key, unshifted))
+            })
+            .map(|(command, _)| *command)
+    }
+
+    /// Pairs of commands that share a binding; only the first of each pair
+    /// can ever fire.
+    pub fn conflicts(&self) -> Vec<(Command, Command)> {
+        let bindings: Vec<(&Command, &KeyBinding)> = self.0.iter().collect();
+        let mut conflicts = Vec::new();
+        for (i, (first, binding)) in bindings.iter().enumerate() {
+            for (second, other) in &bindings[i + 1..] {
+                if binding == other {
+                    conflicts.push((**first, **second));
+                }
+            }
+        }
+        conflicts
+    }
+}
@@ -1,5 +1,23 @@
+pub mod affinity;
+pub mod app_groups;
 pub mod charts;
+pub mod cli;
+pub mod cpus;
+pub mod disks;
+pub mod export;
+pub mod history;
+pub mod keymap;
+pub mod networks;
+pub mod priority;
+pub mod process_actions;
+pub mod process_details;
 pub mod process_table;
+pub mod process_tree;
+pub mod query;
+pub mod sampler;
+pub mod session;
+pub mod settings;
 pub mod theme;
+pub mod tui;
 pub mod ui;
 pub mod utilities;
@@ -1,3 +1,7 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use task_manager::cli::{self, Cli};
 use task_manager::theme::Theme;
 use task_manager::ui::*;
 
@@ -5,10 +9,21 @@ fn theme(state: &State) -> Theme {
     state.theme_selected.unwrap()
 }
 
-fn main() -> iced::Result {
-    iced::application(State::default, update, view)
+fn main() -> ExitCode {
+    if let Some(command) = Cli::parse().command {
+        return cli::run(command);
+    }
+
+    let result = iced::application(State::default, update, view)
         .title("Task Manager")
         .subscription(subscription)
         .theme(theme)
-        .run()
+        .run();
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("task-manager: {err}");
+            ExitCode::FAILURE
+        }
+    }
 }
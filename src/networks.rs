@@ -0,0 +1,118 @@
+use std::time::Instant;
+
+use sysinfo::{NetworkData, Networks};
+
+use crate::history::{History, Retention};
+use crate::utilities::per_second;
+
+/// Receive/transmit state for a single network interface, keyed by
+/// interface name.
+pub struct NetworkState {
+    pub name: String,
+    pub mac_address: String,
+    pub ip_addresses: Vec<String>,
+    pub received: f64,
+    pub transmitted: f64,
+    pub rx_history: History,
+    pub tx_history: History,
+    pub total_received: u64,
+    pub total_transmitted: u64,
+    pub packets_received: u64,
+    pub packets_transmitted: u64,
+    pub errors_received: u64,
+    pub errors_transmitted: u64,
+    /// Time and cumulative received/transmitted counters of the previous
+    /// sample.
+    prev: Option<(Instant, u64, u64)>,
+}
+
+impl NetworkState {
+    pub fn new(name: String, retention: Retention) -> Self {
+        Self {
+            name,
+            mac_address: String::new(),
+            ip_addresses: Vec::new(),
+            received: 0.0,
+            transmitted: 0.0,
+            rx_history: History::new(retention),
+            tx_history: History::new(retention),
+            total_received: 0,
+            total_transmitted: 0,
+            packets_received: 0,
+            packets_transmitted: 0,
+            errors_received: 0,
+            errors_transmitted: 0,
+            prev: None,
+        }
+    }
+
+    pub fn record(&mut self, total_received: u64, total_transmitted: u64) {
+        self.record_at(Instant::now(), total_received, total_transmitted);
+    }
+
+    /// Records a new sample from the cumulative byte counters and stores
+    /// the receive/transmit throughput in KB/s since the previous sample.
+    /// Like disks, the first sample only primes the counters.
+    pub fn record_at(&mut self, time: Instant, total_received: u64, total_transmitted: u64) {
+        let (received, transmitted) = match self.prev {
+            Some((prev_time, prev_received, prev_transmitted)) => {
+                let elapsed = time.duration_since(prev_time);
+                (
+                    per_second(total_received.saturating_sub(prev_received), elapsed) / 1_000.0,
+                    per_second(total_transmitted.saturating_sub(prev_transmitted), elapsed)
+                        / 1_000.0,
+                )
+            }
+            None => (0.0, 0.0),
+        };
+
+        self.prev = Some((time, total_received, total_transmitted));
+        self.total_received = total_received;
+        self.total_transmitted = total_transmitted;
+        self.push_rates_at(time, received, transmitted);
+    }
+
+    /// Records receive/transmit rates in KB/s that were worked out
+    /// elsewhere, as when replaying a recorded session.
+    pub fn push_rates_at(&mut self, time: Instant, received: f64, transmitted: f64) {
+        self.received = received;
+        self.transmitted = transmitted;
+        self.rx_history.push_at(time, received);
+        self.tx_history.push_at(time, transmitted);
+    }
+
+    fn update_details(&mut self, data: &NetworkData) {
+        self.mac_address = data.mac_address().to_string();
+        self.ip_addresses = data.ip_networks().iter().map(|ip| ip.to_string()).collect();
+        self.packets_received = data.total_packets_received();
+        self.packets_transmitted = data.total_packets_transmitted();
+        self.errors_received = data.total_errors_on_received();
+        self.errors_transmitted = data.total_errors_on_transmitted();
+    }
+}
+
+/// Brings `states` in line with the current interface list, keeping the
+/// entries sorted by name so the tab panel order doesn't shuffle.
+pub fn sync_networks(states: &mut Vec<NetworkState>, networks: &Networks, retention: Retention) {
+    states.retain(|s| networks.list().contains_key(&s.name));
+
+    for (name, data) in networks.list() {
+        let index = match states.iter().position(|s| &s.name == name) {
+            Some(index) => index,
+            None => {
+                states.push(NetworkState::new(name.clone(), retention));
+                states.len() - 1
+            }
+        };
+
+        let state = &mut states[index];
+        state.update_details(data);
+        state.record(data.total_received(), data.total_transmitted());
+    }
+
+    states.sort_by(|a, b| a.name.cmp(&b.name));
+}
+
+pub fn find_network<'a>(states: &'a [NetworkState], name: &str) -> Option<&'a NetworkState> {
+    states.iter().find(|s| s.name == name)
+}
@@ -0,0 +1,248 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+pub const MIN_NICE: i32 = -20;
+pub const MAX_NICE: i32 = 19;
+/// I/O priority levels run from 0 (highest) to 7 (lowest).
+pub const MAX_IO_LEVEL: u8 = 7;
+
+/// Windows-style priority classes, each mapped onto a nice value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityClass {
+    Low,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+}
+
+impl PriorityClass {
+    pub const ALL: [PriorityClass; 5] = [
+        PriorityClass::Low,
+        PriorityClass::BelowNormal,
+        PriorityClass::Normal,
+        PriorityClass::AboveNormal,
+        PriorityClass::High,
+    ];
+
+    pub fn nice(self) -> i32 {
+        match self {
+            PriorityClass::Low => 19,
+            PriorityClass::BelowNormal => 10,
+            PriorityClass::Normal => 0,
+            PriorityClass::AboveNormal => -5,
+            PriorityClass::High => -10,
+        }
+    }
+
+    /// The class whose nice value is closest to `nice`.
+    pub fn from_nice(nice: i32) -> Self {
+        match nice {
+            15.. => PriorityClass::Low,
+            5..=14 => PriorityClass::BelowNormal,
+            -2..=4 => PriorityClass::Normal,
+            -7..=-3 => PriorityClass::AboveNormal,
+            _ => PriorityClass::High,
+        }
+    }
+}
+
+impl std::fmt::Display for PriorityClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriorityClass::Low => write!(f, "Low"),
+            PriorityClass::BelowNormal => write!(f, "Below normal"),
+            PriorityClass::Normal => write!(f, "Normal"),
+            PriorityClass::AboveNormal => write!(f, "Above normal"),
+            PriorityClass::High => write!(f, "High"),
+        }
+    }
+}
+
+/// Linux I/O scheduling class, as used by `ionice`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum IoClass {
+    RealTime,
+    BestEffort,
+    Idle,
+    /// No class set; the kernel derives a best-effort level from the nice
+    /// value.
+    None,
+}
+
+impl IoClass {
+    /// Classes that can be set from the priority dialog.
+    pub const ALL: [IoClass; 3] = [IoClass::RealTime, IoClass::BestEffort, IoClass::Idle];
+
+    fn from_raw(class: u32) -> Self {
+        match class {
+            1 => IoClass::RealTime,
+            2 => IoClass::BestEffort,
+            3 => IoClass::Idle,
+            _ => IoClass::None,
+        }
+    }
+
+    fn raw(self) -> u32 {
+        match self {
+            IoClass::None => 0,
+            IoClass::RealTime => 1,
+            IoClass::BestEffort => 2,
+            IoClass::Idle => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for IoClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IoClass::RealTime => write!(f, "Realtime"),
+            IoClass::BestEffort => write!(f, "Best effort"),
+            IoClass::Idle => write!(f, "Idle"),
+            IoClass::None => write!(f, "None"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct IoPriority {
+    pub class: IoClass,
+    pub level: u8,
+}
+
+impl IoPriority {
+    const CLASS_SHIFT: u32 = 13;
+
+    fn from_raw(raw: u32) -> Self {
+        Self {
+            class: IoClass::from_raw(raw >> Self::CLASS_SHIFT),
+            level: (raw & ((1 << Self::CLASS_SHIFT) - 1)).min(MAX_IO_LEVEL as u32) as u8,
+        }
+    }
+
+    fn raw(self) -> u32 {
+        (self.class.raw() << Self::CLASS_SHIFT) | self.level.min(MAX_IO_LEVEL) as u32
+    }
+
+    /// The priority the kernel actually applies: processes without an I/O
+    /// class get a best-effort level derived from their nice value.
+    pub fn effective(self, nice: i32) -> Self {
+        match self.class {
+            IoClass::None => Self {
+                class: IoClass::BestEffort,
+                level: ((nice.clamp(MIN_NICE, MAX_NICE) + 20) / 5) as u8,
+            },
+            _ => self,
+        }
+    }
+}
+
+/// Formatted like `ionice` output: `be/4`, `rt/0`, `idle`, `none`.
+impl std::fmt::Display for IoPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.class {
+            IoClass::RealTime => write!(f, "rt/{}", self.level),
+            IoClass::BestEffort => write!(f, "be/{}", self.level),
+            IoClass::Idle => write!(f, "idle"),
+            IoClass::None => write!(f, "none"),
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn nice(pid: u32) -> io::Result<i32> {
+    // getpriority can legitimately return -1, so errno has to be cleared
+    // to tell that apart from a failure.
+    clear_errno();
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t) };
+    if nice == -1 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error().is_some_and(|code| code != 0) {
+            return Err(err);
+        }
+    }
+    Ok(nice)
+}
+
+#[cfg(not(unix))]
+pub fn nice(_pid: u32) -> io::Result<i32> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Sets the nice value of `pid`. Lowering it below the current value
+/// usually needs root.
+#[cfg(unix)]
+pub fn set_nice(pid: u32, nice: i32) -> io::Result<()> {
+    let nice = nice.clamp(MIN_NICE, MAX_NICE);
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(unix))]
+pub fn set_nice(_pid: u32, _nice: i32) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+
+#[cfg(target_os = "linux")]
+pub fn io_priority(pid: u32) -> io::Result<IoPriority> {
+    let raw = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_get,
+            IOPRIO_WHO_PROCESS,
+            pid as libc::c_long,
+        )
+    };
+    if raw < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(IoPriority::from_raw(raw as u32))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn io_priority(_pid: u32) -> io::Result<IoPriority> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Sets the I/O priority of `pid` via `ioprio_set`. The realtime class
+/// needs root.
+#[cfg(target_os = "linux")]
+pub fn set_io_priority(pid: u32, priority: IoPriority) -> io::Result<()> {
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            pid as libc::c_long,
+            priority.raw() as libc::c_long,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_io_priority(_pid: u32, _priority: IoPriority) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(unix)]
+fn clear_errno() {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    unsafe {
+        *libc::__errno_location() = 0;
+    }
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    unsafe {
+        *libc::__error() = 0;
+    }
+}
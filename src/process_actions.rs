@@ -0,0 +1,624 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::time::{Duration, Instant};
+
+use sysinfo::{
+    Pid, Process, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System, UpdateKind,
+    Users,
+};
+
+/// How long a terminated process gets to exit before it is killed.
+pub const ESCALATION_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long killed processes get to disappear before they are reported as
+/// survivors.
+pub const KILL_GRACE: Duration = Duration::from_secs(1);
+
+/// Something that can be done to a process from the action menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    /// SIGTERM, escalated to SIGKILL if the process is still running after
+    /// the escalation timeout.
+    Terminate,
+    /// Terminate the process and all of its descendants, children first,
+    /// killing whatever is left after the escalation timeout.
+    EndTree,
+    Kill,
+    Suspend,
+    Resume,
+    Hangup,
+    Interrupt,
+    /// Any other signal supported by the platform.
+    Signal(Signal),
+}
+
+impl ProcessAction {
+    pub const MENU: [ProcessAction; 7] = [
+        ProcessAction::Terminate,
+        ProcessAction::EndTree,
+        ProcessAction::Kill,
+        ProcessAction::Suspend,
+        ProcessAction::Resume,
+        ProcessAction::Hangup,
+        ProcessAction::Interrupt,
+    ];
+
+    pub fn signal(self) -> Signal {
+        match self {
+            ProcessAction::Terminate | ProcessAction::EndTree => Signal::Term,
+            ProcessAction::Kill => Signal::Kill,
+            ProcessAction::Suspend => Signal::Stop,
+            ProcessAction::Resume => Signal::Continue,
+            ProcessAction::Hangup => Signal::Hangup,
+            ProcessAction::Interrupt => Signal::Interrupt,
+            ProcessAction::Signal(signal) => signal,
+        }
+    }
+
+    /// Whether the action has to be confirmed first. Everything except
+    /// suspending and resuming can end the process.
+    pub fn needs_confirmation(self) -> bool {
+        !matches!(self, ProcessAction::Suspend | ProcessAction::Resume)
+    }
+}
+
+impl std::fmt::Display for ProcessAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessAction::Terminate => write!(f, "End task (SIGTERM)"),
+            ProcessAction::EndTree => write!(f, "End process tree"),
+            ProcessAction::Kill => write!(f, "Kill (SIGKILL)"),
+            ProcessAction::Suspend => write!(f, "Suspend (SIGSTOP)"),
+            ProcessAction::Resume => write!(f, "Resume (SIGCONT)"),
+            ProcessAction::Hangup => write!(f, "Hang up (SIGHUP)"),
+            ProcessAction::Interrupt => write!(f, "Interrupt (SIGINT)"),
+            ProcessAction::Signal(signal) => write!(f, "Signal {signal}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionOutcome {
+    Sent,
+    PermissionDenied,
+    AlreadyExited,
+    /// The platform has no equivalent of the requested signal.
+    Unsupported,
+    Failed(String),
+    /// PIDs of a process tree that were still running after being killed.
+    Survived(Vec<u32>),
+    /// The PID now belongs to a different process than the one the action
+    /// was confirmed for.
+    PidReused,
+    /// Refused because the process is protected.
+    Protected(String),
+}
+
+impl ActionOutcome {
+    pub fn is_success(&self) -> bool {
+        *self == ActionOutcome::Sent
+    }
+}
+
+impl From<io::Error> for ActionOutcome {
+    fn from(err: io::Error) -> Self {
+        #[cfg(unix)]
+        if err.raw_os_error() == Some(libc::ESRCH) {
+            return ActionOutcome::AlreadyExited;
+        }
+
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => ActionOutcome::PermissionDenied,
+            io::ErrorKind::Unsupported => ActionOutcome::Unsupported,
+            _ => ActionOutcome::Failed(err.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for ActionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionOutcome::Sent => write!(f, "done"),
+            ActionOutcome::PermissionDenied => write!(f, "permission denied"),
+            ActionOutcome::AlreadyExited => write!(f, "process already exited"),
+            ActionOutcome::Unsupported => write!(f, "not supported on this platform"),
+            ActionOutcome::Failed(err) => write!(f, "failed: {err}"),
+            ActionOutcome::Survived(pids) => {
+                let pids: Vec<String> = pids.iter().map(u32::to_string).collect();
+                write!(f, "still running: {}", pids.join(", "))
+            }
+            ActionOutcome::PidReused => write!(f, "PID now belongs to a different process"),
+            ActionOutcome::Protected(reason) => write!(f, "refused, {reason}"),
+        }
+    }
+}
+
+/// Result of one action on one process, shown under the process table's
+/// top bar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionReport {
+    pub pid: u32,
+    pub name: String,
+    pub action: ProcessAction,
+    pub outcome: ActionOutcome,
+}
+
+impl std::fmt::Display for ActionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} — {} ({}): {}",
+            self.action, self.name, self.pid, self.outcome
+        )
+    }
+}
+
+/// Results of one action applied to one or more processes, such as every
+/// process of an app or a multi-row selection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkReport {
+    /// What was done, e.g. `End task (SIGTERM)` or `Set priority`.
+    pub action: String,
+    /// PID, name and outcome for each process.
+    pub results: Vec<(u32, String, ActionOutcome)>,
+}
+
+impl BulkReport {
+    pub fn new(action: impl ToString) -> Self {
+        Self {
+            action: action.to_string(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn from_reports(action: ProcessAction, reports: Vec<ActionReport>) -> Self {
+        Self {
+            action: action.to_string(),
+            results: reports
+                .into_iter()
+                .map(|report| (report.pid, report.name, report.outcome))
+                .collect(),
+        }
+    }
+
+    /// Everything one `poll` did, which can mix kills of single processes
+    /// with process trees that were finished off or survived.
+    pub fn from_escalations(reports: Vec<ActionReport>) -> Self {
+        let mut actions: Vec<ProcessAction> = Vec::new();
+        for report in &reports {
+            if !actions.contains(&report.action) {
+                actions.push(report.action);
+            }
+        }
+        let action: Vec<String> = actions.iter().map(ToString::to_string).collect();
+        Self {
+            action: action.join(", "),
+            results: reports
+                .into_iter()
+                .map(|report| (report.pid, report.name, report.outcome))
+                .collect(),
+        }
+    }
+
+    pub fn push(&mut self, pid: u32, name: impl ToString, outcome: ActionOutcome) {
+        self.results.push((pid, name.to_string(), outcome));
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.results
+            .iter()
+            .all(|(_, _, outcome)| outcome.is_success())
+    }
+}
+
+/// A single result reads like an [`ActionReport`]. Several are summed up
+/// with every failure listed, e.g.
+/// `End task (SIGTERM) — 3 processes: 2 done; chrome (42): permission denied`.
+impl std::fmt::Display for BulkReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let [(pid, name, outcome)] = self.results.as_slice() {
+            return write!(f, "{} — {name} ({pid}): {outcome}", self.action);
+        }
+
+        let failed: Vec<_> = self
+            .results
+            .iter()
+            .filter(|(_, _, outcome)| !outcome.is_success())
+            .collect();
+        write!(
+            f,
+            "{} — {} processes: {} done",
+            self.action,
+            self.results.len(),
+            self.results.len() - failed.len()
+        )?;
+        for (pid, name, outcome) in failed {
+            write!(f, "; {name} ({pid}): {outcome}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The process an action was requested for, as it was when the
+/// confirmation dialog opened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessTarget {
+    pub pid: u32,
+    pub start_time: u64,
+    pub name: String,
+    pub user: String,
+    pub command_line: String,
+}
+
+impl ProcessTarget {
+    pub fn capture(sys: &mut System, users: &Users, pid: u32) -> Option<Self> {
+        let sys_pid = Pid::from_u32(pid);
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[sys_pid]),
+            true,
+            ProcessRefreshKind::nothing()
+                .with_user(UpdateKind::Always)
+                .with_cmd(UpdateKind::Always),
+        );
+        let process = sys.process(sys_pid)?;
+
+        let user = process
+            .user_id()
+            .and_then(|uid| users.get_user_by_id(uid))
+            .map(|user| user.name().to_string())
+            .unwrap_or_default();
+        let command_line = process
+            .cmd()
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Some(Self {
+            pid,
+            start_time: process.start_time(),
+            name: process.name().to_string_lossy().to_string(),
+            user,
+            command_line,
+        })
+    }
+
+    /// Checks that the PID still refers to this process and hasn't been
+    /// reused since it was captured.
+    pub fn check(&self, sys: &mut System) -> Result<(), ActionOutcome> {
+        let sys_pid = Pid::from_u32(self.pid);
+        refresh(sys, ProcessesToUpdate::Some(&[sys_pid]));
+
+        match sys.process(sys_pid) {
+            None => Err(ActionOutcome::AlreadyExited),
+            Some(process) if process.start_time() != self.start_time => {
+                Err(ActionOutcome::PidReused)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// Why `action` on `pid` needs extra care, if it does. PID 1 and this
+/// process are always protected, `names` adds more by process name. Ending
+/// a tree checks every process in it.
+pub fn protection(
+    sys: &System,
+    pid: u32,
+    action: ProcessAction,
+    names: &[String],
+) -> Option<String> {
+    let pids = match action {
+        ProcessAction::EndTree => descendants(sys, pid),
+        _ => vec![pid],
+    };
+
+    pids.into_iter().find_map(|pid| {
+        if pid == 1 {
+            return Some("PID 1 is the init process".to_string());
+        }
+        if pid == std::process::id() {
+            return Some("this is the task manager itself".to_string());
+        }
+        let name = sys.process(Pid::from_u32(pid))?.name().to_string_lossy();
+        names
+            .iter()
+            .any(|protected| *protected == name)
+            .then(|| format!("{name} ({pid}) is a protected process"))
+    })
+}
+
+/// A terminated process that gets killed if it is still around at
+/// `deadline`. The start time guards against the PID being reused.
+#[derive(Debug, Clone, PartialEq)]
+struct PendingKill {
+    pid: u32,
+    start_time: u64,
+    deadline: Instant,
+}
+
+/// A process tree being ended. `members` are listed children first so
+/// parents can't respawn anything that was already signalled.
+#[derive(Debug, Clone, PartialEq)]
+struct PendingTree {
+    root: u32,
+    name: String,
+    members: Vec<(u32, u64)>,
+    deadline: Instant,
+    /// Set once SIGKILL has been sent to the members that ignored SIGTERM.
+    forced: bool,
+}
+
+/// Sends signals and keeps track of terminations that may need to be
+/// escalated.
+#[derive(Debug, Clone)]
+pub struct ProcessControl {
+    timeout: Duration,
+    pending: Vec<PendingKill>,
+    trees: Vec<PendingTree>,
+}
+
+impl Default for ProcessControl {
+    fn default() -> Self {
+        Self::new(ESCALATION_TIMEOUT)
+    }
+}
+
+impl ProcessControl {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            pending: Vec::new(),
+            trees: Vec::new(),
+        }
+    }
+
+    /// Whether any terminated process still has to be checked on.
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty() || !self.trees.is_empty()
+    }
+
+    /// Like `send`, but only if `target.pid` still refers to the process
+    /// that was confirmed.
+    pub fn send_checked(
+        &mut self,
+        sys: &mut System,
+        target: &ProcessTarget,
+        action: ProcessAction,
+    ) -> ActionReport {
+        let Err(outcome) = target.check(sys) else {
+            return self.send(sys, target.pid, action);
+        };
+
+        ActionReport {
+            pid: target.pid,
+            name: target.name.clone(),
+            action,
+            outcome,
+        }
+    }
+
+    pub fn send(&mut self, sys: &mut System, pid: u32, action: ProcessAction) -> ActionReport {
+        self.send_at(sys, pid, action, Instant::now())
+    }
+
+    pub fn send_at(
+        &mut self,
+        sys: &mut System,
+        pid: u32,
+        action: ProcessAction,
+        now: Instant,
+    ) -> ActionReport {
+        if action == ProcessAction::EndTree {
+            return self.end_tree_at(sys, pid, now);
+        }
+
+        let sys_pid = Pid::from_u32(pid);
+        refresh(sys, ProcessesToUpdate::Some(&[sys_pid]));
+
+        let Some(process) = sys.process(sys_pid) else {
+            return ActionReport {
+                pid,
+                name: String::new(),
+                action,
+                outcome: ActionOutcome::AlreadyExited,
+            };
+        };
+        let name = process.name().to_string_lossy().to_string();
+        let start_time = process.start_time();
+
+        let mut outcome = match signal(process, action.signal()) {
+            // Platforms without SIGTERM (Windows) only know how to kill.
+            ActionOutcome::Unsupported if action == ProcessAction::Terminate => {
+                return self.send_at(sys, pid, ProcessAction::Kill, now);
+            }
+            outcome => outcome,
+        };
+
+        if let ActionOutcome::Failed(_) = outcome {
+            refresh(sys, ProcessesToUpdate::Some(&[sys_pid]));
+            if sys.process(sys_pid).is_none() {
+                outcome = ActionOutcome::AlreadyExited;
+            }
+        }
+
+        if action == ProcessAction::Terminate && outcome.is_success() {
+            self.pending.retain(|p| p.pid != pid);
+            self.pending.push(PendingKill {
+                pid,
+                start_time,
+                deadline: now + self.timeout,
+            });
+        }
+
+        ActionReport {
+            pid,
+            name,
+            action,
+            outcome,
+        }
+    }
+
+    /// Sends SIGTERM to `pid` and every descendant, children first. The
+    /// report covers the root; survivors are reported by `poll` once the
+    /// tree has been killed.
+    pub fn end_tree_at(&mut self, sys: &mut System, pid: u32, now: Instant) -> ActionReport {
+        refresh(sys, ProcessesToUpdate::All);
+
+        let Some(root) = sys.process(Pid::from_u32(pid)) else {
+            return ActionReport {
+                pid,
+                name: String::new(),
+                action: ProcessAction::EndTree,
+                outcome: ActionOutcome::AlreadyExited,
+            };
+        };
+        let name = root.name().to_string_lossy().to_string();
+
+        let mut members = Vec::new();
+        let mut outcome = ActionOutcome::AlreadyExited;
+        for member in descendants(sys, pid) {
+            let Some(process) = sys.process(Pid::from_u32(member)) else {
+                continue;
+            };
+            let sent = match signal(process, Signal::Term) {
+                ActionOutcome::Unsupported => signal(process, Signal::Kill),
+                sent => sent,
+            };
+            members.push((member, process.start_time()));
+            if member == pid {
+                outcome = sent;
+            }
+        }
+
+        self.trees.retain(|t| t.root != pid);
+        self.trees.push(PendingTree {
+            root: pid,
+            name: name.clone(),
+            members,
+            deadline: now + self.timeout,
+            forced: false,
+        });
+
+        ActionReport {
+            pid,
+            name,
+            action: ProcessAction::EndTree,
+            outcome,
+        }
+    }
+
+    pub fn poll(&mut self, sys: &mut System) -> Vec<ActionReport> {
+        self.poll_at(sys, Instant::now())
+    }
+
+    /// Kills every terminated process whose deadline has passed and which
+    /// is still running. Returns a report for each kill.
+    pub fn poll_at(&mut self, sys: &mut System, now: Instant) -> Vec<ActionReport> {
+        let (due, waiting): (Vec<_>, Vec<_>) =
+            self.pending.drain(..).partition(|p| p.deadline <= now);
+        self.pending = waiting;
+
+        let mut reports = Vec::new();
+        for pending in due {
+            let sys_pid = Pid::from_u32(pending.pid);
+            refresh(sys, ProcessesToUpdate::Some(&[sys_pid]));
+            if is_running(sys, pending.pid, pending.start_time) {
+                reports.push(self.send_at(sys, pending.pid, ProcessAction::Kill, now));
+            }
+        }
+
+        let (due, waiting): (Vec<_>, Vec<_>) =
+            self.trees.drain(..).partition(|t| t.deadline <= now);
+        self.trees = waiting;
+
+        if !due.is_empty() {
+            refresh(sys, ProcessesToUpdate::All);
+        }
+        for mut tree in due {
+            tree.members
+                .retain(|&(pid, start_time)| is_running(sys, pid, start_time));
+
+            if tree.members.is_empty() || tree.forced {
+                let outcome = if tree.members.is_empty() {
+                    ActionOutcome::Sent
+                } else {
+                    ActionOutcome::Survived(tree.members.iter().map(|&(pid, _)| pid).collect())
+                };
+                reports.push(ActionReport {
+                    pid: tree.root,
+                    name: tree.name,
+                    action: ProcessAction::EndTree,
+                    outcome,
+                });
+                continue;
+            }
+
+            for &(pid, _) in &tree.members {
+                if let Some(process) = sys.process(Pid::from_u32(pid)) {
+                    signal(process, Signal::Kill);
+                }
+            }
+            tree.forced = true;
+            tree.deadline = now + KILL_GRACE;
+            self.trees.push(tree);
+        }
+
+        reports
+    }
+}
+
+/// `pid` and all of its descendants in post-order, so every child comes
+/// before its parent. Threads are skipped since signalling one signals
+/// the whole process.
+pub fn descendants(sys: &System, pid: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (child, process) in sys.processes() {
+        if process.thread_kind().is_some() {
+            continue;
+        }
+        if let Some(parent) = process.parent() {
+            children
+                .entry(parent.as_u32())
+                .or_default()
+                .push(child.as_u32());
+        }
+    }
+
+    let mut order = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![(pid, false)];
+    while let Some((current, visited)) = stack.pop() {
+        if visited {
+            order.push(current);
+            continue;
+        }
+        if !seen.insert(current) {
+            continue;
+        }
+
+        stack.push((current, true));
+        for &child in children.get(&current).into_iter().flatten() {
+            stack.push((child, false));
+        }
+    }
+    order
+}
+
+/// Refreshes only what sysinfo always updates, such as the name, status
+/// and start time, so the sampler's CPU and disk readings stay intact.
+fn refresh(sys: &mut System, processes: ProcessesToUpdate) {
+    sys.refresh_processes_specifics(processes, true, ProcessRefreshKind::nothing());
+}
+
+fn is_running(sys: &System, pid: u32, start_time: u64) -> bool {
+    sys.process(Pid::from_u32(pid))
+        .is_some_and(|p| p.start_time() == start_time && p.status() != ProcessStatus::Zombie)
+}
+
+fn signal(process: &Process, signal: Signal) -> ActionOutcome {
+    match process.kill_with(signal) {
+        Some(true) => ActionOutcome::Sent,
+        None => ActionOutcome::Unsupported,
+        Some(false) => io::Error::last_os_error().into(),
+    }
+}
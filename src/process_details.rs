@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+
+use sysinfo::{Groups, Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind, Users};
+
+/// Everything the details panel shows for one process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessDetails {
+    pub pid: u32,
+    pub name: String,
+    pub command_line: String,
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    pub environment: Vec<String>,
+    pub user: Option<String>,
+    pub group: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub start_time: u64,
+    /// Seconds the process has been running.
+    pub run_time: u64,
+    pub status: String,
+    pub parent: Option<(u32, String)>,
+    pub threads: Option<usize>,
+    pub virtual_memory: u64,
+    pub resident_memory: u64,
+    pub total_read_bytes: u64,
+    pub total_written_bytes: u64,
+}
+
+impl ProcessDetails {
+    /// Refreshes what the panel shows about `pid` and collects it. Returns
+    /// `None` once the process has exited.
+    ///
+    /// CPU and disk usage are left alone: refreshing them mid-interval
+    /// would make the selected row read too low on the next sample.
+    pub fn collect(sys: &mut System, users: &Users, groups: &Groups, pid: u32) -> Option<Self> {
+        let sys_pid = Pid::from_u32(pid);
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[sys_pid]),
+            true,
+            ProcessRefreshKind::nothing()
+                .with_memory()
+                .with_user(UpdateKind::Always)
+                .with_cmd(UpdateKind::Always)
+                .with_cwd(UpdateKind::Always)
+                .with_environ(UpdateKind::Always)
+                .with_exe(UpdateKind::Always)
+                .with_tasks(),
+        );
+        let process = sys.process(sys_pid)?;
+
+        let user = process
+            .user_id()
+            .and_then(|uid| users.get_user_by_id(uid))
+            .map(|user| user.name().to_string());
+        let group = process.group_id().and_then(|gid| {
+            groups
+                .list()
+                .iter()
+                .find(|group| *group.id() == gid)
+                .map(|group| group.name().to_string())
+        });
+        let parent = process.parent().map(|parent| {
+            let name = sys
+                .process(parent)
+                .map(|p| p.name().to_string_lossy().to_string())
+                .unwrap_or_default();
+            (parent.as_u32(), name)
+        });
+        let disk_usage = process.disk_usage();
+
+        Some(Self {
+            pid,
+            name: process.name().to_string_lossy().to_string(),
+            command_line: process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" "),
+            exe: process.exe().map(PathBuf::from),
+            cwd: process.cwd().map(PathBuf::from),
+            environment: process
+                .environ()
+                .iter()
+                .map(|var| var.to_string_lossy().to_string())
+                .collect(),
+            user,
+            group,
+            start_time: process.start_time(),
+            run_time: process.run_time(),
+            status: process.status().to_string(),
+            parent,
+            // sysinfo lists every thread except the main one as a task.
+            threads: process.tasks().map(|tasks| tasks.len() + 1),
+            virtual_memory: process.virtual_memory(),
+            resident_memory: process.memory(),
+            total_read_bytes: disk_usage.total_read_bytes,
+            total_written_bytes: disk_usage.total_written_bytes,
+        })
+    }
+}
@@ -1,92 +1,747 @@
 use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+use std::time::{Duration, Instant};
 
+use iced::advanced::widget::operation::{Operation, Scrollable};
+use iced::advanced::widget::{Id, operate};
+use iced::keyboard::Modifiers;
 use iced::widget::operation::scroll_to;
 use iced::widget::scrollable::AbsoluteOffset;
-use iced::widget::{container, responsive, text};
-use iced::{Element, Length, Renderer, Task};
+use iced::widget::{Space, container, responsive, row, text};
+use iced::{Element, Length, Rectangle, Renderer, Task, Vector};
 use iced_table2::table;
-use sysinfo::System;
+use serde::{Deserialize, Serialize};
+use sysinfo::{System, Users};
 
+use crate::app_groups::{AppGroup, build_groups};
+use crate::priority::{self, IoPriority};
+use crate::process_tree::{TreeNode, build_tree};
+use crate::query::{Query, QueryError};
+use crate::settings::{ColumnSettings, Units};
 use crate::theme::{TableStyle, Theme};
+use crate::utilities::{format_bytes, format_duration, format_timestamp};
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
+    pub parent_pid: Option<u32>,
     pub name: String,
     pub cpu_usage: f32,
     pub memory_bytes: u64,
-    pub disk_read_bytes: u64,
-    pub disk_written_bytes: u64,
+    /// Bytes per second, averaged since the previous sample.
+    pub disk_read_rate: u64,
+    pub disk_write_rate: u64,
+    /// `None` where the platform doesn't report it or access was denied.
+    /// Only collected while the Nice or I/O priority column is visible.
+    pub nice: Option<i32>,
+    pub io_priority: Option<IoPriority>,
+    pub user: Option<String>,
+    pub status: String,
+    pub threads: Option<usize>,
+    pub virtual_memory: u64,
+    /// Seconds since the Unix epoch.
+    pub start_time: u64,
+    /// Accumulated CPU time in milliseconds.
+    pub cpu_time: u64,
+    pub command_line: String,
+    pub exe: Option<String>,
+    pub session_id: Option<u32>,
+    /// Only collected while the Open files column is visible.
+    pub open_files: Option<usize>,
+    /// Position in the process tree, filled in by `apply_filter` in tree
+    /// and apps mode.
+    #[serde(skip)]
+    pub tree: Option<TreeNode>,
+    /// Set on the summary row of an app in apps mode.
+    #[serde(skip)]
+    pub group: Option<AppGroup>,
+    /// Set by `apply_filter` on rows kept at the top by a pin.
+    #[serde(skip)]
+    pub pinned: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProcessViewMode {
+    #[default]
+    List,
+    Tree,
+    /// Processes grouped by executable.
+    Apps,
+}
+
+impl ProcessViewMode {
+    pub const ALL: [ProcessViewMode; 3] = [
+        ProcessViewMode::List,
+        ProcessViewMode::Tree,
+        ProcessViewMode::Apps,
+    ];
+}
+
+impl std::fmt::Display for ProcessViewMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProcessColumnKind {
     Pid,
     Name,
     CpuUsage,
     MemoryUsage,
     DiskUsage,
+    Nice,
+    IoPriority,
+    User,
+    Status,
+    Threads,
+    VirtualMemory,
+    StartTime,
+    CpuTime,
+    CommandLine,
+    Executable,
+    ParentPid,
+    Session,
+    DiskRead,
+    DiskWrite,
+    OpenFiles,
+}
+
+impl ProcessColumnKind {
+    pub const ALL: [ProcessColumnKind; 20] = [
+        ProcessColumnKind::Pid,
+        ProcessColumnKind::Name,
+        ProcessColumnKind::CpuUsage,
+        ProcessColumnKind::MemoryUsage,
+        ProcessColumnKind::DiskUsage,
+        ProcessColumnKind::Nice,
+        ProcessColumnKind::IoPriority,
+        ProcessColumnKind::User,
+        ProcessColumnKind::Status,
+        ProcessColumnKind::Threads,
+        ProcessColumnKind::VirtualMemory,
+        ProcessColumnKind::StartTime,
+        ProcessColumnKind::CpuTime,
+        ProcessColumnKind::CommandLine,
+        ProcessColumnKind::Executable,
+        ProcessColumnKind::ParentPid,
+        ProcessColumnKind::Session,
+        ProcessColumnKind::DiskRead,
+        ProcessColumnKind::DiskWrite,
+        ProcessColumnKind::OpenFiles,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProcessColumnKind::Pid => "PID",
+            ProcessColumnKind::Name => "Name",
+            ProcessColumnKind::CpuUsage => "CPU %",
+            ProcessColumnKind::MemoryUsage => "Memory",
+            ProcessColumnKind::DiskUsage => "Disk (R+W)/s",
+            ProcessColumnKind::Nice => "Nice",
+            ProcessColumnKind::IoPriority => "I/O priority",
+            ProcessColumnKind::User => "User",
+            ProcessColumnKind::Status => "Status",
+            ProcessColumnKind::Threads => "Threads",
+            ProcessColumnKind::VirtualMemory => "Virtual memory",
+            ProcessColumnKind::StartTime => "Started",
+            ProcessColumnKind::CpuTime => "CPU time",
+            ProcessColumnKind::CommandLine => "Command line",
+            ProcessColumnKind::Executable => "Executable",
+            ProcessColumnKind::ParentPid => "Parent PID",
+            ProcessColumnKind::Session => "Session",
+            ProcessColumnKind::DiskRead => "Disk read/s",
+            ProcessColumnKind::DiskWrite => "Disk write/s",
+            ProcessColumnKind::OpenFiles => "Open files",
+        }
+    }
+
+    /// Field name used for the column in exports.
+    pub fn key(self) -> &'static str {
+        match self {
+            ProcessColumnKind::Pid => "pid",
+            ProcessColumnKind::Name => "name",
+            ProcessColumnKind::CpuUsage => "cpu_usage",
+            ProcessColumnKind::MemoryUsage => "memory_bytes",
+            ProcessColumnKind::DiskUsage => "disk_bytes_per_sec",
+            ProcessColumnKind::Nice => "nice",
+            ProcessColumnKind::IoPriority => "io_priority",
+            ProcessColumnKind::User => "user",
+            ProcessColumnKind::Status => "status",
+            ProcessColumnKind::Threads => "threads",
+            ProcessColumnKind::VirtualMemory => "virtual_memory_bytes",
+            ProcessColumnKind::StartTime => "start_time",
+            ProcessColumnKind::CpuTime => "cpu_time_ms",
+            ProcessColumnKind::CommandLine => "command_line",
+            ProcessColumnKind::Executable => "executable",
+            ProcessColumnKind::ParentPid => "parent_pid",
+            ProcessColumnKind::Session => "session",
+            ProcessColumnKind::DiskRead => "disk_read_bytes_per_sec",
+            ProcessColumnKind::DiskWrite => "disk_written_bytes_per_sec",
+            ProcessColumnKind::OpenFiles => "open_files",
+        }
+    }
+
+    /// Only the original five columns are shown until the user picks more.
+    pub fn default_visible(self) -> bool {
+        matches!(
+            self,
+            ProcessColumnKind::Pid
+                | ProcessColumnKind::Name
+                | ProcessColumnKind::CpuUsage
+                | ProcessColumnKind::MemoryUsage
+                | ProcessColumnKind::DiskUsage
+        )
+    }
+
+    pub fn default_width(self) -> f32 {
+        match self {
+            ProcessColumnKind::Pid => 80.0,
+            ProcessColumnKind::Name => 250.0,
+            ProcessColumnKind::CpuUsage => 100.0,
+            ProcessColumnKind::MemoryUsage => 120.0,
+            ProcessColumnKind::DiskUsage => 150.0,
+            ProcessColumnKind::Nice => 70.0,
+            ProcessColumnKind::IoPriority => 100.0,
+            ProcessColumnKind::User => 100.0,
+            ProcessColumnKind::Status => 90.0,
+            ProcessColumnKind::Threads => 80.0,
+            ProcessColumnKind::VirtualMemory => 120.0,
+            ProcessColumnKind::StartTime => 180.0,
+            ProcessColumnKind::CpuTime => 100.0,
+            ProcessColumnKind::CommandLine => 300.0,
+            ProcessColumnKind::Executable => 250.0,
+            ProcessColumnKind::ParentPid => 90.0,
+            ProcessColumnKind::Session => 80.0,
+            ProcessColumnKind::DiskRead => 110.0,
+            ProcessColumnKind::DiskWrite => 110.0,
+            ProcessColumnKind::OpenFiles => 90.0,
+        }
+    }
+
+    /// Direction used the first time a column is clicked: text-like columns
+    /// start A→Z, resource columns start with the heaviest process on top.
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            ProcessColumnKind::Pid
+            | ProcessColumnKind::Name
+            | ProcessColumnKind::Nice
+            | ProcessColumnKind::IoPriority
+            | ProcessColumnKind::User
+            | ProcessColumnKind::Status
+            | ProcessColumnKind::StartTime
+            | ProcessColumnKind::CommandLine
+            | ProcessColumnKind::Executable
+            | ProcessColumnKind::ParentPid
+            | ProcessColumnKind::Session => SortOrder::Ascending,
+            ProcessColumnKind::CpuUsage
+            | ProcessColumnKind::MemoryUsage
+            | ProcessColumnKind::DiskUsage
+            | ProcessColumnKind::Threads
+            | ProcessColumnKind::VirtualMemory
+            | ProcessColumnKind::CpuTime
+            | ProcessColumnKind::DiskRead
+            | ProcessColumnKind::DiskWrite
+            | ProcessColumnKind::OpenFiles => SortOrder::Descending,
+        }
+    }
+}
+
+/// Accepts the export keys and the short field names used in search
+/// queries, such as `mem` or `cmd`.
+impl std::str::FromStr for ProcessColumnKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.trim().to_lowercase();
+        let alias = match name.as_str() {
+            "ppid" => Some(ProcessColumnKind::ParentPid),
+            "cpu" => Some(ProcessColumnKind::CpuUsage),
+            "mem" | "memory" => Some(ProcessColumnKind::MemoryUsage),
+            "disk" => Some(ProcessColumnKind::DiskUsage),
+            "io" => Some(ProcessColumnKind::IoPriority),
+            "vmem" | "virtual" => Some(ProcessColumnKind::VirtualMemory),
+            "started" => Some(ProcessColumnKind::StartTime),
+            "time" | "cpu_time" => Some(ProcessColumnKind::CpuTime),
+            "cmd" | "command" => Some(ProcessColumnKind::CommandLine),
+            "exe" => Some(ProcessColumnKind::Executable),
+            "read" => Some(ProcessColumnKind::DiskRead),
+            "write" => Some(ProcessColumnKind::DiskWrite),
+            "files" | "fd" => Some(ProcessColumnKind::OpenFiles),
+            _ => None,
+        };
+
+        alias
+            .or_else(|| Self::ALL.into_iter().find(|kind| kind.key() == name))
+            .ok_or_else(|| format!("unknown column \"{name}\""))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn toggle(self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
 }
 
 pub struct ProcessColumn {
     kind: ProcessColumnKind,
     width: f32,
     resize_offset: Option<f32>,
+    sort: Option<SortOrder>,
+    units: Units,
 }
 
 impl ProcessColumn {
     pub fn new(kind: ProcessColumnKind) -> Self {
-        let width = match kind {
-            ProcessColumnKind::Pid => 80.0,
-            ProcessColumnKind::Name => 250.0,
-            ProcessColumnKind::CpuUsage => 100.0,
-            ProcessColumnKind::MemoryUsage => 120.0,
-            ProcessColumnKind::DiskUsage => 150.0,
-        };
+        Self::with_width(kind, kind.default_width())
+    }
+
+    pub fn with_width(kind: ProcessColumnKind, width: f32) -> Self {
         Self {
             kind,
             width,
             resize_offset: None,
+            sort: None,
+            units: Units::default(),
         }
     }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn kind(&self) -> ProcessColumnKind {
+        self.kind
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum ProcessTableMessage {
+    Navigate(Navigation),
+    /// A character typed while the table has keyboard focus, which jumps
+    /// to the next process whose name starts with what was typed.
+    TypeAhead(char),
     SyncHeader(AbsoluteOffset),
     Resizing(usize, f32),
     Resized,
     RowSelected(u32),
     SearchChanged(String),
+    SortBy(ProcessColumnKind),
+    ViewModeSelected(ProcessViewMode),
+    ToggleExpanded(u32),
+    ToggleGroup(String),
+    GroupSelected(String),
+    SelectAll,
+    ClearSelection,
+    ModifiersChanged(Modifiers),
+    OpenColumnMenu,
+    CloseColumnMenu,
+    ToggleColumn(ProcessColumnKind, bool),
+    MoveColumnLeft(ProcessColumnKind),
+    MoveColumnRight(ProcessColumnKind),
+}
+
+impl ProcessTableMessage {
+    /// Whether the message changes the column layout, which is saved with
+    /// the settings.
+    pub fn changes_columns(&self) -> bool {
+        matches!(
+            self,
+            ProcessTableMessage::Resized
+                | ProcessTableMessage::ToggleColumn(..)
+                | ProcessTableMessage::MoveColumnLeft(_)
+                | ProcessTableMessage::MoveColumnRight(_)
+        )
+    }
+}
+
+/// Body rows have a fixed height so keyboard navigation can work out
+/// where a row is and scroll it into view.
+pub const ROW_HEIGHT: f32 = 26.0;
+/// Padding `iced_table2` puts around every cell.
+const CELL_PADDING: f32 = 4.0;
+/// Rows PageUp and PageDown move by.
+pub const PAGE_ROWS: usize = 20;
+/// Pause after which type-ahead starts matching from scratch.
+pub const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Keyboard movement through the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Navigation {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+/// Identifies a row across refreshes, which may reorder the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowKey {
+    Process(u32),
+    Group(String),
+}
+
+impl RowKey {
+    fn of(row: &ProcessInfo) -> Self {
+        match &row.group {
+            Some(group) => RowKey::Group(group.key.clone()),
+            None => RowKey::Process(row.pid),
+        }
+    }
+}
+
+/// PIDs a row stands for: all members of an app group, or just itself.
+fn row_pids(row: &ProcessInfo) -> Vec<u32> {
+    match &row.group {
+        Some(group) => group.members.clone(),
+        None => vec![row.pid],
+    }
 }
 
 pub struct ProcessTableState {
     pub columns: Vec<ProcessColumn>,
     pub rows: Vec<ProcessInfo>,
     pub filtered_rows: Vec<ProcessInfo>,
+    /// The row clicked last, which the details panel and single-process
+    /// dialogs use.
     pub selected_pid: Option<u32>,
+    /// Every selected PID. Selecting an app group selects all its members.
+    pub selection: BTreeSet<u32>,
+    /// Row a shift-click range starts from.
+    pub anchor: Option<RowKey>,
+    /// Row the keyboard moves from: the one clicked or navigated to last.
+    pub focus: Option<RowKey>,
+    /// Characters typed for type-ahead, lowercase, and when the last one
+    /// came in.
+    pub type_ahead: String,
+    pub type_ahead_at: Option<Instant>,
+    /// Modifier keys held down, which decide what a click on a row does.
+    pub modifiers: Modifiers,
     pub header: iced::widget::Id,
     pub body: iced::widget::Id,
     pub footer: iced::widget::Id,
+    /// The search box, so a shortcut can focus it.
+    pub search: iced::widget::Id,
     pub search_query: String,
+    /// The search box parsed as a query. While the text doesn't parse, the
+    /// last query that did keeps filtering and `query_error` says why.
+    pub query: Query,
+    pub query_error: Option<QueryError>,
+    /// Queries from the pinned processes setting.
+    pub pins: Vec<Query>,
+    pub sort_column: ProcessColumnKind,
+    pub sort_order: SortOrder,
+    pub units: Units,
+    /// Columns switched off in the settings, kept so their order and width
+    /// survive a save.
+    pub hidden_columns: Vec<ColumnSettings>,
+    pub view_mode: ProcessViewMode,
+    /// PIDs whose children are hidden in tree mode.
+    pub collapsed: HashSet<u32>,
+    /// Keys of the app groups that are expanded in apps mode.
+    pub expanded_groups: HashSet<String>,
+    /// Whether the column menu opened from the header is showing.
+    pub column_menu: bool,
 }
 
 impl Default for ProcessTableState {
     fn default() -> Self {
-        Self {
-            columns: vec![
-                ProcessColumn::new(ProcessColumnKind::Pid),
-                ProcessColumn::new(ProcessColumnKind::Name),
-                ProcessColumn::new(ProcessColumnKind::CpuUsage),
-                ProcessColumn::new(ProcessColumnKind::MemoryUsage),
-                ProcessColumn::new(ProcessColumnKind::DiskUsage),
-            ],
+        let mut state = Self {
+            columns: ProcessColumnKind::ALL
+                .into_iter()
+                .filter(|kind| kind.default_visible())
+                .map(ProcessColumn::new)
+                .collect(),
             rows: Vec::new(),
             filtered_rows: Vec::new(),
             selected_pid: None,
+            selection: BTreeSet::new(),
+            anchor: None,
+            focus: None,
+            type_ahead: String::new(),
+            type_ahead_at: None,
+            modifiers: Modifiers::default(),
             header: iced::widget::Id::unique(),
             body: iced::widget::Id::unique(),
             footer: iced::widget::Id::unique(),
+            search: iced::widget::Id::unique(),
             search_query: String::new(),
+            query: Query::default(),
+            query_error: None,
+            pins: Vec::new(),
+            sort_column: ProcessColumnKind::CpuUsage,
+            sort_order: SortOrder::Descending,
+            units: Units::default(),
+            hidden_columns: ProcessColumnKind::ALL
+                .into_iter()
+                .filter(|kind| !kind.default_visible())
+                .map(ColumnSettings::new)
+                .collect(),
+            view_mode: ProcessViewMode::default(),
+            collapsed: HashSet::new(),
+            expanded_groups: HashSet::new(),
+            column_menu: false,
+        };
+        state.sync_columns();
+        state
+    }
+}
+
+impl ProcessTableState {
+    /// Mirrors table-wide state onto the columns so each header knows
+    /// whether to draw a sort arrow and each cell which units to use.
+    fn sync_columns(&mut self) {
+        for col in &mut self.columns {
+            col.sort = (col.kind == self.sort_column).then_some(self.sort_order);
+            col.units = self.units;
+        }
+    }
+
+    pub fn apply_column_settings(&mut self, columns: &[ColumnSettings]) {
+        self.columns = columns
+            .iter()
+            .filter(|c| c.visible)
+            .map(|c| ProcessColumn::with_width(c.kind, c.width))
+            .collect();
+        self.hidden_columns = columns.iter().filter(|c| !c.visible).cloned().collect();
+        self.sync_columns();
+    }
+
+    pub fn column_settings(&self) -> Vec<ColumnSettings> {
+        self.columns
+            .iter()
+            .map(|c| ColumnSettings {
+                kind: c.kind,
+                width: c.width,
+                visible: true,
+            })
+            .chain(self.hidden_columns.iter().cloned())
+            .collect()
+    }
+
+    /// Replaces the pins with the given queries. Patterns that don't parse
+    /// are skipped; the settings already warn about them.
+    pub fn set_pins(&mut self, patterns: &[String]) {
+        self.pins = patterns
+            .iter()
+            .filter_map(|pattern| Query::parse(pattern).ok())
+            .collect();
+    }
+
+    pub fn set_units(&mut self, units: Units) {
+        self.units = units;
+        self.sync_columns();
+    }
+
+    /// PIDs an action on the selection applies to, lowest first.
+    pub fn selected_pids(&self) -> Vec<u32> {
+        self.selection.iter().copied().collect()
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+        self.selected_pid = None;
+        self.anchor = None;
+        self.focus = None;
+    }
+
+    /// Plain-text description of the selected processes for pasting
+    /// elsewhere, one line per process.
+    pub fn selection_info(&self) -> String {
+        self.rows
+            .iter()
+            .filter(|r| self.selection.contains(&r.pid))
+            .map(|r| {
+                format!(
+                    "{} (PID {}) — CPU {:.1}%, memory {}, user {}, command line: {}",
+                    r.name,
+                    r.pid,
+                    r.cpu_usage,
+                    format_bytes(r.memory_bytes, self.units),
+                    r.user.as_deref().unwrap_or("unknown"),
+                    r.command_line,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Position of the focused row in `filtered_rows`.
+    pub fn focused_index(&self) -> Option<usize> {
+        self.focus.as_ref().and_then(|key| self.row_index(key))
+    }
+
+    fn row_index(&self, key: &RowKey) -> Option<usize> {
+        self.filtered_rows
+            .iter()
+            .position(|r| RowKey::of(r) == *key)
+    }
+
+    /// Selects a row the way a click does: a plain click selects just that
+    /// row, Ctrl toggles it and Shift extends the selection from the
+    /// anchor. Ctrl+Shift adds the range to the selection.
+    fn select_row(&mut self, key: RowKey) {
+        let Some(index) = self.row_index(&key) else {
+            return;
+        };
+        let pid = self.filtered_rows[index].pid;
+        let pids = row_pids(&self.filtered_rows[index]);
+        let anchor = self
+            .anchor
+            .as_ref()
+            .and_then(|anchor| self.row_index(anchor));
+
+        if self.modifiers.shift()
+            && let Some(anchor) = anchor
+        {
+            if !self.modifiers.command() {
+                self.selection.clear();
+            }
+            let range = anchor.min(index)..=anchor.max(index);
+            for row in &self.filtered_rows[range] {
+                self.selection.extend(row_pids(row));
+            }
+        } else {
+            if !self.modifiers.command() {
+                self.selection.clear();
+                self.selection.extend(pids);
+            } else if pids.iter().all(|pid| self.selection.contains(pid)) {
+                for pid in &pids {
+                    self.selection.remove(pid);
+                }
+            } else {
+                self.selection.extend(pids);
+            }
+            self.anchor = Some(key.clone());
+        }
+        self.selected_pid = Some(pid);
+        self.focus = Some(key);
+    }
+
+    /// Moves the focus like a click on the target row would, so Shift
+    /// extends the selection. Returns the index of the new focused row.
+    fn navigate(&mut self, navigation: Navigation) -> Option<usize> {
+        let last = self.filtered_rows.len().checked_sub(1)?;
+        let current = self.focus.as_ref().and_then(|key| self.row_index(key));
+        let index = match (navigation, current) {
+            (Navigation::End, _) => last,
+            (Navigation::Home, _) | (_, None) => 0,
+            (Navigation::Up, Some(index)) => index.saturating_sub(1),
+            (Navigation::Down, Some(index)) => (index + 1).min(last),
+            (Navigation::PageUp, Some(index)) => index.saturating_sub(PAGE_ROWS),
+            (Navigation::PageDown, Some(index)) => (index + PAGE_ROWS).min(last),
+        };
+        self.select_row(RowKey::of(&self.filtered_rows[index]));
+        Some(index)
+    }
+
+    /// Selects the next row whose name starts with the characters typed
+    /// so far. Typing the same letter again moves on to the next match.
+    fn type_ahead(&mut self, c: char, now: Instant) -> Option<usize> {
+        let expired = self
+            .type_ahead_at
+            .is_none_or(|at| now.duration_since(at) > TYPE_AHEAD_TIMEOUT);
+        if expired {
+            self.type_ahead.clear();
+        }
+        self.type_ahead_at = Some(now);
+        self.type_ahead.extend(c.to_lowercase());
+
+        let len = self.filtered_rows.len();
+        let current = self.focus.as_ref().and_then(|key| self.row_index(key));
+        // A longer prefix may still match the focused row; a fresh letter
+        // looks past it.
+        let start = match current {
+            Some(index) if self.type_ahead.chars().count() == 1 => index + 1,
+            Some(index) => index,
+            None => 0,
+        };
+        let index = (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&index| {
+                self.filtered_rows[index]
+                    .name
+                    .to_lowercase()
+                    .starts_with(&self.type_ahead)
+            })?;
+
+        // Shift may be held to type a capital, which shouldn't extend the
+        // selection.
+        let modifiers = std::mem::take(&mut self.modifiers);
+        self.select_row(RowKey::of(&self.filtered_rows[index]));
+        self.modifiers = modifiers;
+        Some(index)
+    }
+
+    /// Whether a row is drawn as selected: all the processes it stands for
+    /// are.
+    fn is_row_selected(&self, row: &ProcessInfo) -> bool {
+        row_pids(row).iter().all(|pid| self.selection.contains(pid))
+    }
+
+    /// The selected rows as shown, in table order.
+    pub fn selected_rows(&self) -> Vec<&ProcessInfo> {
+        self.filtered_rows
+            .iter()
+            .filter(|r| self.is_row_selected(r))
+            .collect()
+    }
+
+    /// Kinds of the shown columns, left to right.
+    pub fn visible_columns(&self) -> Vec<ProcessColumnKind> {
+        self.columns.iter().map(|c| c.kind).collect()
+    }
+
+    pub fn is_visible(&self, kind: ProcessColumnKind) -> bool {
+        self.columns.iter().any(|c| c.kind == kind)
+    }
+
+    pub fn slow_columns(&self) -> SlowColumns {
+        SlowColumns::among(self.columns.iter().map(|c| c.kind))
+    }
+
+    /// Shows or hides a column. A shown column is added at the right end;
+    /// the last visible column can't be hidden.
+    fn set_column_visible(&mut self, kind: ProcessColumnKind, visible: bool) {
+        if visible {
+            if let Some(index) = self.hidden_columns.iter().position(|c| c.kind == kind) {
+                let hidden = self.hidden_columns.remove(index);
+                self.columns
+                    .push(ProcessColumn::with_width(hidden.kind, hidden.width));
+            }
+        } else if self.columns.len() > 1
+            && let Some(index) = self.columns.iter().position(|c| c.kind == kind)
+        {
+            let column = self.columns.remove(index);
+            self.hidden_columns.push(ColumnSettings {
+                kind,
+                width: column.width,
+                visible: false,
+            });
+        }
+        self.sync_columns();
+    }
+
+    /// Swaps a visible column with its neighbour `offset` places away.
+    fn move_column(&mut self, kind: ProcessColumnKind, offset: isize) {
+        let Some(index) = self.columns.iter().position(|c| c.kind == kind) else {
+            return;
+        };
+        if let Some(target) = index.checked_add_signed(offset)
+            && target < self.columns.len()
+        {
+            self.columns.swap(index, target);
         }
     }
 }
@@ -114,38 +769,282 @@ pub fn update(
                 }
             }
         }
-        ProcessTableMessage::RowSelected(pid) => {
-            state.selected_pid = Some(pid);
+        ProcessTableMessage::Navigate(navigation) => {
+            if let Some(index) = state.navigate(navigation) {
+                return scroll_into_view(state.body.clone(), index);
+            }
+        }
+        ProcessTableMessage::TypeAhead(c) => {
+            if let Some(index) = state.type_ahead(c, Instant::now()) {
+                return scroll_into_view(state.body.clone(), index);
+            }
+        }
+        ProcessTableMessage::RowSelected(pid) => state.select_row(RowKey::Process(pid)),
+        ProcessTableMessage::GroupSelected(key) => state.select_row(RowKey::Group(key)),
+        ProcessTableMessage::SelectAll => {
+            // The tree view also shows the ancestors of every match, which
+            // weren't searched for and so aren't selected.
+            let query = &state.query;
+            state.selection = state
+                .filtered_rows
+                .iter()
+                .filter(|row| row.tree.is_none() || query.matches(row))
+                .flat_map(row_pids)
+                .collect();
         }
+        ProcessTableMessage::ClearSelection => state.clear_selection(),
+        ProcessTableMessage::ModifiersChanged(modifiers) => state.modifiers = modifiers,
         ProcessTableMessage::SearchChanged(query) => {
+            match Query::parse(&query) {
+                Ok(parsed) => {
+                    state.query = parsed;
+                    state.query_error = None;
+                }
+                Err(err) => state.query_error = Some(err),
+            }
             state.search_query = query;
             apply_filter(state);
         }
+        ProcessTableMessage::SortBy(kind) => {
+            if state.sort_column == kind {
+                state.sort_order = state.sort_order.toggle();
+            } else {
+                state.sort_column = kind;
+                state.sort_order = kind.default_order();
+            }
+            state.sync_columns();
+            apply_filter(state);
+        }
+        ProcessTableMessage::ViewModeSelected(mode) => {
+            state.view_mode = mode;
+            apply_filter(state);
+        }
+        ProcessTableMessage::ToggleExpanded(pid) => {
+            if !state.collapsed.remove(&pid) {
+                state.collapsed.insert(pid);
+            }
+            apply_filter(state);
+        }
+        ProcessTableMessage::ToggleGroup(key) => {
+            if !state.expanded_groups.remove(&key) {
+                state.expanded_groups.insert(key);
+            }
+            apply_filter(state);
+        }
+        ProcessTableMessage::OpenColumnMenu => state.column_menu = true,
+        ProcessTableMessage::CloseColumnMenu => state.column_menu = false,
+        ProcessTableMessage::ToggleColumn(kind, visible) => {
+            state.set_column_visible(kind, visible);
+        }
+        ProcessTableMessage::MoveColumnLeft(kind) => state.move_column(kind, -1),
+        ProcessTableMessage::MoveColumnRight(kind) => state.move_column(kind, 1),
     }
     Task::none()
 }
 
+/// Scrolls the table body just far enough for the row at `index` to be
+/// fully visible, keeping the horizontal position.
+fn scroll_into_view(body: Id, index: usize) -> Task<ProcessTableMessage> {
+    struct ScrollIntoView {
+        target: Id,
+        top: f32,
+        bottom: f32,
+    }
+
+    impl Operation for ScrollIntoView {
+        fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation)) {
+            operate(self);
+        }
+
+        fn scrollable(
+            &mut self,
+            id: Option<&Id>,
+            bounds: Rectangle,
+            _content_bounds: Rectangle,
+            translation: Vector,
+            state: &mut dyn Scrollable,
+        ) {
+            if id != Some(&self.target) {
+                return;
+            }
+            let y = if self.top < translation.y {
+                self.top
+            } else if self.bottom > translation.y + bounds.height {
+                self.bottom - bounds.height
+            } else {
+                return;
+            };
+            state.scroll_to(AbsoluteOffset {
+                x: None,
+                y: Some(y),
+            });
+        }
+    }
+
+    let top = index as f32 * ROW_HEIGHT;
+    operate(ScrollIntoView {
+        target: body,
+        top,
+        bottom: top + ROW_HEIGHT,
+    })
+    .discard()
+}
+
+/// Rebuilds `filtered_rows` from `rows` for the current search, sort and
+/// view mode, with pinned processes moved to the top.
 pub fn apply_filter(state: &mut ProcessTableState) {
-    if state.search_query.is_empty() {
-        state.filtered_rows = state.rows.clone();
-    } else {
-        let query = state.search_query.to_lowercase();
-        state.filtered_rows = state
-            .rows
+    filter_rows(state);
+
+    let pinned: HashSet<u32> = state
+        .rows
+        .iter()
+        .filter(|r| state.pins.iter().any(|pin| pin.matches(r)))
+        .map(|r| r.pid)
+        .collect();
+    pin_to_top(&mut state.filtered_rows, &pinned);
+}
+
+/// Moves rows standing for a pinned process above the rest, each with the
+/// tree children or group members shown under it. The pinned and unpinned
+/// parts both keep their sort order.
+fn pin_to_top(rows: &mut Vec<ProcessInfo>, pinned: &HashSet<u32>) {
+    if pinned.is_empty() {
+        return;
+    }
+
+    let mut blocks: Vec<Vec<ProcessInfo>> = Vec::new();
+    for mut row in rows.drain(..) {
+        row.pinned = row_pids(&row).iter().any(|pid| pinned.contains(pid));
+        match blocks.last_mut() {
+            Some(block) if row.tree.as_ref().is_some_and(|node| node.depth > 0) => block.push(row),
+            _ => blocks.push(vec![row]),
+        }
+    }
+
+    let (top, rest): (Vec<_>, Vec<_>) = blocks.into_iter().partition(|block| block[0].pinned);
+    rows.extend(top.into_iter().chain(rest).flatten());
+}
+
+fn filter_rows(state: &mut ProcessTableState) {
+    let query = &state.query;
+    let matches = |r: &ProcessInfo| query.matches(r);
+
+    // Exited processes drop out of the selection.
+    let running: HashSet<u32> = state.rows.iter().map(|r| r.pid).collect();
+    state.selection.retain(|pid| running.contains(pid));
+
+    if state.view_mode == ProcessViewMode::Tree {
+        // Collapsed PIDs that have exited can never be expanded again.
+        state
+            .collapsed
+            .retain(|pid| state.rows.iter().any(|r| r.pid == *pid));
+
+        state.filtered_rows = build_tree(
+            &state.rows,
+            &state.collapsed,
+            state.sort_column,
+            state.sort_order,
+            (!query.is_empty()).then_some(&matches as &dyn Fn(&ProcessInfo) -> bool),
+        );
+        return;
+    }
+
+    if state.view_mode == ProcessViewMode::Apps {
+        let rows: Vec<ProcessInfo> = state.rows.iter().filter(|r| matches(r)).cloned().collect();
+        state.filtered_rows = build_groups(
+            &rows,
+            &state.expanded_groups,
+            state.sort_column,
+            state.sort_order,
+            !query.is_empty(),
+        );
+
+        let groups: HashSet<&str> = state
+            .filtered_rows
             .iter()
-            .filter(|r| {
-                r.name.to_lowercase().contains(&query)
-                    || r.pid.to_string().contains(&query)
-            })
-            .cloned()
+            .filter_map(|r| r.group.as_ref())
+            .map(|group| group.key.as_str())
             .collect();
+        state
+            .expanded_groups
+            .retain(|key| groups.contains(key.as_str()));
+        return;
+    }
+
+    if query.is_empty() {
+        state.filtered_rows = state.rows.clone();
+    } else {
+        state.filtered_rows = state.rows.iter().filter(|r| matches(r)).cloned().collect();
     }
+
+    sort_processes(
+        &mut state.filtered_rows,
+        state.sort_column,
+        state.sort_order,
+    );
+}
+
+/// Sorts rows by the given column. Ties are broken by PID so rows keep a
+/// stable position between refreshes.
+pub fn sort_processes(rows: &mut [ProcessInfo], column: ProcessColumnKind, order: SortOrder) {
+    rows.sort_by(|a, b| compare_processes(a, b, column, order));
+}
+
+pub fn compare_processes(
+    a: &ProcessInfo,
+    b: &ProcessInfo,
+    column: ProcessColumnKind,
+    order: SortOrder,
+) -> Ordering {
+    let ordering = match column {
+        ProcessColumnKind::Pid => a.pid.cmp(&b.pid),
+        ProcessColumnKind::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        ProcessColumnKind::CpuUsage => a
+            .cpu_usage
+            .partial_cmp(&b.cpu_usage)
+            .unwrap_or(Ordering::Equal),
+        ProcessColumnKind::MemoryUsage => a.memory_bytes.cmp(&b.memory_bytes),
+        ProcessColumnKind::DiskUsage => {
+            (a.disk_read_rate + a.disk_write_rate).cmp(&(b.disk_read_rate + b.disk_write_rate))
+        }
+        ProcessColumnKind::Nice => a.nice.cmp(&b.nice),
+        ProcessColumnKind::IoPriority => effective_io_priority(a).cmp(&effective_io_priority(b)),
+        ProcessColumnKind::User => a.user.cmp(&b.user),
+        ProcessColumnKind::Status => a.status.cmp(&b.status),
+        ProcessColumnKind::Threads => a.threads.cmp(&b.threads),
+        ProcessColumnKind::VirtualMemory => a.virtual_memory.cmp(&b.virtual_memory),
+        ProcessColumnKind::StartTime => a.start_time.cmp(&b.start_time),
+        ProcessColumnKind::CpuTime => a.cpu_time.cmp(&b.cpu_time),
+        ProcessColumnKind::CommandLine => a.command_line.cmp(&b.command_line),
+        ProcessColumnKind::Executable => a.exe.cmp(&b.exe),
+        ProcessColumnKind::ParentPid => a.parent_pid.cmp(&b.parent_pid),
+        ProcessColumnKind::Session => a.session_id.cmp(&b.session_id),
+        ProcessColumnKind::DiskRead => a.disk_read_rate.cmp(&b.disk_read_rate),
+        ProcessColumnKind::DiskWrite => a.disk_write_rate.cmp(&b.disk_write_rate),
+        ProcessColumnKind::OpenFiles => a.open_files.cmp(&b.open_files),
+    };
+
+    let ordering = match order {
+        SortOrder::Ascending => ordering,
+        SortOrder::Descending => ordering.reverse(),
+    };
+
+    ordering.then_with(|| a.pid.cmp(&b.pid))
+}
+
+pub fn effective_io_priority(row: &ProcessInfo) -> Option<IoPriority> {
+    row.io_priority
+        .map(|io| io.effective(row.nice.unwrap_or_default()))
 }
 
 pub fn view(state: &ProcessTableState) -> Element<'_, ProcessTableMessage, Theme> {
-    let selected_row = state
-        .selected_pid
-        .and_then(|pid| state.filtered_rows.iter().position(|r| r.pid == pid));
+    let selected_rows: HashSet<usize> = state
+        .filtered_rows
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| state.is_row_selected(r))
+        .map(|(index, _)| index)
+        .collect();
 
     responsive(move |size| {
         let table = table(
@@ -157,7 +1056,9 @@ pub fn view(state: &ProcessTableState) -> Element<'_, ProcessTableMessage, Theme
         )
         .on_column_resize(ProcessTableMessage::Resizing, ProcessTableMessage::Resized)
         .min_width(size.width)
-        .style(TableStyle { selected_row });
+        .style(TableStyle {
+            selected_rows: selected_rows.clone(),
+        });
 
         table.into()
     })
@@ -168,15 +1069,23 @@ impl<'a> table::Column<'a, ProcessTableMessage, Theme, Renderer> for ProcessColu
     type Row = ProcessInfo;
 
     fn header(&'a self, _col_index: usize) -> Element<'a, ProcessTableMessage, Theme> {
-        let label = match self.kind {
-            ProcessColumnKind::Pid => "PID",
-            ProcessColumnKind::Name => "Name",
-            ProcessColumnKind::CpuUsage => "CPU %",
-            ProcessColumnKind::MemoryUsage => "Memory",
-            ProcessColumnKind::DiskUsage => "Disk (R+W)",
+        let label = self.kind.label();
+
+        let label = match self.sort {
+            Some(SortOrder::Ascending) => format!("{label} ▲"),
+            Some(SortOrder::Descending) => format!("{label} ▼"),
+            None => label.to_string(),
         };
 
-        container(text(label).size(14)).height(24).into()
+        iced::widget::mouse_area(
+            container(text(label).size(14))
+                .height(24)
+                .width(Length::Fill),
+        )
+        .on_press(ProcessTableMessage::SortBy(self.kind))
+        .on_right_press(ProcessTableMessage::OpenColumnMenu)
+        .interaction(iced::mouse::Interaction::Pointer)
+        .into()
     }
 
     fn cell(
@@ -186,41 +1095,60 @@ impl<'a> table::Column<'a, ProcessTableMessage, Theme, Renderer> for ProcessColu
         row: &'a ProcessInfo,
     ) -> Element<'a, ProcessTableMessage, Theme> {
         let content: Element<'_, ProcessTableMessage, Theme> = match self.kind {
-            ProcessColumnKind::Pid => text(row.pid).size(13).into(),
-
-            ProcessColumnKind::Name => text(&row.name)
-                .size(13)
-                .wrapping(text::Wrapping::None)
-                .into(),
+            // A group row's PID is just its first member's, so it's left
+            // out.
+            ProcessColumnKind::Pid if row.group.is_some() => Space::new().into(),
 
-            ProcessColumnKind::CpuUsage => text(format!("{:.1}%", row.cpu_usage)).size(13).into(),
-
-            ProcessColumnKind::MemoryUsage => {
-                let mb = row.memory_bytes as f64 / 1_048_576.0;
-                let label = if mb >= 1024.0 {
-                    format!("{:.1} GB", mb / 1024.0)
+            ProcessColumnKind::Name => {
+                let name = if row.pinned {
+                    text(format!("★ {}", row.name))
                 } else {
-                    format!("{:.1} MB", mb)
+                    text(&row.name)
                 };
-                text(label).size(13).into()
-            }
+                let name = name.size(13).wrapping(text::Wrapping::None);
 
-            ProcessColumnKind::DiskUsage => {
-                let total = row.disk_read_bytes + row.disk_written_bytes;
-                let mb = total as f64 / 1_048_576.0;
-                let label = if mb >= 1024.0 {
-                    format!("{:.1} GB", mb / 1024.0)
-                } else {
-                    format!("{:.1} MB", mb)
-                };
-                text(label).size(13).into()
+                match &row.tree {
+                    Some(node) => {
+                        let toggle: Element<'_, ProcessTableMessage, Theme> = if node.has_children {
+                            iced::widget::mouse_area(
+                                text(if node.expanded { "▾" } else { "▸" })
+                                    .size(13)
+                                    .width(14),
+                            )
+                            .on_press(match &row.group {
+                                Some(group) => ProcessTableMessage::ToggleGroup(group.key.clone()),
+                                None => ProcessTableMessage::ToggleExpanded(row.pid),
+                            })
+                            .interaction(iced::mouse::Interaction::Pointer)
+                            .into()
+                        } else {
+                            Space::new().width(14).into()
+                        };
+
+                        row![Space::new().width(node.depth as f32 * 14.0), toggle, name].into()
+                    }
+                    None => name.into(),
+                }
             }
+
+            kind => text(cell_text(kind, row, self.units))
+                .size(13)
+                .wrapping(text::Wrapping::None)
+                .into(),
         };
 
-        let pid = row.pid;
-        iced::widget::mouse_area(container(content).width(Length::Fill))
-            .on_press(ProcessTableMessage::RowSelected(pid))
-            .into()
+        let on_press = match &row.group {
+            Some(group) => ProcessTableMessage::GroupSelected(group.key.clone()),
+            None => ProcessTableMessage::RowSelected(row.pid),
+        };
+        iced::widget::mouse_area(
+            container(content)
+                .width(Length::Fill)
+                .height(ROW_HEIGHT - 2.0 * CELL_PADDING)
+                .align_y(iced::alignment::Vertical::Center),
+        )
+        .on_press(on_press)
+        .into()
     }
 
     fn width(&self) -> f32 {
@@ -232,25 +1160,148 @@ impl<'a> table::Column<'a, ProcessTableMessage, Theme, Renderer> for ProcessColu
     }
 }
 
-pub fn collect_processes(sys: &System) -> Vec<ProcessInfo> {
+/// CPU usage shown for a row. Collapsed tree rows stand in for their
+/// whole subtree, so they show the subtree total.
+pub fn shown_cpu(row: &ProcessInfo) -> f32 {
+    match &row.tree {
+        Some(node) if node.has_children && !node.expanded => node.subtree_cpu,
+        _ => row.cpu_usage,
+    }
+}
+
+/// Memory shown for a row, with the subtree total for collapsed tree rows.
+pub fn shown_memory(row: &ProcessInfo) -> u64 {
+    match &row.tree {
+        Some(node) if node.has_children && !node.expanded => node.subtree_memory,
+        _ => row.memory_bytes,
+    }
+}
+
+/// A column's value as the table shows it. Values the platform doesn't
+/// report are shown as a dash, and a group row's PID is left empty since
+/// it's just its first member's.
+pub fn cell_text(kind: ProcessColumnKind, row: &ProcessInfo, units: Units) -> String {
+    fn or_dash(value: Option<impl ToString>) -> String {
+        value.map_or_else(|| "—".to_string(), |value| value.to_string())
+    }
+
+    match kind {
+        ProcessColumnKind::Pid if row.group.is_some() => String::new(),
+        ProcessColumnKind::Pid => row.pid.to_string(),
+        ProcessColumnKind::Name => row.name.clone(),
+        ProcessColumnKind::CpuUsage => format!("{:.1}%", shown_cpu(row)),
+        ProcessColumnKind::MemoryUsage => format_bytes(shown_memory(row), units),
+        ProcessColumnKind::DiskUsage => format!(
+            "{}/s",
+            format_bytes(row.disk_read_rate + row.disk_write_rate, units)
+        ),
+        ProcessColumnKind::Nice => or_dash(row.nice),
+        ProcessColumnKind::IoPriority => or_dash(effective_io_priority(row)),
+        ProcessColumnKind::User => or_dash(row.user.as_ref()),
+        ProcessColumnKind::Status => row.status.clone(),
+        ProcessColumnKind::Threads => or_dash(row.threads),
+        ProcessColumnKind::VirtualMemory => format_bytes(row.virtual_memory, units),
+        ProcessColumnKind::StartTime => format_timestamp(row.start_time),
+        ProcessColumnKind::CpuTime => format_duration(row.cpu_time / 1000),
+        ProcessColumnKind::CommandLine => row.command_line.clone(),
+        ProcessColumnKind::Executable => or_dash(row.exe.as_ref()),
+        ProcessColumnKind::ParentPid => or_dash(row.parent_pid),
+        ProcessColumnKind::Session => or_dash(row.session_id),
+        ProcessColumnKind::DiskRead => format!("{}/s", format_bytes(row.disk_read_rate, units)),
+        ProcessColumnKind::DiskWrite => {
+            format!("{}/s", format_bytes(row.disk_write_rate, units))
+        }
+        ProcessColumnKind::OpenFiles => or_dash(row.open_files),
+    }
+}
+
+/// Columns that take a read per process on top of what sysinfo gathers,
+/// so they're only filled in while shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SlowColumns {
+    /// Counting open files reads a directory per process.
+    pub open_files: bool,
+    /// Nice and I/O priority each take a system call per process.
+    pub priorities: bool,
+}
+
+impl SlowColumns {
+    pub fn among(columns: impl IntoIterator<Item = ProcessColumnKind>) -> Self {
+        columns
+            .into_iter()
+            .fold(Self::default(), |slow, kind| match kind {
+                ProcessColumnKind::OpenFiles => Self {
+                    open_files: true,
+                    ..slow
+                },
+                ProcessColumnKind::Nice | ProcessColumnKind::IoPriority => Self {
+                    priorities: true,
+                    ..slow
+                },
+                _ => slow,
+            })
+    }
+}
+
+/// Builds one row per process. Threads, which sysinfo lists next to
+/// processes on Linux, are skipped and counted in the Threads column
+/// instead. The slow columns are left empty unless asked for. Disk bytes
+/// are those since the previous refresh; `Sampler` turns them into rates.
+pub fn collect_processes(sys: &System, users: &Users, slow: SlowColumns) -> Vec<ProcessInfo> {
     let mut procs: Vec<ProcessInfo> = sys
         .processes()
         .values()
+        .filter(|p| p.thread_kind().is_none())
         .map(|p| ProcessInfo {
             pid: p.pid().as_u32(),
+            parent_pid: p.parent().map(|parent| parent.as_u32()),
             name: p.name().to_string_lossy().to_string(),
             cpu_usage: p.cpu_usage(),
             memory_bytes: p.memory(),
-            disk_read_bytes: p.disk_usage().read_bytes,
-            disk_written_bytes: p.disk_usage().written_bytes,
+            disk_read_rate: p.disk_usage().read_bytes,
+            disk_write_rate: p.disk_usage().written_bytes,
+            nice: slow
+                .priorities
+                .then(|| priority::nice(p.pid().as_u32()).ok())
+                .flatten(),
+            io_priority: slow
+                .priorities
+                .then(|| priority::io_priority(p.pid().as_u32()).ok())
+                .flatten(),
+            user: p
+                .user_id()
+                .and_then(|uid| users.get_user_by_id(uid))
+                .map(|user| user.name().to_string()),
+            status: p.status().to_string(),
+            // sysinfo lists every thread except the main one as a task.
+            threads: p.tasks().map(|tasks| tasks.len() + 1),
+            virtual_memory: p.virtual_memory(),
+            start_time: p.start_time(),
+            cpu_time: p.accumulated_cpu_time(),
+            command_line: p
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" "),
+            exe: p.exe().map(|exe| exe.display().to_string()),
+            session_id: p.session_id().map(|session| session.as_u32()),
+            open_files: if slow.open_files {
+                p.open_files()
+            } else {
+                None
+            },
+            tree: None,
+            group: None,
+            pinned: false,
         })
         .collect();
 
-    procs.sort_by(|a, b| {
-        b.cpu_usage
-            .partial_cmp(&a.cpu_usage)
-            .unwrap_or(Ordering::Equal)
-    });
+    sort_processes(
+        &mut procs,
+        ProcessColumnKind::CpuUsage,
+        SortOrder::Descending,
+    );
 
     procs
 }
@@ -0,0 +1,181 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::process_table::{ProcessColumnKind, ProcessInfo, SortOrder, compare_processes};
+
+/// Where a row sits in the process tree. Only set while the table is in
+/// tree mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    pub depth: usize,
+    pub has_children: bool,
+    pub expanded: bool,
+    /// CPU usage of the process and all of its descendants.
+    pub subtree_cpu: f32,
+    /// Memory of the process and all of its descendants.
+    pub subtree_memory: u64,
+}
+
+/// Flattens `rows` into tree order: every process follows its parent,
+/// siblings are sorted by the given column and children of collapsed
+/// processes are left out.
+///
+/// When `matches` is given only matching processes and their ancestors are
+/// kept, and everything is expanded so no match can hide under a collapsed
+/// parent.
+pub fn build_tree(
+    rows: &[ProcessInfo],
+    collapsed: &HashSet<u32>,
+    column: ProcessColumnKind,
+    order: SortOrder,
+    matches: Option<&dyn Fn(&ProcessInfo) -> bool>,
+) -> Vec<ProcessInfo> {
+    let index: HashMap<u32, usize> = rows.iter().enumerate().map(|(i, r)| (r.pid, i)).collect();
+
+    let mut children: HashMap<u32, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        match row.parent_pid {
+            Some(parent) if parent != row.pid && index.contains_key(&parent) => {
+                children.entry(parent).or_default().push(i)
+            }
+            _ => roots.push(i),
+        }
+    }
+
+    // Processes whose parents form a cycle can't be reached from any root,
+    // so each cycle is broken at its lowest PID.
+    let mut reached = HashSet::new();
+    for &root in &roots {
+        reach(rows, &children, root, &mut reached);
+    }
+    let mut stranded: Vec<usize> = (0..rows.len()).filter(|i| !reached.contains(i)).collect();
+    stranded.sort_by_key(|&i| rows[i].pid);
+    for i in stranded {
+        if reached.contains(&i) {
+            continue;
+        }
+        if let Some(siblings) = rows[i].parent_pid.and_then(|p| children.get_mut(&p)) {
+            siblings.retain(|&k| k != i);
+        }
+        roots.push(i);
+        reach(rows, &children, i, &mut reached);
+    }
+
+    let visible: Option<HashSet<u32>> = matches.map(|matches| {
+        let mut visible = HashSet::new();
+        for row in rows.iter().filter(|r| matches(r)) {
+            let mut current = Some(row);
+            // `insert` returning false also stops us on a parent cycle.
+            while let Some(row) = current
+                && visible.insert(row.pid)
+            {
+                current = row
+                    .parent_pid
+                    .and_then(|parent| index.get(&parent))
+                    .map(|&i| &rows[i]);
+            }
+        }
+        visible
+    });
+
+    let sort = |indices: &mut Vec<usize>| {
+        indices.sort_by(|&a, &b| compare_processes(&rows[a], &rows[b], column, order));
+    };
+    sort(&mut roots);
+    for siblings in children.values_mut() {
+        sort(siblings);
+    }
+
+    let mut totals = HashMap::new();
+    for &root in &roots {
+        subtree_totals(rows, &children, root, &mut totals, &mut HashSet::new());
+    }
+
+    let mut flattened = Vec::new();
+    let mut stack: Vec<(usize, usize)> = roots.iter().rev().map(|&i| (i, 0)).collect();
+    let mut seen = HashSet::new();
+
+    while let Some((i, depth)) = stack.pop() {
+        let row = &rows[i];
+        if !seen.insert(row.pid) {
+            continue;
+        }
+
+        let is_visible = |pid: &u32| visible.as_ref().is_none_or(|v| v.contains(pid));
+        if !is_visible(&row.pid) {
+            continue;
+        }
+
+        let kids: Vec<usize> = children
+            .get(&row.pid)
+            .map(|kids| {
+                kids.iter()
+                    .copied()
+                    .filter(|&k| is_visible(&rows[k].pid))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let expanded = visible.is_some() || !collapsed.contains(&row.pid);
+        let (subtree_cpu, subtree_memory) = totals
+            .get(&row.pid)
+            .copied()
+            .unwrap_or((row.cpu_usage, row.memory_bytes));
+
+        let mut node = row.clone();
+        node.tree = Some(TreeNode {
+            depth,
+            has_children: !kids.is_empty(),
+            expanded,
+            subtree_cpu,
+            subtree_memory,
+        });
+        flattened.push(node);
+
+        if expanded {
+            stack.extend(kids.iter().rev().map(|&k| (k, depth + 1)));
+        }
+    }
+
+    flattened
+}
+
+/// Marks `i` and everything below it as reached.
+fn reach(
+    rows: &[ProcessInfo],
+    children: &HashMap<u32, Vec<usize>>,
+    i: usize,
+    reached: &mut HashSet<usize>,
+) {
+    let mut stack = vec![i];
+    while let Some(i) = stack.pop() {
+        if reached.insert(i) {
+            stack.extend(children.get(&rows[i].pid).into_iter().flatten());
+        }
+    }
+}
+
+fn subtree_totals(
+    rows: &[ProcessInfo],
+    children: &HashMap<u32, Vec<usize>>,
+    i: usize,
+    totals: &mut HashMap<u32, (f32, u64)>,
+    visiting: &mut HashSet<u32>,
+) -> (f32, u64) {
+    let row = &rows[i];
+    if let Some(&total) = totals.get(&row.pid) {
+        return total;
+    }
+    if !visiting.insert(row.pid) {
+        return (0.0, 0);
+    }
+
+    let mut total = (row.cpu_usage, row.memory_bytes);
+    for &child in children.get(&row.pid).into_iter().flatten() {
+        let (cpu, memory) = subtree_totals(rows, children, child, totals, visiting);
+        total.0 += cpu;
+        total.1 += memory;
+    }
+
+    totals.insert(row.pid, total);
+    total
+}
@@ -0,0 +1,577 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::process_table::ProcessInfo;
+
+/// A parsed search box query such as
+/// `cpu>20 mem>500MB user:root name~"^cargo"`.
+///
+/// Terms next to each other must all match; `OR`, `NOT` (also `||`, `!`)
+/// and parentheses combine them otherwise. A bare word matches the name or
+/// PID like the plain search always did.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    expr: Option<Expr>,
+}
+
+/// Why a query couldn't be parsed, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    /// Character offset into the query.
+    pub position: usize,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at character {})", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    /// Lowercase text to find in the name or PID.
+    Word(String),
+    Number {
+        field: Field,
+        comparison: Comparison,
+        value: f64,
+    },
+    Text {
+        field: Field,
+        matcher: TextMatcher,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum TextMatcher {
+    /// Case-insensitive substring, lowercase.
+    Contains(String),
+    /// Case-insensitive equality, lowercase.
+    Equals(String),
+    NotEquals(String),
+    Regex(Regex),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn holds(self, left: f64, right: f64) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Pid,
+    ParentPid,
+    Name,
+    Cpu,
+    Memory,
+    VirtualMemory,
+    Disk,
+    DiskRead,
+    DiskWrite,
+    User,
+    Status,
+    Threads,
+    Command,
+    Executable,
+    Session,
+    Nice,
+    OpenFiles,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Count,
+    Percent,
+    Bytes,
+    Text,
+}
+
+/// Field names accepted in queries, with aliases.
+const FIELDS: [(&str, Field); 21] = [
+    ("pid", Field::Pid),
+    ("ppid", Field::ParentPid),
+    ("name", Field::Name),
+    ("cpu", Field::Cpu),
+    ("mem", Field::Memory),
+    ("memory", Field::Memory),
+    ("vmem", Field::VirtualMemory),
+    ("virtual", Field::VirtualMemory),
+    ("disk", Field::Disk),
+    ("read", Field::DiskRead),
+    ("write", Field::DiskWrite),
+    ("user", Field::User),
+    ("status", Field::Status),
+    ("threads", Field::Threads),
+    ("cmd", Field::Command),
+    ("command", Field::Command),
+    ("exe", Field::Executable),
+    ("session", Field::Session),
+    ("nice", Field::Nice),
+    ("files", Field::OpenFiles),
+    ("fd", Field::OpenFiles),
+];
+
+impl Field {
+    fn kind(self) -> FieldKind {
+        match self {
+            Field::Pid
+            | Field::ParentPid
+            | Field::Threads
+            | Field::Session
+            | Field::Nice
+            | Field::OpenFiles => FieldKind::Count,
+            Field::Cpu => FieldKind::Percent,
+            Field::Memory
+            | Field::VirtualMemory
+            | Field::Disk
+            | Field::DiskRead
+            | Field::DiskWrite => FieldKind::Bytes,
+            Field::Name | Field::User | Field::Status | Field::Command | Field::Executable => {
+                FieldKind::Text
+            }
+        }
+    }
+
+    /// `None` where the platform didn't report the value.
+    fn number(self, row: &ProcessInfo) -> Option<f64> {
+        match self {
+            Field::Pid => Some(row.pid as f64),
+            Field::ParentPid => row.parent_pid.map(f64::from),
+            Field::Cpu => Some(row.cpu_usage as f64),
+            Field::Memory => Some(row.memory_bytes as f64),
+            Field::VirtualMemory => Some(row.virtual_memory as f64),
+            Field::Disk => Some((row.disk_read_rate + row.disk_write_rate) as f64),
+            Field::DiskRead => Some(row.disk_read_rate as f64),
+            Field::DiskWrite => Some(row.disk_write_rate as f64),
+            Field::Threads => row.threads.map(|threads| threads as f64),
+            Field::Session => row.session_id.map(f64::from),
+            Field::Nice => row.nice.map(f64::from),
+            Field::OpenFiles => row.open_files.map(|files| files as f64),
+            Field::Name | Field::User | Field::Status | Field::Command | Field::Executable => None,
+        }
+    }
+
+    fn text(self, row: &ProcessInfo) -> Option<&str> {
+        match self {
+            Field::Name => Some(&row.name),
+            Field::User => row.user.as_deref(),
+            Field::Status => Some(&row.status),
+            Field::Command => Some(&row.command_line),
+            Field::Executable => row.exe.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Self, QueryError> {
+        let tokens = tokenize(source)?;
+        let end = source.chars().count();
+        let mut parser = Parser {
+            tokens,
+            next: 0,
+            end,
+        };
+        if parser.tokens.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(Self { expr: Some(expr) }),
+            Some((Token::RightParen, position)) => Err(error("unmatched `)`", *position)),
+            Some((_, position)) => Err(error("expected `AND` or `OR`", *position)),
+        }
+    }
+
+    /// Whether the query matches every process.
+    pub fn is_empty(&self) -> bool {
+        self.expr.is_none()
+    }
+
+    pub fn matches(&self, row: &ProcessInfo) -> bool {
+        self.expr.as_ref().is_none_or(|expr| expr.matches(row))
+    }
+}
+
+impl Expr {
+    fn matches(&self, row: &ProcessInfo) -> bool {
+        match self {
+            Expr::And(left, right) => left.matches(row) && right.matches(row),
+            Expr::Or(left, right) => left.matches(row) || right.matches(row),
+            Expr::Not(expr) => !expr.matches(row),
+            Expr::Term(term) => term.matches(row),
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, row: &ProcessInfo) -> bool {
+        match self {
+            Term::Word(word) => {
+                row.name.to_lowercase().contains(word) || row.pid.to_string().contains(word)
+            }
+            Term::Number {
+                field,
+                comparison,
+                value,
+            } => field
+                .number(row)
+                .is_some_and(|number| comparison.holds(number, *value)),
+            Term::Text { field, matcher } => field.text(row).is_some_and(|text| match matcher {
+                TextMatcher::Contains(needle) => text.to_lowercase().contains(needle),
+                TextMatcher::Equals(value) => text.to_lowercase() == *value,
+                TextMatcher::NotEquals(value) => text.to_lowercase() != *value,
+                TextMatcher::Regex(regex) => regex.is_match(text),
+            }),
+        }
+    }
+}
+
+fn error(message: impl Into<String>, position: usize) -> QueryError {
+    QueryError {
+        message: message.into(),
+        position,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    And,
+    Or,
+    Not,
+    /// A bare or quoted word.
+    Word(String),
+    /// `field op value`, with the positions of the operator and value for
+    /// error messages.
+    Filter {
+        field: String,
+        operator: &'static str,
+        operator_at: usize,
+        value: String,
+        value_at: usize,
+    },
+}
+
+/// Longest first, so `>=` isn't read as `>`.
+const OPERATORS: [&str; 8] = [">=", "<=", "!=", ">", "<", "=", ":", "~"];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let two: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        let token = match c {
+            '(' => {
+                i += 1;
+                Token::LeftParen
+            }
+            ')' => {
+                i += 1;
+                Token::RightParen
+            }
+            _ if two == "&&" => {
+                i += 2;
+                Token::And
+            }
+            _ if two == "||" => {
+                i += 2;
+                Token::Or
+            }
+            '!' => {
+                i += 1;
+                Token::Not
+            }
+            '"' => Token::Word(quoted(&chars, &mut i)?),
+            _ => {
+                let mut end = i;
+                while end < chars.len() && (chars[end].is_ascii_alphabetic() || chars[end] == '_') {
+                    end += 1;
+                }
+                let rest: String = chars[end..chars.len().min(end + 2)].iter().collect();
+                let operator = OPERATORS.into_iter().find(|op| rest.starts_with(op));
+
+                match operator {
+                    Some(operator) if end > i => {
+                        let field = chars[i..end].iter().collect();
+                        let operator_at = end;
+                        i = end + operator.chars().count();
+                        let value_at = i;
+                        let value = if chars.get(i) == Some(&'"') {
+                            quoted(&chars, &mut i)?
+                        } else {
+                            bare(&chars, &mut i)
+                        };
+                        Token::Filter {
+                            field,
+                            operator,
+                            operator_at,
+                            value,
+                            value_at,
+                        }
+                    }
+                    _ => {
+                        let word = bare(&chars, &mut i);
+                        match word.to_ascii_lowercase().as_str() {
+                            "and" => Token::And,
+                            "or" => Token::Or,
+                            "not" => Token::Not,
+                            _ => Token::Word(word),
+                        }
+                    }
+                }
+            }
+        };
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+/// Reads up to the next space or parenthesis.
+fn bare(chars: &[char], i: &mut usize) -> String {
+    let start = *i;
+    while *i < chars.len() && !chars[*i].is_whitespace() && !matches!(chars[*i], '(' | ')') {
+        *i += 1;
+    }
+    chars[start..*i].iter().collect()
+}
+
+/// Reads a double-quoted string starting at `i`; `\"` and `\\` are
+/// escapes.
+fn quoted(chars: &[char], i: &mut usize) -> Result<String, QueryError> {
+    let start = *i;
+    let mut value = String::new();
+    *i += 1;
+    while let Some(&c) = chars.get(*i) {
+        *i += 1;
+        match c {
+            '"' => return Ok(value),
+            '\\' if matches!(chars.get(*i), Some('"' | '\\')) => {
+                value.push(chars[*i]);
+                *i += 1;
+            }
+            _ => value.push(c),
+        }
+    }
+    Err(error("unclosed quote", start))
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+    /// Length of the query, where "expected …" errors at the end point.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.next)
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.and()?;
+        while let Some((Token::Or, _)) = self.peek() {
+            self.next += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.not()?;
+        loop {
+            match self.peek() {
+                Some((Token::And, _)) => self.next += 1,
+                Some((Token::Or | Token::RightParen, _)) | None => return Ok(expr),
+                // Terms next to each other are implicitly ANDed.
+                Some(_) => {}
+            }
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+    }
+
+    fn not(&mut self) -> Result<Expr, QueryError> {
+        if let Some((Token::Not, _)) = self.peek() {
+            self.next += 1;
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        let Some((token, position)) = self.tokens.get(self.next).cloned() else {
+            return Err(error("expected a search term", self.end));
+        };
+        self.next += 1;
+
+        match token {
+            Token::LeftParen => {
+                let expr = self.or()?;
+                match self.peek() {
+                    Some((Token::RightParen, _)) => {
+                        self.next += 1;
+                        Ok(expr)
+                    }
+                    _ => Err(error("unclosed `(`", position)),
+                }
+            }
+            Token::Word(word) => Ok(Expr::Term(Term::Word(word.to_lowercase()))),
+            Token::Filter {
+                field,
+                operator,
+                operator_at,
+                value,
+                value_at,
+            } => filter(&field, position, operator, operator_at, &value, value_at).map(Expr::Term),
+            Token::RightParen => Err(error("unmatched `)`", position)),
+            Token::And | Token::Or | Token::Not => Err(error("expected a search term", position)),
+        }
+    }
+}
+
+fn filter(
+    name: &str,
+    position: usize,
+    operator: &str,
+    operator_at: usize,
+    value: &str,
+    value_at: usize,
+) -> Result<Term, QueryError> {
+    let field = FIELDS
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map(|(_, field)| *field)
+        .ok_or_else(|| error(format!("unknown field `{name}`"), position))?;
+    if value.is_empty() {
+        return Err(error(format!("`{name}{operator}` needs a value"), value_at));
+    }
+
+    let kind = field.kind();
+    if kind == FieldKind::Text {
+        let lowercase = value.to_lowercase();
+        let matcher = match operator {
+            ":" => TextMatcher::Contains(lowercase),
+            "=" => TextMatcher::Equals(lowercase),
+            "!=" => TextMatcher::NotEquals(lowercase),
+            "~" => RegexBuilder::new(value)
+                .case_insensitive(true)
+                .build()
+                .map(TextMatcher::Regex)
+                .map_err(|err| error(format!("invalid regex: {err}"), value_at))?,
+            _ => {
+                return Err(error(
+                    format!("`{operator}` needs a number, but `{name}` is text"),
+                    operator_at,
+                ));
+            }
+        };
+        return Ok(Term::Text { field, matcher });
+    }
+
+    let comparison = match operator {
+        "<" => Comparison::Less,
+        "<=" => Comparison::LessOrEqual,
+        ">" => Comparison::Greater,
+        ">=" => Comparison::GreaterOrEqual,
+        ":" | "=" => Comparison::Equal,
+        "!=" => Comparison::NotEqual,
+        _ => {
+            return Err(error(
+                format!("`{operator}` only works on text fields"),
+                operator_at,
+            ));
+        }
+    };
+    let value = match kind {
+        FieldKind::Bytes => parse_bytes(value),
+        FieldKind::Percent => value.strip_suffix('%').unwrap_or(value).parse().ok(),
+        _ => value.parse().ok(),
+    }
+    .filter(|value: &f64| value.is_finite())
+    .ok_or_else(|| {
+        let hint = if kind == FieldKind::Bytes {
+            " (try 500MB or 1.5GiB)"
+        } else {
+            ""
+        };
+        error(format!("`{value}` is not a number{hint}"), value_at)
+    })?;
+
+    Ok(Term::Number {
+        field,
+        comparison,
+        value,
+    })
+}
+
+/// Parses a size like `512`, `500MB` or `1.5GiB` into bytes. `KB`, `MB`,
+/// … are powers of 1000 and `KiB`, `MiB`, … powers of 1024, as in the
+/// Units setting; a lone `K`, `M`, `G` or `T` is taken as binary.
+pub fn parse_bytes(value: &str) -> Option<f64> {
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+
+    let unit = unit.to_ascii_lowercase();
+    let multiplier = match unit.as_str() {
+        "" | "b" => 1.0,
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        "k" | "kib" => 1024.0,
+        "m" | "mib" => 1024.0_f64.powi(2),
+        "g" | "gib" => 1024.0_f64.powi(3),
+        "t" | "tib" => 1024.0_f64.powi(4),
+        _ => return None,
+    };
+    Some(number * multiplier)
+}
+
+/// Quotes `value` so it reads back as a single query value, whatever
+/// spaces, parentheses or quotes it contains.
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,405 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{
+    CpuRefreshKind, Disks, Networks, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind,
+    Users,
+};
+
+use crate::cpus::{CoreState, sync_cores};
+use crate::disks::{DiskState, sync_disks};
+use crate::history::Retention;
+use crate::networks::{NetworkState, sync_networks};
+use crate::process_table::{ProcessInfo, SlowColumns, collect_processes};
+use crate::utilities::memory_usage_percent;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoreSample {
+    pub name: String,
+    pub usage: f32,
+    /// In GHz.
+    pub frequency: f32,
+}
+
+impl From<&CoreState> for CoreSample {
+    fn from(core: &CoreState) -> Self {
+        Self {
+            name: core.name.clone(),
+            usage: core.usage,
+            frequency: core.frequency,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskSample {
+    pub name: String,
+    pub mount_point: PathBuf,
+    pub file_system: String,
+    pub is_removable: bool,
+    pub total_space: u64,
+    pub available_space: u64,
+    /// Throughput in MB/s.
+    pub usage: f64,
+}
+
+impl From<&DiskState> for DiskSample {
+    fn from(disk: &DiskState) -> Self {
+        Self {
+            name: disk.name.clone(),
+            mount_point: disk.mount_point.clone(),
+            file_system: disk.file_system.clone(),
+            is_removable: disk.is_removable,
+            total_space: disk.total_space,
+            available_space: disk.available_space,
+            usage: disk.usage,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkSample {
+    pub name: String,
+    pub mac_address: String,
+    pub ip_addresses: Vec<String>,
+    /// Rates in KB/s.
+    pub received: f64,
+    pub transmitted: f64,
+    pub total_received: u64,
+    pub total_transmitted: u64,
+    pub packets_received: u64,
+    pub packets_transmitted: u64,
+    pub errors_received: u64,
+    pub errors_transmitted: u64,
+}
+
+impl From<&NetworkState> for NetworkSample {
+    fn from(network: &NetworkState) -> Self {
+        Self {
+            name: network.name.clone(),
+            mac_address: network.mac_address.clone(),
+            ip_addresses: network.ip_addresses.clone(),
+            received: network.received,
+            transmitted: network.transmitted,
+            total_received: network.total_received,
+            total_transmitted: network.total_transmitted,
+            packets_received: network.packets_received,
+            packets_transmitted: network.packets_transmitted,
+            errors_received: network.errors_received,
+            errors_transmitted: network.errors_transmitted,
+        }
+    }
+}
+
+/// Serialized field names match session `Frame` version 1, where the
+/// snapshot is flattened next to the other readings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuSnapshot {
+    /// Usage across all cores, in percent.
+    #[serde(rename = "cpu_usage")]
+    pub usage: f32,
+    pub cores: Vec<CoreSample>,
+}
+
+impl CpuSnapshot {
+    /// Mean frequency across all cores, in GHz.
+    pub fn frequency(&self) -> f32 {
+        if self.cores.is_empty() {
+            0.0
+        } else {
+            self.cores.iter().map(|c| c.frequency).sum::<f32>() / self.cores.len() as f32
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemorySnapshot {
+    #[serde(rename = "used_memory")]
+    pub used: u64,
+    #[serde(rename = "total_memory")]
+    pub total: u64,
+}
+
+impl MemorySnapshot {
+    pub fn usage_percent(&self) -> f64 {
+        memory_usage_percent(self.used as f64, self.total as f64)
+    }
+}
+
+/// One sample of the whole system. Disk and network rates are per second
+/// over `interval`, so they stay right however often samples are taken.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemSnapshot {
+    /// Time since the previous sample, which the rates cover. Not
+    /// recorded, as replays only need the rates.
+    #[serde(skip)]
+    pub interval: Option<Duration>,
+    #[serde(flatten)]
+    pub cpu: CpuSnapshot,
+    #[serde(flatten)]
+    pub memory: MemorySnapshot,
+    /// System uptime in seconds.
+    pub uptime: u64,
+    pub disks: Vec<DiskSample>,
+    pub networks: Vec<NetworkSample>,
+    /// Empty unless asked for, as reading every process is the slow part.
+    /// Disk I/O is per second since the previous sample that included
+    /// processes.
+    pub processes: Vec<ProcessInfo>,
+}
+
+/// Takes snapshots of the system. It keeps the previous counters, so
+/// usage and rates are measured between two calls to `sample`.
+pub struct Sampler {
+    pub sys: System,
+    pub users: Users,
+    /// Which of the slow process columns get filled in.
+    pub slow_columns: SlowColumns,
+    disks: Disks,
+    networks: Networks,
+    cores: Vec<CoreState>,
+    disk_states: Vec<DiskState>,
+    network_states: Vec<NetworkState>,
+    last: Instant,
+    /// When processes were last refreshed, and the time that refresh
+    /// measured their disk I/O over.
+    processes_at: Instant,
+    process_interval: Duration,
+}
+
+/// Only the previous counters matter here, so the histories that come
+/// with the states stay short.
+const COUNTER_RETENTION: Retention = Retention {
+    recent: Duration::from_secs(1),
+    total: Duration::from_secs(1),
+    bucket_width: Duration::from_secs(1),
+};
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler {
+    /// Reads every counter once, so the first snapshot already measures
+    /// usage since now.
+    pub fn new() -> Self {
+        let sys = System::new_all();
+        let disks = Disks::new_with_refreshed_list();
+        let networks = Networks::new_with_refreshed_list();
+
+        let mut cores = Vec::new();
+        sync_cores(&mut cores, &sys, COUNTER_RETENTION);
+        let mut disk_states = Vec::new();
+        sync_disks(&mut disk_states, &disks, COUNTER_RETENTION);
+        let mut network_states = Vec::new();
+        sync_networks(&mut network_states, &networks, COUNTER_RETENTION);
+
+        Self {
+            sys,
+            users: Users::new_with_refreshed_list(),
+            slow_columns: SlowColumns::default(),
+            disks,
+            networks,
+            cores,
+            disk_states,
+            network_states,
+            last: Instant::now(),
+            processes_at: Instant::now(),
+            process_interval: Duration::ZERO,
+        }
+    }
+
+    /// Sleeps until enough time has passed since the previous sample for
+    /// CPU usage to be measured, as when sampling once from the command
+    /// line.
+    pub fn wait_until_ready(&self) {
+        if let Some(wait) = sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.checked_sub(self.last.elapsed()) {
+            std::thread::sleep(wait);
+        }
+    }
+
+    pub fn sample(&mut self, with_processes: bool) -> SystemSnapshot {
+        let now = Instant::now();
+        let interval = now.duration_since(self.last);
+        self.last = now;
+
+        // Frequencies change with load as much as usage does, so both are
+        // read on every sample.
+        self.sys
+            .refresh_cpu_specifics(CpuRefreshKind::nothing().with_cpu_usage().with_frequency());
+        self.sys.refresh_memory();
+        sync_cores(&mut self.cores, &self.sys, COUNTER_RETENTION);
+        self.disks.refresh(true);
+        sync_disks(&mut self.disk_states, &self.disks, COUNTER_RETENTION);
+        self.networks.refresh(true);
+        sync_networks(&mut self.network_states, &self.networks, COUNTER_RETENTION);
+
+        let processes = if with_processes {
+            self.process_interval = now.duration_since(self.processes_at);
+            self.processes_at = now;
+            // Command lines and owners don't change, so they're only read
+            // once per process.
+            self.sys.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::nothing()
+                    .with_memory()
+                    .with_cpu()
+                    .with_disk_usage()
+                    .with_exe(UpdateKind::OnlyIfNotSet)
+                    .with_cmd(UpdateKind::OnlyIfNotSet)
+                    .with_user(UpdateKind::OnlyIfNotSet)
+                    .with_tasks(),
+            );
+            self.processes()
+        } else {
+            Vec::new()
+        };
+
+        SystemSnapshot {
+            interval: Some(interval),
+            processes,
+            ..self.current()
+        }
+    }
+
+    /// The processes as of the last sample, with disk I/O in bytes per
+    /// second.
+    pub fn processes(&self) -> Vec<ProcessInfo> {
+        let mut processes = collect_processes(&self.sys, &self.users, self.slow_columns);
+        disk_rates(&mut processes, self.process_interval);
+        processes
+    }
+
+    /// The values read by the last sample, without reading anything new.
+    /// Processes are left out.
+    pub fn current(&self) -> SystemSnapshot {
+        SystemSnapshot {
+            interval: None,
+            cpu: CpuSnapshot {
+                usage: self.sys.global_cpu_usage(),
+                cores: self.cores.iter().map(Into::into).collect(),
+            },
+            memory: MemorySnapshot {
+                used: self.sys.used_memory(),
+                total: self.sys.total_memory(),
+            },
+            uptime: System::uptime(),
+            disks: self.disk_states.iter().map(Into::into).collect(),
+            networks: self.network_states.iter().map(Into::into).collect(),
+            processes: Vec::new(),
+        }
+    }
+}
+
+/// Turns the bytes each process read and wrote over `interval` into bytes
+/// per second. An empty interval leaves nothing to measure.
+pub fn disk_rates(processes: &mut [ProcessInfo], interval: Duration) {
+    let seconds = interval.as_secs_f64();
+    let rate = |bytes: u64| {
+        if seconds > 0.0 {
+            (bytes as f64 / seconds).round() as u64
+        } else {
+            0
+        }
+    };
+    for process in processes {
+        process.disk_read_rate = rate(process.disk_read_rate);
+        process.disk_write_rate = rate(process.disk_write_rate);
+    }
+}
+
+/// Like `sync_cores`, but from a snapshot, live or recorded.
+pub fn record_cores(
+    cores: &mut Vec<CoreState>,
+    samples: &[CoreSample],
+    time: Instant,
+    retention: Retention,
+) {
+    if cores.len() != samples.len()
+        || cores
+            .iter()
+            .zip(samples)
+            .any(|(core, s)| core.name != s.name)
+    {
+        *cores = samples
+            .iter()
+            .map(|s| CoreState::new(s.name.clone(), retention))
+            .collect();
+    }
+
+    for (core, sample) in cores.iter_mut().zip(samples) {
+        core.record_at(time, sample.usage, sample.frequency);
+    }
+}
+
+/// Like `sync_disks`, but from a snapshot, live or recorded.
+pub fn record_disks(
+    states: &mut Vec<DiskState>,
+    samples: &[DiskSample],
+    time: Instant,
+    retention: Retention,
+) {
+    states.retain(|s| samples.iter().any(|d| d.mount_point == s.mount_point));
+
+    for sample in samples {
+        let index = match states
+            .iter()
+            .position(|s| s.mount_point == sample.mount_point)
+        {
+            Some(index) => index,
+            None => {
+                states.push(DiskState::new(
+                    sample.name.clone(),
+                    sample.mount_point.clone(),
+                    retention,
+                ));
+                states.len() - 1
+            }
+        };
+
+        let state = &mut states[index];
+        state.name = sample.name.clone();
+        state.file_system = sample.file_system.clone();
+        state.is_removable = sample.is_removable;
+        state.total_space = sample.total_space;
+        state.available_space = sample.available_space;
+        state.push_usage_at(time, sample.usage);
+    }
+}
+
+/// Like `sync_networks`, but from a snapshot, live or recorded.
+pub fn record_networks(
+    states: &mut Vec<NetworkState>,
+    samples: &[NetworkSample],
+    time: Instant,
+    retention: Retention,
+) {
+    states.retain(|s| samples.iter().any(|n| n.name == s.name));
+
+    for sample in samples {
+        let index = match states.iter().position(|s| s.name == sample.name) {
+            Some(index) => index,
+            None => {
+                states.push(NetworkState::new(sample.name.clone(), retention));
+                states.len() - 1
+            }
+        };
+
+        let state = &mut states[index];
+        state.mac_address = sample.mac_address.clone();
+        state.ip_addresses = sample.ip_addresses.clone();
+        state.total_received = sample.total_received;
+        state.total_transmitted = sample.total_transmitted;
+        state.packets_received = sample.packets_received;
+        state.packets_transmitted = sample.packets_transmitted;
+        state.errors_received = sample.errors_received;
+        state.errors_transmitted = sample.errors_transmitted;
+        state.push_rates_at(time, sample.received, sample.transmitted);
+    }
+
+    states.sort_by(|a, b| a.name.cmp(&b.name));
+}
@@ -0,0 +1,495 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use flate2::Compression;
+use flate2::bufread::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::history::Retention;
+use crate::process_table::ProcessInfo;
+use crate::sampler::SystemSnapshot;
+
+/// Written in every session header so other files are recognised.
+pub const FORMAT: &str = "task-manager-session";
+
+/// Bumped whenever `Frame` changes in a way older readers can't handle.
+pub const VERSION: u32 = 1;
+
+/// Frames are written out in a gzip member of their own once this many
+/// have been buffered or `FLUSH_INTERVAL` has passed, whichever is first.
+/// A crash loses at most the frames since then.
+pub const FLUSH_FRAMES: usize = 60;
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// File extension offered by the record and open dialogs.
+pub const EXTENSION: &str = "tmsession";
+
+/// First line of a session file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    pub format: String,
+    pub version: u32,
+    /// Seconds since the Unix epoch when recording started.
+    pub started: u64,
+}
+
+impl Header {
+    pub fn new(started: u64) -> Self {
+        Self {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            started,
+        }
+    }
+}
+
+/// Everything shown for one tick. Rates are stored as computed when
+/// recording, so any frame can be shown without the ones before it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Frame {
+    /// Milliseconds since recording started, stamped by the recorder.
+    pub elapsed_ms: u64,
+    #[serde(flatten)]
+    pub snapshot: SystemSnapshot,
+}
+
+impl From<SystemSnapshot> for Frame {
+    fn from(snapshot: SystemSnapshot) -> Self {
+        Self {
+            elapsed_ms: 0,
+            snapshot,
+        }
+    }
+}
+
+impl Frame {
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_millis(self.elapsed_ms)
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(io::Error),
+    /// The file isn't a session recording.
+    NotASession,
+    /// Recorded by a newer version of the app.
+    UnsupportedVersion(u32),
+    /// The header is there but no tick was recorded.
+    Empty,
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::Io(err) => write!(f, "{err}"),
+            SessionError::NotASession => write!(f, "not a task manager recording"),
+            SessionError::UnsupportedVersion(version) => write!(
+                f,
+                "recorded in format version {version}, but only version {VERSION} is supported"
+            ),
+            SessionError::Empty => write!(f, "the recording holds no samples"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<io::Error> for SessionError {
+    fn from(err: io::Error) -> Self {
+        SessionError::Io(err)
+    }
+}
+
+/// Appends frames to a session file: gzip members holding JSON lines, a
+/// header and then one frame per tick. Frames are buffered and written out
+/// as a complete member every `FLUSH_FRAMES` frames or `FLUSH_INTERVAL`, so
+/// a recording cut short by a crash still loads up to the last flush, and
+/// a replay can start decoding at any member.
+pub struct Recorder {
+    path: PathBuf,
+    file: BufWriter<File>,
+    /// Lines not yet written to the file.
+    chunk: GzEncoder<Vec<u8>>,
+    chunk_lines: usize,
+    flushed: Instant,
+    started: Instant,
+    frames: usize,
+}
+
+impl Recorder {
+    /// Creates or replaces the file at `path` and writes the header.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let mut recorder = Self {
+            path: path.to_path_buf(),
+            file: BufWriter::new(File::create(path)?),
+            chunk: GzEncoder::new(Vec::new(), Compression::default()),
+            chunk_lines: 0,
+            flushed: Instant::now(),
+            started: Instant::now(),
+            frames: 0,
+        };
+        recorder.write_line(&Header::new(started))?;
+        recorder.flush()?;
+        Ok(recorder)
+    }
+
+    pub fn append(&mut self, frame: Frame) -> io::Result<()> {
+        self.append_at(self.started.elapsed(), frame)
+    }
+
+    pub fn append_at(&mut self, elapsed: Duration, mut frame: Frame) -> io::Result<()> {
+        frame.elapsed_ms = elapsed.as_millis() as u64;
+        self.write_line(&frame)?;
+        self.frames += 1;
+        if self.chunk_lines >= FLUSH_FRAMES || self.flushed.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Writes out the buffered frames so the file is complete.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.flush()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    fn write_line(&mut self, value: &impl Serialize) -> io::Result<()> {
+        serde_json::to_writer(&mut self.chunk, value)?;
+        self.chunk.write_all(b"\n")?;
+        self.chunk_lines += 1;
+        Ok(())
+    }
+
+    /// Ends the buffered gzip member and writes it to the file.
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushed = Instant::now();
+        if self.chunk_lines == 0 {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(
+            &mut self.chunk,
+            GzEncoder::new(Vec::new(), Compression::default()),
+        );
+        self.chunk_lines = 0;
+        self.file.write_all(&chunk.finish()?)?;
+        self.file.flush()
+    }
+}
+
+/// Where a recording is read from when a frame's processes are needed.
+#[derive(Debug, Clone)]
+enum Source {
+    File(PathBuf),
+    Memory(Arc<[u8]>),
+}
+
+impl Source {
+    /// Opens the recording at the gzip member starting at `offset`.
+    fn open_at(&self, offset: u64) -> io::Result<Box<dyn Read + '_>> {
+        Ok(match self {
+            Source::File(path) => {
+                let mut file = File::open(path)?;
+                file.seek(SeekFrom::Start(offset))?;
+                Box::new(file)
+            }
+            Source::Memory(bytes) => {
+                let mut cursor = Cursor::new(&bytes[..]);
+                cursor.set_position(offset);
+                Box::new(cursor)
+            }
+        })
+    }
+}
+
+/// Where a frame sits in the file: the gzip member it was written in and
+/// its line within that member.
+#[derive(Debug, Clone, Copy)]
+struct Location {
+    offset: u64,
+    line: usize,
+}
+
+/// Counts the compressed bytes read, to find where each member starts.
+struct Counted<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+/// Only the part of a frame that isn't kept in memory.
+#[derive(Deserialize)]
+struct FrameProcesses {
+    processes: Vec<ProcessInfo>,
+}
+
+fn read_header(line: io::Result<String>) -> Result<Header, SessionError> {
+    let header = line
+        .ok()
+        .and_then(|line| serde_json::from_str::<Header>(&line).ok())
+        .filter(|header| header.format == FORMAT)
+        .ok_or(SessionError::NotASession)?;
+    if header.version > VERSION {
+        return Err(SessionError::UnsupportedVersion(header.version));
+    }
+    Ok(header)
+}
+
+/// A loaded recording. The system metrics of every frame are kept in
+/// memory, as the charts need them all; process lists, which make up most
+/// of the file, are read from it again when a frame is shown.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub header: Header,
+    /// Never empty. Process lists are left empty; see `processes`.
+    pub frames: Vec<Frame>,
+    /// Set when the file ends part way through a frame, as it does when
+    /// the app stopped without finishing the recording.
+    pub truncated: bool,
+    source: Source,
+    /// Parallel to `frames`.
+    locations: Vec<Location>,
+}
+
+impl Session {
+    pub fn load(path: &Path) -> Result<Self, SessionError> {
+        Self::read_from(File::open(path)?, Source::File(path.to_path_buf()))
+    }
+
+    /// Reads a recording held in memory.
+    pub fn read(mut reader: impl Read) -> Result<Self, SessionError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let bytes: Arc<[u8]> = bytes.into();
+        Self::read_from(&bytes[..], Source::Memory(bytes.clone()))
+    }
+
+    fn read_from(reader: impl Read, source: Source) -> Result<Self, SessionError> {
+        let mut reader = BufReader::new(Counted {
+            inner: reader,
+            count: 0,
+        });
+        let mut header: Option<Header> = None;
+        let mut frames = Vec::new();
+        let mut locations = Vec::new();
+        let mut truncated = false;
+
+        'members: while !reader.fill_buf()?.is_empty() {
+            let offset = reader.get_ref().count - reader.buffer().len() as u64;
+            let lines = BufReader::new(GzDecoder::new(&mut reader)).lines();
+            for (line_index, line) in lines.enumerate() {
+                if header.is_none() {
+                    header = Some(read_header(line)?);
+                    continue;
+                }
+
+                match line.map(|line| serde_json::from_str::<Frame>(&line)) {
+                    Ok(Ok(mut frame)) => {
+                        frame.snapshot.processes = Vec::new();
+                        frames.push(frame);
+                        locations.push(Location {
+                            offset,
+                            line: line_index,
+                        });
+                    }
+                    _ => {
+                        truncated = true;
+                        break 'members;
+                    }
+                }
+            }
+        }
+
+        let header = header.ok_or(SessionError::NotASession)?;
+        if frames.is_empty() {
+            return Err(SessionError::Empty);
+        }
+        Ok(Self {
+            header,
+            frames,
+            truncated,
+            source,
+            locations,
+        })
+    }
+
+    /// The processes recorded in the frame at `index`, decoded from the
+    /// file's gzip member holding it.
+    pub fn processes(&self, index: usize) -> Result<Vec<ProcessInfo>, SessionError> {
+        let location = self.locations[index];
+        let reader = BufReader::new(self.source.open_at(location.offset)?);
+        let line = BufReader::new(GzDecoder::new(reader))
+            .lines()
+            .nth(location.line)
+            .unwrap_or_else(|| Err(io::ErrorKind::UnexpectedEof.into()))?;
+        let frame: FrameProcesses = serde_json::from_str(&line).map_err(io::Error::from)?;
+        Ok(frame.processes)
+    }
+
+    /// Time from the first frame to the last.
+    pub fn duration(&self) -> Duration {
+        self.frames.last().map_or(Duration::ZERO, Frame::elapsed)
+            - self.frames.first().map_or(Duration::ZERO, Frame::elapsed)
+    }
+
+    /// Seconds since the Unix epoch at which `frame` was recorded.
+    pub fn timestamp(&self, frame: &Frame) -> u64 {
+        self.header.started + frame.elapsed().as_secs()
+    }
+}
+
+/// Playback speeds offered by the replay controls.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplaySpeed(pub f64);
+
+impl ReplaySpeed {
+    pub const ALL: [ReplaySpeed; 7] = [
+        ReplaySpeed(0.5),
+        ReplaySpeed(1.0),
+        ReplaySpeed(2.0),
+        ReplaySpeed(5.0),
+        ReplaySpeed(10.0),
+        ReplaySpeed(60.0),
+        ReplaySpeed(600.0),
+    ];
+}
+
+impl Default for ReplaySpeed {
+    fn default() -> Self {
+        ReplaySpeed(1.0)
+    }
+}
+
+impl std::fmt::Display for ReplaySpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}×", self.0)
+    }
+}
+
+/// Playback position in a loaded session.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    path: PathBuf,
+    session: Arc<Session>,
+    position: usize,
+    /// Recording time playback has reached. The frame shown is the last
+    /// one recorded at or before it.
+    clock: Duration,
+    /// Stands for the start of the recording when frames are pushed into
+    /// chart histories.
+    origin: Instant,
+    pub playing: bool,
+    pub speed: ReplaySpeed,
+}
+
+impl Replay {
+    pub fn new(path: PathBuf, session: Arc<Session>) -> Self {
+        let clock = session.frames[0].elapsed();
+        Self {
+            path,
+            session,
+            position: 0,
+            clock,
+            origin: Instant::now(),
+            playing: false,
+            speed: ReplaySpeed::default(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.session.frames
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.session.frames[self.position]
+    }
+
+    /// The processes recorded in the current frame.
+    pub fn processes(&self) -> Result<Vec<ProcessInfo>, SessionError> {
+        self.session.processes(self.position)
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position + 1 == self.session.frames.len()
+    }
+
+    /// When `frame` happened, for pushing it into chart histories.
+    pub fn time_of(&self, frame: &Frame) -> Instant {
+        self.origin + frame.elapsed()
+    }
+
+    /// Moves playback on by `real` time at the current speed. Playback
+    /// stops at the last frame.
+    pub fn advance(&mut self, real: Duration) {
+        if !self.playing {
+            return;
+        }
+
+        self.clock += real.mul_f64(self.speed.0);
+        let frames = &self.session.frames;
+        while self.position + 1 < frames.len() && frames[self.position + 1].elapsed() <= self.clock
+        {
+            self.position += 1;
+        }
+        if self.is_at_end() {
+            self.playing = false;
+        }
+    }
+
+    /// Jumps to the frame at `index`, clamped to the recording.
+    pub fn seek(&mut self, index: usize) {
+        self.position = index.min(self.session.frames.len() - 1);
+        self.clock = self.frame().elapsed();
+    }
+
+    /// Starts over from the beginning when played at the end.
+    pub fn toggle_playing(&mut self) {
+        if !self.playing && self.is_at_end() {
+            self.seek(0);
+        }
+        self.playing = !self.playing;
+    }
+
+    /// Index of the first frame a chart with `retention` still shows when
+    /// the current frame is the newest.
+    pub fn window_start(&self, retention: Retention) -> usize {
+        let now = self.frame().elapsed();
+        self.session.frames[..self.position]
+            .iter()
+            .position(|frame| now - frame.elapsed() <= retention.total)
+            .unwrap_or(self.position)
+    }
+}
@@ -0,0 +1,428 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::history::Retention;
+use crate::keymap::{Command, KeyBinding, Keymap};
+use crate::process_table::ProcessColumnKind;
+use crate::query::Query;
+use crate::theme::Theme;
+
+pub const MIN_REFRESH_INTERVAL_MS: u64 = 100;
+pub const MAX_REFRESH_INTERVAL_MS: u64 = 60_000;
+pub const MAX_HISTORY_MINUTES: u64 = 24 * 60;
+pub const MIN_COLUMN_WIDTH: f32 = 40.0;
+pub const MAX_COLUMN_WIDTH: f32 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Units {
+    /// Powers of 1024 (MiB, GiB).
+    #[default]
+    Binary,
+    /// Powers of 1000 (MB, GB).
+    Decimal,
+}
+
+impl Units {
+    pub const ALL: [Units; 2] = [Units::Binary, Units::Decimal];
+}
+
+impl std::fmt::Display for Units {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Units::Binary => write!(f, "Binary (MiB, GiB)"),
+            Units::Decimal => write!(f, "Decimal (MB, GB)"),
+        }
+    }
+}
+
+/// The view the app opens on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DefaultView {
+    #[default]
+    Processes,
+    Performance,
+}
+
+impl DefaultView {
+    pub const ALL: [DefaultView; 2] = [DefaultView::Processes, DefaultView::Performance];
+}
+
+impl std::fmt::Display for DefaultView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// What happens when a protected process is about to be ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ProtectedPolicy {
+    Refuse,
+    /// Ask a second time before sending the signal.
+    #[default]
+    ConfirmTwice,
+}
+
+impl ProtectedPolicy {
+    pub const ALL: [ProtectedPolicy; 2] = [ProtectedPolicy::Refuse, ProtectedPolicy::ConfirmTwice];
+}
+
+impl std::fmt::Display for ProtectedPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtectedPolicy::Refuse => write!(f, "Refuse"),
+            ProtectedPolicy::ConfirmTwice => write!(f, "Ask twice"),
+        }
+    }
+}
+
+/// Process names that are protected out of the box: init systems and
+/// display servers whose death takes the session down with them.
+pub const DEFAULT_PROTECTED_PROCESSES: [&str; 10] = [
+    "systemd",
+    "init",
+    "launchd",
+    "Xorg",
+    "Xwayland",
+    "gnome-shell",
+    "kwin_wayland",
+    "WindowServer",
+    "csrss.exe",
+    "winlogon.exe",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSettings {
+    pub kind: ProcessColumnKind,
+    pub width: f32,
+    pub visible: bool,
+}
+
+impl ColumnSettings {
+    pub fn new(kind: ProcessColumnKind) -> Self {
+        Self {
+            kind,
+            width: kind.default_width(),
+            visible: kind.default_visible(),
+        }
+    }
+}
+
+/// A process search kept under a name, shown as a chip above the table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedFilter {
+    pub name: String,
+    pub query: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub theme: Theme,
+    pub refresh_interval_ms: u64,
+    pub default_view: DefaultView,
+    /// Minutes of full-resolution chart history. Downsampled history is
+    /// kept twelve times as long.
+    pub history_minutes: u64,
+    pub units: Units,
+    /// Process table columns in display order.
+    pub columns: Vec<ColumnSettings>,
+    /// Names of processes that need extra confirmation, or can't be ended
+    /// at all, depending on `protected_policy`. PID 1 and the task manager
+    /// itself are always protected.
+    pub protected_processes: Vec<String>,
+    pub protected_policy: ProtectedPolicy,
+    pub saved_filters: Vec<SavedFilter>,
+    /// Search queries for processes that are kept at the top of the table,
+    /// such as `name=sshd` or `cmd~"nginx: master"`.
+    pub pinned_processes: Vec<String>,
+    /// Keyboard shortcuts. Commands missing from the file keep their
+    /// default binding.
+    pub keymap: Keymap,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::Dark,
+            refresh_interval_ms: 1000,
+            default_view: DefaultView::default(),
+            history_minutes: 5,
+            units: Units::default(),
+            columns: ProcessColumnKind::ALL
+                .into_iter()
+                .map(ColumnSettings::new)
+                .collect(),
+            protected_processes: DEFAULT_PROTECTED_PROCESSES
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            protected_policy: ProtectedPolicy::default(),
+            saved_filters: Vec::new(),
+            pinned_processes: Vec::new(),
+            keymap: Keymap::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// `$XDG_CONFIG_HOME/task-manager/config.toml` on Linux and the
+    /// platform equivalent elsewhere.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("task-manager").join("config.toml"))
+    }
+
+    /// Loads the user's settings. A missing file silently yields the
+    /// defaults; anything else that goes wrong is returned as a warning
+    /// alongside the best settings that could be salvaged.
+    pub fn load() -> (Self, Vec<String>) {
+        let Some(path) = Self::path() else {
+            return (Self::default(), Vec::new());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(source) => Self::parse(&source),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (Self::default(), Vec::new()),
+            Err(err) => (
+                Self::default(),
+                vec![format!("Could not read {}: {err}", path.display())],
+            ),
+        }
+    }
+
+    /// Parses a config file field by field, so one bad value only resets
+    /// that value instead of the whole file.
+    pub fn parse(source: &str) -> (Self, Vec<String>) {
+        let mut settings = Self::default();
+        let mut warnings = Vec::new();
+
+        let table = match toml::from_str::<toml::Table>(source) {
+            Ok(table) => table,
+            Err(err) => {
+                warnings.push(format!("config.toml is not valid TOML: {err}"));
+                return (settings, warnings);
+            }
+        };
+
+        read_field(&table, "theme", &mut settings.theme, &mut warnings);
+        read_field(
+            &table,
+            "refresh_interval_ms",
+            &mut settings.refresh_interval_ms,
+            &mut warnings,
+        );
+        read_field(
+            &table,
+            "default_view",
+            &mut settings.default_view,
+            &mut warnings,
+        );
+        read_field(
+            &table,
+            "history_minutes",
+            &mut settings.history_minutes,
+            &mut warnings,
+        );
+        read_field(&table, "units", &mut settings.units, &mut warnings);
+        read_field(
+            &table,
+            "protected_processes",
+            &mut settings.protected_processes,
+            &mut warnings,
+        );
+        read_field(
+            &table,
+            "protected_policy",
+            &mut settings.protected_policy,
+            &mut warnings,
+        );
+        read_field(
+            &table,
+            "saved_filters",
+            &mut settings.saved_filters,
+            &mut warnings,
+        );
+        read_field(
+            &table,
+            "pinned_processes",
+            &mut settings.pinned_processes,
+            &mut warnings,
+        );
+
+        if let Some(value) = table.get("columns") {
+            match value.as_array() {
+                Some(entries) => {
+                    settings.columns = entries
+                        .iter()
+                        .filter_map(|entry| match entry.clone().try_into() {
+                            Ok(column) => Some(column),
+                            Err(err) => {
+                                warnings.push(format!("Ignoring invalid column: {err}"));
+                                None
+                            }
+                        })
+                        .collect();
+                }
+                None => warnings.push("Ignoring `columns`: expected an array".to_string()),
+            }
+        }
+
+        if let Some(value) = table.get("keymap") {
+            match value.as_table() {
+                Some(entries) => {
+                    for (name, binding) in entries {
+                        let command: Result<Command, _> =
+                            toml::Value::String(name.clone()).try_into();
+                        let Ok(command) = command else {
+                            warnings.push(format!("Ignoring unknown shortcut `{name}`"));
+                            continue;
+                        };
+                        match binding.clone().try_into::<KeyBinding>() {
+                            Ok(binding) => settings.keymap.set(command, binding),
+                            Err(err) => {
+                                warnings.push(format!("Ignoring invalid shortcut `{name}`: {err}"))
+                            }
+                        }
+                    }
+                }
+                None => warnings.push("Ignoring `keymap`: expected a table".to_string()),
+            }
+        }
+
+        settings.validate(&mut warnings);
+        (settings, warnings)
+    }
+
+    /// Clamps out-of-range values, repairs the column list, drops unusable
+    /// filters and pins and reports conflicting shortcuts.
+    pub fn validate(&mut self, warnings: &mut Vec<String>) {
+        let interval = self
+            .refresh_interval_ms
+            .clamp(MIN_REFRESH_INTERVAL_MS, MAX_REFRESH_INTERVAL_MS);
+        if interval != self.refresh_interval_ms {
+            warnings.push(format!(
+                "refresh_interval_ms must be between {MIN_REFRESH_INTERVAL_MS} and {MAX_REFRESH_INTERVAL_MS}"
+            ));
+            self.refresh_interval_ms = interval;
+        }
+
+        let minutes = self.history_minutes.clamp(1, MAX_HISTORY_MINUTES);
+        if minutes != self.history_minutes {
+            warnings.push(format!(
+                "history_minutes must be between 1 and {MAX_HISTORY_MINUTES}"
+            ));
+            self.history_minutes = minutes;
+        }
+
+        let mut seen = Vec::new();
+        self.columns.retain(|c| {
+            let duplicate = seen.contains(&c.kind);
+            seen.push(c.kind);
+            !duplicate
+        });
+
+        for column in &mut self.columns {
+            if !column.width.is_finite() {
+                column.width = column.kind.default_width();
+            }
+            column.width = column.width.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+        }
+
+        for kind in ProcessColumnKind::ALL {
+            if !seen.contains(&kind) {
+                self.columns.push(ColumnSettings::new(kind));
+            }
+        }
+
+        let mut names = Vec::new();
+        for name in self.protected_processes.drain(..) {
+            let name = name.trim().to_string();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        self.protected_processes = names;
+
+        let mut filters: Vec<SavedFilter> = Vec::new();
+        for filter in self.saved_filters.drain(..) {
+            let name = filter.name.trim().to_string();
+            if !name.is_empty() && !filters.iter().any(|f| f.name == name) {
+                filters.push(SavedFilter { name, ..filter });
+            }
+        }
+        self.saved_filters = filters;
+
+        let mut pins = Vec::new();
+        for pin in self.pinned_processes.drain(..) {
+            let pin = pin.trim().to_string();
+            if pin.is_empty() || pins.contains(&pin) {
+                continue;
+            }
+            match Query::parse(&pin) {
+                Ok(_) => pins.push(pin),
+                Err(err) => warnings.push(format!("Ignoring pinned process `{pin}`: {err}")),
+            }
+        }
+        self.pinned_processes = pins;
+
+        for (first, second) in self.keymap.conflicts() {
+            let binding = self
+                .keymap
+                .binding(first)
+                .expect("conflicting commands are bound");
+            warnings.push(format!(
+                "{binding} is bound to both \"{first}\" and \"{second}\"; only \"{first}\" will work"
+            ));
+        }
+
+        if !self.columns.iter().any(|c| c.visible) {
+            warnings.push("At least one column must be visible".to_string());
+            self.columns = Self::default().columns;
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "no config directory available")
+        })?;
+        self.save_to(&path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = toml::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, contents)
+    }
+
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.refresh_interval_ms)
+    }
+
+    pub fn retention(&self) -> Retention {
+        let recent = Duration::from_secs(self.history_minutes * 60);
+        Retention {
+            recent,
+            total: recent * 12,
+            ..Retention::default()
+        }
+    }
+}
+
+fn read_field<T: DeserializeOwned>(
+    table: &toml::Table,
+    key: &str,
+    target: &mut T,
+    warnings: &mut Vec<String>,
+) {
+    if let Some(value) = table.get(key) {
+        match value.clone().try_into() {
+            Ok(value) => *target = value,
+            Err(err) => warnings.push(format!("Ignoring invalid `{key}`: {err}")),
+        }
+    }
+}
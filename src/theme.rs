@@ -1,8 +1,11 @@
+use std::collections::HashSet;
+
 use iced::overlay::menu;
-use iced::widget::{button, container, pick_list, scrollable, text, text_input};
+use iced::widget::{button, checkbox, container, pick_list, scrollable, slider, text, text_input};
 use iced::{Background, Border, Color, color, theme};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Theme {
     Light,
     #[default]
@@ -145,7 +148,7 @@ impl text_input::Catalog for Theme {
 
 #[derive(Default, Clone)]
 pub struct TableStyle {
-    pub selected_row: Option<usize>,
+    pub selected_rows: HashSet<usize>,
 }
 
 impl iced_table2::Catalog for Theme {
@@ -168,9 +171,9 @@ impl iced_table2::Catalog for Theme {
 
     fn row(&self, style: &Self::Style, index: usize) -> container::Style {
         let palette = self.palette();
-        let bg = if style.selected_row == Some(index) {
+        let bg = if style.selected_rows.contains(&index) {
             palette.accent_fill_color_default
-        } else if index % 2 == 0 {
+        } else if index.is_multiple_of(2) {
             palette.solid_background_fill_color_base
         } else {
             palette.solid_background_fill_color_quarternary
@@ -300,6 +303,81 @@ impl pick_list::Catalog for Theme {
     }
 }
 
+impl slider::Catalog for Theme {
+    type Class<'a> = slider::StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(|theme: &Theme, status| {
+            let palette = theme.palette();
+            let handle = match status {
+                slider::Status::Active => palette.accent_fill_color_default,
+                slider::Status::Hovered => palette.accent_fill_color_secondary,
+                slider::Status::Dragged => palette.accent_fill_color_tertiary,
+            };
+            slider::Style {
+                rail: slider::Rail {
+                    backgrounds: (
+                        Background::Color(palette.accent_fill_color_default),
+                        Background::Color(palette.control_strong_fill_color_default),
+                    ),
+                    width: 4.0,
+                    border: Border::default().rounded(2),
+                },
+                handle: slider::Handle {
+                    shape: slider::HandleShape::Circle { radius: 8.0 },
+                    background: Background::Color(handle),
+                    border_width: 2.0,
+                    border_color: palette.control_solid_fill_color_default,
+                },
+            }
+        })
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: slider::Status) -> slider::Style {
+        class(self, status)
+    }
+}
+
+impl checkbox::Catalog for Theme {
+    type Class<'a> = checkbox::StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(|theme: &Theme, status| {
+            let palette = theme.palette();
+            let (is_checked, hovered, disabled) = match status {
+                checkbox::Status::Active { is_checked } => (is_checked, false, false),
+                checkbox::Status::Hovered { is_checked } => (is_checked, true, false),
+                checkbox::Status::Disabled { is_checked } => (is_checked, false, true),
+            };
+            let background = match (is_checked, hovered, disabled) {
+                (_, _, true) => palette.control_fill_color_disabled,
+                (true, false, _) => palette.accent_fill_color_default,
+                (true, true, _) => palette.accent_fill_color_secondary,
+                (false, false, _) => palette.control_alt_fill_color_secondary,
+                (false, true, _) => palette.control_alt_fill_color_tertiary,
+            };
+            checkbox::Style {
+                background: Background::Color(background),
+                icon_color: palette.text_on_accent_fill_color_primary,
+                border: Border::default().rounded(4).width(1).color(if is_checked {
+                    palette.accent_fill_color_default
+                } else {
+                    palette.control_strong_stroke_color_default
+                }),
+                text_color: Some(if disabled {
+                    palette.text_fill_color_disabled
+                } else {
+                    palette.text_fill_color_primary
+                }),
+            }
+        })
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: checkbox::Status) -> checkbox::Style {
+        class(self, status)
+    }
+}
+
 macro_rules! from_argb {
     ($hex:expr) => {{
         let hex = $hex as u32;
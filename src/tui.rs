@@ -0,0 +1,583 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Sparkline, Table, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame as TerminalFrame};
+
+use crate::export::text_table;
+use crate::history::History;
+use crate::process_actions::{
+    ActionOutcome, ActionReport, BulkReport, ProcessAction, ProcessControl, ProcessTarget,
+    protection,
+};
+use crate::process_table::{
+    Navigation, ProcessColumnKind, ProcessTableMessage, ProcessTableState, SortOrder, apply_filter,
+    cell_text,
+};
+use crate::sampler::{Sampler, SystemSnapshot};
+use crate::settings::{DefaultView, ProtectedPolicy, Settings};
+use crate::utilities::{format_bytes, format_duration};
+
+/// How often pending end-task escalations are checked while idle.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuiView {
+    Processes,
+    Performance,
+}
+
+/// What keys currently do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    Normal,
+    /// Keys edit the search query.
+    Search,
+    /// End task was asked for these processes and waits for y or n.
+    Confirm {
+        targets: Vec<ProcessTarget>,
+        protection: Option<String>,
+        confirmed_once: bool,
+    },
+}
+
+/// The Processes and Performance views for a terminal. Samples come from
+/// the same `Sampler` as the window and the command line reports, and
+/// rows are filtered, sorted and acted on with the window's process table
+/// and process actions.
+pub struct App {
+    sampler: Sampler,
+    pub settings: Settings,
+    pub table: ProcessTableState,
+    table_state: TableState,
+    process_control: ProcessControl,
+    pub view: TuiView,
+    pub mode: Mode,
+    pub paused: bool,
+    pub status: Option<String>,
+    pub quit: bool,
+    latest: SystemSnapshot,
+    cpu_history: History,
+    memory_history: History,
+    /// Total throughput of every disk, in MB/s.
+    disk_history: History,
+}
+
+/// Runs the terminal UI until it is quit, restoring the terminal even if
+/// drawing fails.
+pub fn run() -> io::Result<()> {
+    let (settings, _) = Settings::load();
+    let mut app = App::new(settings);
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    pub fn new(settings: Settings) -> Self {
+        let retention = settings.retention();
+        let mut table = ProcessTableState::default();
+        table.apply_column_settings(&settings.columns);
+        table.set_units(settings.units);
+        table.set_pins(&settings.pinned_processes);
+
+        let mut sampler = Sampler::new();
+        sampler.slow_columns = table.slow_columns();
+
+        let mut app = Self {
+            sampler,
+            table,
+            table_state: TableState::default(),
+            process_control: ProcessControl::default(),
+            view: match settings.default_view {
+                DefaultView::Processes => TuiView::Processes,
+                DefaultView::Performance => TuiView::Performance,
+            },
+            settings,
+            mode: Mode::Normal,
+            paused: false,
+            status: None,
+            quit: false,
+            latest: SystemSnapshot::default(),
+            cpu_history: History::new(retention),
+            memory_history: History::new(retention),
+            disk_history: History::new(retention),
+        };
+        app.refresh();
+        app
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        let mut next_refresh = Instant::now() + self.settings.refresh_interval();
+
+        while !self.quit {
+            if Instant::now() >= next_refresh {
+                if !self.paused {
+                    self.refresh();
+                }
+                next_refresh = Instant::now() + self.settings.refresh_interval();
+            }
+            if self.process_control.has_pending() {
+                let reports = self.process_control.poll(&mut self.sampler.sys);
+                if !reports.is_empty() {
+                    self.status = Some(BulkReport::from_escalations(reports).to_string());
+                }
+            }
+
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let timeout = next_refresh
+                .saturating_duration_since(Instant::now())
+                .min(POLL_INTERVAL);
+            if event::poll(timeout)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Samples everything once and feeds the charts and the table.
+    pub fn refresh(&mut self) {
+        let mut snapshot = self.sampler.sample(true);
+
+        self.cpu_history.push(snapshot.cpu.usage as f64);
+        self.memory_history.push(snapshot.memory.usage_percent());
+        self.disk_history
+            .push(snapshot.disks.iter().map(|disk| disk.usage).sum());
+
+        self.table.rows = std::mem::take(&mut snapshot.processes);
+        apply_filter(&mut self.table);
+        self.latest = snapshot;
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        match &self.mode {
+            Mode::Search => self.search_key(key),
+            Mode::Confirm { .. } => self.confirm_key(key),
+            Mode::Normal => self.normal_key(key),
+        }
+    }
+
+    fn normal_key(&mut self, key: KeyEvent) {
+        let navigation = match key.code {
+            KeyCode::Up => Some(Navigation::Up),
+            KeyCode::Down => Some(Navigation::Down),
+            KeyCode::PageUp => Some(Navigation::PageUp),
+            KeyCode::PageDown => Some(Navigation::PageDown),
+            KeyCode::Home => Some(Navigation::Home),
+            KeyCode::End => Some(Navigation::End),
+            _ => None,
+        };
+        if let Some(navigation) = navigation {
+            if self.view == TuiView::Processes {
+                self.table_message(ProcessTableMessage::Navigate(navigation));
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Char('1') => self.view = TuiView::Processes,
+            KeyCode::Char('2') => self.view = TuiView::Performance,
+            KeyCode::Tab => {
+                self.view = match self.view {
+                    TuiView::Processes => TuiView::Performance,
+                    TuiView::Performance => TuiView::Processes,
+                }
+            }
+            KeyCode::Char('p') => self.paused = !self.paused,
+            KeyCode::F(5) => self.refresh(),
+            KeyCode::Char('/') if self.view == TuiView::Processes => self.mode = Mode::Search,
+            KeyCode::Char('s') => self.cycle_sort(1),
+            KeyCode::Char('S') => self.cycle_sort(-1),
+            KeyCode::Char('r') => {
+                self.table_message(ProcessTableMessage::SortBy(self.table.sort_column))
+            }
+            KeyCode::Delete | KeyCode::Char('e') if self.view == TuiView::Processes => {
+                self.request_end_task()
+            }
+            KeyCode::Esc => {
+                self.table.clear_selection();
+                self.status = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Typing filters as it goes, like the window's search box. Esc clears
+    /// the search, Enter keeps it.
+    fn search_key(&mut self, key: KeyEvent) {
+        let mut query = self.table.search_query.clone();
+        match key.code {
+            KeyCode::Enter => self.mode = Mode::Normal,
+            KeyCode::Esc => {
+                query.clear();
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) => query.push(c),
+            _ => return,
+        }
+        if query != self.table.search_query {
+            self.table_message(ProcessTableMessage::SearchChanged(query));
+        }
+    }
+
+    fn confirm_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let Mode::Confirm {
+                    targets,
+                    protection,
+                    confirmed_once,
+                } = std::mem::replace(&mut self.mode, Mode::Normal)
+                else {
+                    return;
+                };
+                if protection.is_some() && !confirmed_once {
+                    self.mode = Mode::Confirm {
+                        targets,
+                        protection,
+                        confirmed_once: true,
+                    };
+                    return;
+                }
+
+                let action = ProcessAction::Terminate;
+                let reports = targets
+                    .iter()
+                    .map(|target| {
+                        self.process_control
+                            .send_checked(&mut self.sampler.sys, target, action)
+                    })
+                    .collect();
+                self.status = Some(BulkReport::from_reports(action, reports).to_string());
+                self.table.clear_selection();
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.mode = Mode::Normal,
+            _ => {}
+        }
+    }
+
+    /// Asks to end the selected processes, turning down protected ones
+    /// when the settings say so.
+    fn request_end_task(&mut self) {
+        let pids = self.table.selected_pids();
+        let Some(&first) = pids.first() else {
+            return;
+        };
+
+        let sys = &mut self.sampler.sys;
+        let targets: Vec<ProcessTarget> = pids
+            .into_iter()
+            .filter_map(|pid| ProcessTarget::capture(sys, &self.sampler.users, pid))
+            .collect();
+        if targets.is_empty() {
+            self.status = Some(
+                ActionReport {
+                    pid: first,
+                    name: String::new(),
+                    action: ProcessAction::Terminate,
+                    outcome: ActionOutcome::AlreadyExited,
+                }
+                .to_string(),
+            );
+            return;
+        }
+
+        let protection = targets.iter().find_map(|target| {
+            protection(
+                &self.sampler.sys,
+                target.pid,
+                ProcessAction::Terminate,
+                &self.settings.protected_processes,
+            )
+        });
+        if let Some(reason) = &protection
+            && self.settings.protected_policy == ProtectedPolicy::Refuse
+        {
+            self.status = Some(format!("Refused: {reason}"));
+            return;
+        }
+
+        self.mode = Mode::Confirm {
+            targets,
+            protection,
+            confirmed_once: false,
+        };
+    }
+
+    /// Sorts by the next or previous shown column.
+    fn cycle_sort(&mut self, step: isize) {
+        let columns = self.table.visible_columns();
+        let current = columns
+            .iter()
+            .position(|kind| *kind == self.table.sort_column)
+            .unwrap_or(0);
+        let next = (current as isize + step).rem_euclid(columns.len() as isize) as usize;
+        self.table_message(ProcessTableMessage::SortBy(columns[next]));
+    }
+
+    /// The window's table logic works without a window; the scrolling it
+    /// would do is handled by `TableState` instead.
+    fn table_message(&mut self, message: ProcessTableMessage) {
+        let _ = crate::process_table::update(&mut self.table, message);
+    }
+
+    pub fn draw(&mut self, frame: &mut TerminalFrame) {
+        let [tabs, main, status, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let selected = match self.view {
+            TuiView::Processes => 0,
+            TuiView::Performance => 1,
+        };
+        frame.render_widget(
+            Tabs::new(["1 Processes", "2 Performance"])
+                .select(selected)
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            tabs,
+        );
+
+        match self.view {
+            TuiView::Processes => self.draw_processes(frame, main),
+            TuiView::Performance => self.draw_performance(frame, main),
+        }
+
+        frame.render_widget(Paragraph::new(self.status_line()), status);
+        frame.render_widget(
+            Paragraph::new(self.help_line()).style(Style::new().add_modifier(Modifier::DIM)),
+            help,
+        );
+    }
+
+    fn status_line(&self) -> Line<'_> {
+        match &self.mode {
+            Mode::Search => Line::from(format!("Search: {}█", self.table.search_query)),
+            Mode::Confirm {
+                targets,
+                protection,
+                confirmed_once,
+            } => {
+                let names: Vec<String> = targets
+                    .iter()
+                    .map(|target| format!("{} ({})", target.name, target.pid))
+                    .collect();
+                let warning = match (protection, confirmed_once) {
+                    (Some(reason), false) => format!(" Protected: {reason}."),
+                    (Some(reason), true) => {
+                        format!(" Protected: {reason}. Are you absolutely sure?")
+                    }
+                    (None, _) => String::new(),
+                };
+                Line::from(format!("End {}?{warning} [y/n]", names.join(", ")))
+                    .style(Style::new().fg(Color::Yellow))
+            }
+            Mode::Normal => match (&self.table.query_error, &self.status) {
+                (Some(err), _) => Line::from(format!("Search: {err}")).style(Color::Red),
+                (None, Some(status)) => Line::from(status.as_str()),
+                (None, None) if self.paused => Line::from("Updates paused. Press p to resume."),
+                (None, None) => Line::default(),
+            },
+        }
+    }
+
+    fn help_line(&self) -> &'static str {
+        match (&self.mode, self.view) {
+            (Mode::Search, _) => "Enter keep search · Esc clear",
+            (Mode::Confirm { .. }, _) => "y end task · n cancel",
+            (Mode::Normal, TuiView::Processes) => {
+                "↑↓ move · / search · s/S sort column · r reverse · Del end task · p pause · Tab view · q quit"
+            }
+            (Mode::Normal, TuiView::Performance) => "p pause · F5 refresh · Tab view · q quit",
+        }
+    }
+
+    fn draw_processes(&mut self, frame: &mut TerminalFrame, area: Rect) {
+        let columns = self.table.visible_columns();
+        let units = self.table.units;
+
+        let header = Row::new(columns.iter().map(|kind| {
+            let arrow = match self.table.sort_order {
+                _ if *kind != self.table.sort_column => "",
+                SortOrder::Ascending => " ▲",
+                SortOrder::Descending => " ▼",
+            };
+            Cell::from(format!("{}{arrow}", kind.label()))
+        }))
+        .style(Style::new().add_modifier(Modifier::BOLD));
+
+        let rows = self.table.filtered_rows.iter().map(|row| {
+            let cells = columns.iter().map(|kind| {
+                let text = cell_text(*kind, row, units);
+                match (kind, &row.tree) {
+                    (ProcessColumnKind::Name, Some(node)) => {
+                        Cell::from(format!("{}{text}", "  ".repeat(node.depth)))
+                    }
+                    _ => Cell::from(text),
+                }
+            });
+            let style = if self.table.selection.contains(&row.pid) {
+                Style::new().add_modifier(Modifier::REVERSED)
+            } else if row.pinned {
+                Style::new().add_modifier(Modifier::BOLD)
+            } else {
+                Style::new()
+            };
+            Row::new(cells).style(style)
+        });
+
+        let widths = columns.iter().map(|kind| match kind {
+            ProcessColumnKind::Name
+            | ProcessColumnKind::CommandLine
+            | ProcessColumnKind::Executable => Constraint::Fill(1),
+            kind => Constraint::Length((kind.default_width() / 8.0) as u16),
+        });
+
+        let title = format!(
+            " {} of {} processes ",
+            self.table.filtered_rows.len(),
+            self.table.rows.len()
+        );
+        self.table_state.select(self.table.focused_index());
+        frame.render_stateful_widget(
+            Table::new(rows, widths)
+                .header(header)
+                .block(Block::bordered().title(title)),
+            area,
+            &mut self.table_state,
+        );
+    }
+
+    fn draw_performance(&self, frame: &mut TerminalFrame, area: Rect) {
+        let latest = &self.latest;
+        let units = self.settings.units;
+        let [cpu, memory, disk, details] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(
+                (latest.disks.len() + latest.networks.len() + 4).min(u16::MAX as usize) as u16,
+            ),
+        ])
+        .areas(area);
+
+        sparkline(
+            frame,
+            cpu,
+            &self.cpu_history,
+            Some(100.0),
+            Color::Cyan,
+            format!(
+                " CPU {:.1}% · {:.2} GHz · {} logical processors · up {} ",
+                latest.cpu.usage,
+                latest.cpu.frequency(),
+                latest.cpu.cores.len(),
+                format_duration(latest.uptime)
+            ),
+        );
+        sparkline(
+            frame,
+            memory,
+            &self.memory_history,
+            Some(100.0),
+            Color::Magenta,
+            format!(
+                " Memory {:.1}% · {} of {} ",
+                latest.memory.usage_percent(),
+                format_bytes(latest.memory.used, units),
+                format_bytes(latest.memory.total, units)
+            ),
+        );
+        sparkline(
+            frame,
+            disk,
+            &self.disk_history,
+            None,
+            Color::Green,
+            format!(
+                " Disk {:.2} MB/s ",
+                self.disk_history.latest().unwrap_or_default()
+            ),
+        );
+
+        let disks: Vec<Vec<String>> = latest
+            .disks
+            .iter()
+            .map(|d| {
+                vec![
+                    d.mount_point.display().to_string(),
+                    format!(
+                        "{} of {}",
+                        format_bytes(d.total_space.saturating_sub(d.available_space), units),
+                        format_bytes(d.total_space, units)
+                    ),
+                    format!("{:.2} MB/s", d.usage),
+                ]
+            })
+            .chain(latest.networks.iter().map(|n| {
+                vec![
+                    n.name.clone(),
+                    format!("↓ {:.1} KB/s", n.received),
+                    format!("↑ {:.1} KB/s", n.transmitted),
+                ]
+            }))
+            .collect();
+        let text = text_table(
+            &["Disk / interface", "Used / receive", "Throughput / send"].map(String::from),
+            &disks,
+            &[false, true, true],
+        );
+        frame.render_widget(
+            Paragraph::new(text).block(Block::bordered().title(" Disks and networks ")),
+            details,
+        );
+    }
+}
+
+/// Draws the newest samples that fit in `area`. Values are scaled by 100
+/// as sparklines only take whole numbers.
+fn sparkline(
+    frame: &mut TerminalFrame,
+    area: Rect,
+    history: &History,
+    max: Option<f64>,
+    color: Color,
+    title: String,
+) {
+    let values = history.values();
+    let width = area.width.saturating_sub(2) as usize;
+    let data: Vec<u64> = values[values.len().saturating_sub(width)..]
+        .iter()
+        .map(|value| (value * 100.0).round().max(0.0) as u64)
+        .collect();
+
+    let mut widget = Sparkline::default()
+        .block(Block::bordered().title(title))
+        .data(&data)
+        .style(Style::new().fg(color));
+    if let Some(max) = max {
+        widget = widget.max((max * 100.0) as u64);
+    }
+    frame.render_widget(widget, area);
+}
@@ -1,11 +1,36 @@
-use std::time::Duration;
-use sysinfo::{Disks, Pid, ProcessesToUpdate, System};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sysinfo::{Groups, Signal};
 
-use iced::widget::{Space, button, column, container, pick_list, row, text, text_input};
+use iced::keyboard::key::Physical;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::widget::{
+    Space, button, center, checkbox, column, container, mouse_area, opaque, pick_list, row,
+    scrollable, slider, stack, text, text_input,
+};
 use iced::{Alignment, Background, Color, Element, Length, Subscription, Task};
 
+use crate::affinity;
 use crate::charts::*;
+use crate::cpus::{CoreState, average_frequency};
+use crate::disks::{DiskState, find_disk};
+use crate::export::{self, ExportFormat};
+use crate::history::History;
+use crate::keymap::{Command, KeyBinding};
+use crate::networks::{NetworkState, find_network};
+use crate::priority::{self, IoClass, IoPriority, MAX_IO_LEVEL, MAX_NICE, MIN_NICE, PriorityClass};
+use crate::process_actions::{
+    ActionOutcome, ActionReport, BulkReport, ProcessAction, ProcessControl, ProcessTarget,
+    protection,
+};
+use crate::process_details::ProcessDetails;
 use crate::process_table::*;
+use crate::query::{Query, QueryError, quote};
+use crate::sampler::{self, Sampler, SystemSnapshot};
+use crate::session::{self, Recorder, Replay, ReplaySpeed, Session};
+use crate::settings::{DefaultView, ProtectedPolicy, SavedFilter, Settings, Units};
 use crate::theme::Theme;
 use crate::utilities::*;
 use plotters::prelude::*;
@@ -18,34 +43,201 @@ pub enum SelectedView {
     Settings,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+impl From<DefaultView> for SelectedView {
+    fn from(view: DefaultView) -> Self {
+        match view {
+            DefaultView::Processes => SelectedView::Processes,
+            DefaultView::Performance => SelectedView::Performance,
+        }
+    }
+}
+
+/// Update speeds offered in the settings, like Windows Task Manager's
+/// "Update speed" menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateSpeed {
+    /// Refresh every given number of milliseconds.
+    Every(u64),
+    Paused,
+}
+
+impl UpdateSpeed {
+    pub const ALL: [UpdateSpeed; 6] = [
+        UpdateSpeed::Every(250),
+        UpdateSpeed::Every(500),
+        UpdateSpeed::Every(1000),
+        UpdateSpeed::Every(2000),
+        UpdateSpeed::Every(5000),
+        UpdateSpeed::Paused,
+    ];
+}
+
+impl std::fmt::Display for UpdateSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateSpeed::Every(ms) => write!(f, "{} s", *ms as f64 / 1000.0),
+            UpdateSpeed::Paused => write!(f, "Paused"),
+        }
+    }
+}
+
+/// Full-resolution chart history offered in the settings, in minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryLength(pub u64);
+
+impl HistoryLength {
+    pub const ALL: [HistoryLength; 5] = [
+        HistoryLength(1),
+        HistoryLength(5),
+        HistoryLength(15),
+        HistoryLength(30),
+        HistoryLength(60),
+    ];
+}
+
+impl std::fmt::Display for HistoryLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            1 => write!(f, "1 minute"),
+            minutes => write!(f, "{minutes} minutes"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum SelectedTab {
     Cpu,
     Memory,
-    Disk,
+    /// Identified by mount point.
+    Disk(PathBuf),
+    /// Identified by interface name.
+    Network(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CpuGraphMode {
+    #[default]
+    Overall,
+    LogicalProcessors,
+}
+
+impl CpuGraphMode {
+    pub const ALL: [CpuGraphMode; 2] = [CpuGraphMode::Overall, CpuGraphMode::LogicalProcessors];
+}
+
+impl std::fmt::Display for CpuGraphMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CpuGraphMode::Overall => write!(f, "Overall utilization"),
+            CpuGraphMode::LogicalProcessors => write!(f, "Logical processors"),
+        }
+    }
+}
+
+/// A process action waiting for the user to confirm it.
+struct Confirmation {
+    /// Several targets when a whole app group is acted on.
+    targets: Vec<ProcessTarget>,
+    action: ProcessAction,
+    /// Set when the process is protected; such actions are confirmed twice.
+    protection: Option<String>,
+    confirmed_once: bool,
+}
+
+/// Message shown above the process table after acting on a process.
+struct StatusLine {
+    message: String,
+    success: bool,
+}
+
+impl From<ActionReport> for StatusLine {
+    fn from(report: ActionReport) -> Self {
+        Self {
+            message: report.to_string(),
+            success: report.outcome.is_success(),
+        }
+    }
+}
+
+impl From<BulkReport> for StatusLine {
+    fn from(report: BulkReport) -> Self {
+        Self {
+            message: report.to_string(),
+            success: report.is_success(),
+        }
+    }
+}
+
+/// Values being edited in the "Set priority" dialog.
+struct PriorityDialog {
+    /// The first target is the one whose current values are shown.
+    targets: Vec<ProcessTarget>,
+    nice: i32,
+    initial_nice: i32,
+    /// `None` where I/O priorities aren't supported.
+    io: Option<IoPriority>,
+    initial_io: Option<IoPriority>,
+}
+
+/// CPUs being edited in the "Set affinity" dialog.
+struct AffinityDialog {
+    target: ProcessTarget,
+    cpus: Vec<bool>,
+    /// Apply to every thread instead of only the main thread.
+    all_threads: bool,
 }
 
 pub struct State {
     pub theme_selected: Option<Theme>,
-    sys: System,
-    total_mem: f32,
+    pub settings: Settings,
+    settings_warnings: Vec<String>,
+    /// When set, ticks are ignored so charts and the process table can be
+    /// inspected. Not persisted.
+    paused: bool,
+    /// Set while every tick is appended to a session file.
+    recorder: Option<Recorder>,
+    /// Set while a recorded session is shown instead of live data.
+    replay: Option<Replay>,
+    sampler: Sampler,
+    groups: Groups,
     cpu_usage: f32,
     cpu_frequency: f32,
     num_of_cpus: i32,
-    cpu_history: Vec<f64>,
+    cpu_history: History,
+    cores: Vec<CoreState>,
+    cpu_graph_mode: CpuGraphMode,
     memory_usage: f64,
-    memory_history: Vec<f64>,
+    memory_history: History,
+    used_memory: u64,
+    total_memory: u64,
     uptime: String,
-    disks: Disks,
-    disk_size: f32,
-    disk_available_space: f32,
-    disk_usage: f64,
-    disk_history: Vec<f64>,
-    prev_disk_read: u64,
-    prev_disk_written: u64,
+    disk_states: Vec<DiskState>,
+    network_states: Vec<NetworkState>,
     selected_tab: SelectedTab,
     selected_view: SelectedView,
     process_table: ProcessTableState,
+    process_control: ProcessControl,
+    /// Outcome of the most recent process action.
+    status: Option<StatusLine>,
+    confirmation: Option<Confirmation>,
+    priority_dialog: Option<PriorityDialog>,
+    affinity_dialog: Option<AffinityDialog>,
+    show_details: bool,
+    /// Details of the selected process, refreshed every tick while the
+    /// details panel is open.
+    details: Option<ProcessDetails>,
+    /// Comma separated protected process names being edited in the settings.
+    protected_input: String,
+    /// Shortcuts being edited in the settings, which may not parse yet.
+    shortcut_inputs: BTreeMap<Command, String>,
+    /// Name being typed for the current search while the save filter
+    /// dialog is open.
+    save_filter_name: Option<String>,
+    /// Pin pattern being typed in the settings.
+    pin_input: String,
+    /// Why `pin_input` doesn't parse, shown under it like the search box's
+    /// error.
+    pin_error: Option<QueryError>,
     processes_icon: iced::widget::image::Handle,
     performance_icon: iced::widget::image::Handle,
     settings_icon: iced::widget::image::Handle,
@@ -53,32 +245,60 @@ pub struct State {
 
 impl Default for State {
     fn default() -> Self {
-        let mut sys = System::new_all();
-        sys.refresh_all();
-        let total_mem = bytes_to_gb(sys.total_memory());
-        let num_of_cpus = sys.cpus().len() as i32;
+        let sampler = Sampler::new();
+        let initial = sampler.current();
+        let (settings, settings_warnings) = Settings::load();
+        let retention = settings.retention();
+        let now = Instant::now();
+        let mut disk_states = Vec::new();
+        sampler::record_disks(&mut disk_states, &initial.disks, now, retention);
+        let mut network_states = Vec::new();
+        sampler::record_networks(&mut network_states, &initial.networks, now, retention);
+
+        let mut process_table = ProcessTableState::default();
+        process_table.apply_column_settings(&settings.columns);
+        process_table.set_units(settings.units);
+        process_table.set_pins(&settings.pinned_processes);
+        let protected_input = settings.protected_processes.join(", ");
+        let shortcut_inputs = shortcut_inputs(&settings);
 
         Self {
-            theme_selected: Some(crate::theme::Theme::Dark),
-            sys,
-            total_mem,
+            theme_selected: Some(settings.theme),
+            selected_view: settings.default_view.into(),
+            settings,
+            settings_warnings,
+            paused: false,
+            recorder: None,
+            replay: None,
+            sampler,
+            groups: Groups::new_with_refreshed_list(),
             cpu_usage: 0.0,
             cpu_frequency: 0.0,
-            cpu_history: Vec::new(),
-            num_of_cpus,
+            cpu_history: History::new(retention),
+            cores: Vec::new(),
+            cpu_graph_mode: CpuGraphMode::default(),
+            num_of_cpus: initial.cpu.cores.len() as i32,
             memory_usage: 0.0,
-            memory_history: Vec::new(),
+            memory_history: History::new(retention),
+            used_memory: initial.memory.used,
+            total_memory: initial.memory.total,
             uptime: String::new(),
-            disks: Disks::new_with_refreshed_list(),
-            disk_size: 0.0,
-            disk_available_space: 0.0,
-            disk_usage: 0.0,
-            disk_history: Vec::new(),
-            prev_disk_read: 0,
-            prev_disk_written: 0,
+            disk_states,
+            network_states,
             selected_tab: SelectedTab::Cpu,
-            selected_view: SelectedView::Processes,
-            process_table: ProcessTableState::default(),
+            process_table,
+            process_control: ProcessControl::default(),
+            status: None,
+            confirmation: None,
+            priority_dialog: None,
+            affinity_dialog: None,
+            show_details: false,
+            details: None,
+            protected_input,
+            shortcut_inputs,
+            save_filter_name: None,
+            pin_input: String::new(),
+            pin_error: None,
             processes_icon: iced::widget::image::Handle::from_bytes(
                 include_bytes!("../assets/tetris-svgrepo-com.png").as_slice(),
             ),
@@ -95,66 +315,113 @@ impl Default for State {
 #[derive(Debug, Clone)]
 pub enum Message {
     Tick,
+    RefreshNow,
+    TogglePause,
+    UpdateSpeedSelected(UpdateSpeed),
     SelectCpu,
+    CpuGraphModeSelected(CpuGraphMode),
     SelectMemory,
-    SelectDisk,
+    SelectDisk(PathBuf),
+    SelectNetwork(String),
     OpenProcesses,
     OpenPerformance,
     OpenSettings,
     EndTask,
+    ProcessActionSelected(ProcessAction),
+    SignalSelected(Signal),
+    ConfirmAction,
+    CancelAction,
+    CheckEscalations,
+    OpenPriorityDialog,
+    PriorityClassSelected(PriorityClass),
+    NiceChanged(i32),
+    IoClassSelected(IoClass),
+    IoLevelChanged(u8),
+    ApplyPriority,
+    OpenAffinityDialog,
+    AffinityCpuToggled(usize, bool),
+    AffinityAllToggled(bool),
+    AffinityThreadsToggled(bool),
+    ApplyAffinity,
+    ToggleDetails,
+    CopySelection,
+    CopyRows,
+    ExportSelected(ExportFormat),
+    /// The path written to, or `None` if the save dialog was cancelled.
+    Exported(Result<Option<PathBuf>, String>),
     ProcessTable(ProcessTableMessage),
     ThemeSelected(Theme),
+    DefaultViewSelected(DefaultView),
+    UnitsSelected(Units),
+    HistoryLengthSelected(HistoryLength),
+    ProtectedProcessesChanged(String),
+    ProtectedProcessesSubmitted,
+    ProtectedPolicySelected(ProtectedPolicy),
+    KeyPressed {
+        key: Key,
+        physical_key: Physical,
+        modifiers: Modifiers,
+    },
+    ShortcutChanged(Command, String),
+    OpenSaveFilter,
+    SaveFilterNameChanged(String),
+    SaveFilter,
+    ApplySavedFilter(usize),
+    RemoveSavedFilter(usize),
+    TogglePinSelected,
+    PinInputChanged(String),
+    PinSubmitted,
+    RemovePin(usize),
+    StartRecording,
+    /// The file to record to, or `None` if the dialog was cancelled.
+    RecordingPathChosen(Option<PathBuf>),
+    StopRecording,
+    OpenRecording,
+    /// The recording opened, or `None` if the dialog was cancelled.
+    RecordingLoaded(Result<Option<(PathBuf, Arc<Session>)>, String>),
+    ReplayTick,
+    ReplayPlayPause,
+    ReplaySeek(u32),
+    ReplaySpeedSelected(ReplaySpeed),
+    CloseReplay,
 }
 
 pub fn update(state: &mut State, message: Message) -> Task<Message> {
     match message {
         Message::Tick => {
-            state.sys.refresh_cpu_usage();
-            state.sys.refresh_memory();
-            state.sys.refresh_processes(ProcessesToUpdate::All, true);
-
-            state.cpu_usage = state.sys.global_cpu_usage();
-            state.cpu_history.push(state.cpu_usage as f64);
-
-            state.memory_usage = memory_usage_percent(
-                state.sys.used_memory() as f64,
-                state.sys.total_memory() as f64,
-            );
-            state.memory_history.push(state.memory_usage);
-
-            state.uptime = format_uptime(sysinfo::System::uptime());
-
-            for cpu in state.sys.cpus() {
-                state.cpu_frequency = mhz_to_ghz(cpu.frequency());
+            if !state.paused && state.replay.is_none() {
+                refresh(state);
             }
-
-            for disk in state.disks.list() {
-                let read = disk.usage().read_bytes;
-                let written = disk.usage().written_bytes;
-
-                let read_delta = read.saturating_sub(state.prev_disk_read);
-                let written_delta = written.saturating_sub(state.prev_disk_written);
-
-                state.disk_usage = (read_delta + written_delta) as f64 / 1_000_000.0;
-                state.disk_history.push(state.disk_usage);
-                state.disk_size = bytes_to_gb(disk.total_space());
-                state.disk_available_space = bytes_to_gb(disk.available_space());
-
-                state.prev_disk_read = read;
-                state.prev_disk_written = written;
+        }
+        Message::RefreshNow => {
+            if state.replay.is_none() {
+                refresh(state);
             }
-
-            state.process_table.rows = collect_processes(&state.sys);
-            crate::process_table::apply_filter(&mut state.process_table);
+        }
+        Message::TogglePause => match &mut state.replay {
+            Some(replay) => replay.toggle_playing(),
+            None => state.paused = !state.paused,
+        },
+        Message::UpdateSpeedSelected(UpdateSpeed::Paused) => state.paused = true,
+        Message::UpdateSpeedSelected(UpdateSpeed::Every(ms)) => {
+            state.paused = false;
+            state.settings.refresh_interval_ms = ms;
+            save_settings(state);
         }
         Message::SelectCpu => {
             state.selected_tab = SelectedTab::Cpu;
         }
+        Message::CpuGraphModeSelected(mode) => {
+            state.cpu_graph_mode = mode;
+        }
         Message::SelectMemory => {
             state.selected_tab = SelectedTab::Memory;
         }
-        Message::SelectDisk => {
-            state.selected_tab = SelectedTab::Disk;
+        Message::SelectDisk(mount_point) => {
+            state.selected_tab = SelectedTab::Disk(mount_point);
+        }
+        Message::SelectNetwork(name) => {
+            state.selected_tab = SelectedTab::Network(name);
         }
         Message::OpenProcesses => {
             state.selected_view = SelectedView::Processes;
@@ -165,23 +432,954 @@ pub fn update(state: &mut State, message: Message) -> Task<Message> {
         Message::OpenSettings => {
             state.selected_view = SelectedView::Settings;
         }
-        Message::EndTask => {
-            if let Some(pid) = state.process_table.selected_pid {
-                if let Some(process) = state.sys.process(Pid::from_u32(pid)) {
-                    process.kill();
+        Message::EndTask => request_action(state, ProcessAction::Terminate),
+        Message::ProcessActionSelected(action) => request_action(state, action),
+        Message::SignalSelected(signal) => request_action(state, ProcessAction::Signal(signal)),
+        Message::ConfirmAction => {
+            if let Some(mut confirmation) = state.confirmation.take() {
+                if confirmation.protection.is_some() && !confirmation.confirmed_once {
+                    confirmation.confirmed_once = true;
+                    state.confirmation = Some(confirmation);
+                } else {
+                    let action = confirmation.action;
+                    let reports = confirmation
+                        .targets
+                        .iter()
+                        .map(|target| {
+                            state.process_control.send_checked(
+                                &mut state.sampler.sys,
+                                target,
+                                action,
+                            )
+                        })
+                        .collect();
+                    state.status = Some(BulkReport::from_reports(action, reports).into());
+                    if matches!(
+                        action,
+                        ProcessAction::Terminate | ProcessAction::EndTree | ProcessAction::Kill
+                    ) {
+                        state.process_table.clear_selection();
+                    }
+                }
+            }
+        }
+        Message::CancelAction => {
+            state.confirmation = None;
+            state.priority_dialog = None;
+            state.affinity_dialog = None;
+            state.save_filter_name = None;
+            state.process_table.column_menu = false;
+        }
+        Message::CheckEscalations => {
+            let reports = state.process_control.poll(&mut state.sampler.sys);
+            if !reports.is_empty() {
+                state.status = Some(BulkReport::from_escalations(reports).into());
+            }
+        }
+        Message::OpenPriorityDialog => open_priority_dialog(state),
+        Message::PriorityClassSelected(class) => {
+            if let Some(dialog) = &mut state.priority_dialog {
+                dialog.nice = class.nice();
+            }
+        }
+        Message::NiceChanged(nice) => {
+            if let Some(dialog) = &mut state.priority_dialog {
+                dialog.nice = nice;
+            }
+        }
+        Message::IoClassSelected(class) => {
+            if let Some(io) = state.priority_dialog.as_mut().and_then(|d| d.io.as_mut()) {
+                io.class = class;
+            }
+        }
+        Message::IoLevelChanged(level) => {
+            if let Some(io) = state.priority_dialog.as_mut().and_then(|d| d.io.as_mut()) {
+                io.level = level;
+            }
+        }
+        Message::ApplyPriority => {
+            if let Some(dialog) = state.priority_dialog.take() {
+                apply_priority(state, dialog);
+            }
+        }
+        Message::OpenAffinityDialog => open_affinity_dialog(state),
+        Message::AffinityCpuToggled(cpu, allowed) => {
+            if let Some(allowed_cpu) = state
+                .affinity_dialog
+                .as_mut()
+                .and_then(|dialog| dialog.cpus.get_mut(cpu))
+            {
+                *allowed_cpu = allowed;
+            }
+        }
+        Message::AffinityAllToggled(allowed) => {
+            if let Some(dialog) = &mut state.affinity_dialog {
+                dialog.cpus.fill(allowed);
+            }
+        }
+        Message::AffinityThreadsToggled(all_threads) => {
+            if let Some(dialog) = &mut state.affinity_dialog {
+                dialog.all_threads = all_threads;
+            }
+        }
+        Message::ApplyAffinity => {
+            if let Some(dialog) = state.affinity_dialog.take() {
+                apply_affinity(state, dialog);
+            }
+        }
+        Message::ToggleDetails => {
+            state.show_details = !state.show_details;
+            update_details(state);
+        }
+        Message::CopySelection => {
+            let info = state.process_table.selection_info();
+            if !info.is_empty() {
+                let count = state.process_table.selection.len();
+                state.status = Some(StatusLine {
+                    message: format!("Copied {count} processes to the clipboard"),
+                    success: true,
+                });
+                return iced::clipboard::write(info);
+            }
+        }
+        Message::CopyRows => {
+            let rows = state.process_table.selected_rows();
+            if !rows.is_empty() {
+                let tsv = export::to_tsv(
+                    &state.process_table.visible_columns(),
+                    &rows,
+                    state.settings.units,
+                );
+                state.status = Some(StatusLine {
+                    message: format!(
+                        "Copied {} as tab-separated text",
+                        match rows.len() {
+                            1 => "1 row".to_string(),
+                            n => format!("{n} rows"),
+                        }
+                    ),
+                    success: true,
+                });
+                return iced::clipboard::write(tsv);
+            }
+        }
+        Message::ExportSelected(format) => {
+            // Taken now, so the file holds what was on screen when Export
+            // was chosen.
+            let contents = export::export(
+                format,
+                &state.process_table.visible_columns(),
+                &state.process_table.filtered_rows,
+                state.settings.units,
+            );
+            return Task::perform(save_export(format, contents), Message::Exported);
+        }
+        Message::StartRecording => {
+            return Task::perform(choose_recording_path(), Message::RecordingPathChosen);
+        }
+        Message::RecordingPathChosen(Some(path)) => {
+            state.status = Some(match Recorder::create(&path) {
+                Ok(recorder) => {
+                    state.recorder = Some(recorder);
+                    StatusLine {
+                        message: format!("Recording to {}", path.display()),
+                        success: true,
+                    }
                 }
-                state.process_table.selected_pid = None;
+                Err(err) => StatusLine {
+                    message: format!("Could not record to {}: {err}", path.display()),
+                    success: false,
+                },
+            });
+        }
+        Message::RecordingPathChosen(None) => {}
+        Message::StopRecording => stop_recording(state),
+        Message::OpenRecording => {
+            return Task::perform(open_recording(), Message::RecordingLoaded);
+        }
+        Message::RecordingLoaded(Ok(Some((path, session)))) => start_replay(state, path, session),
+        Message::RecordingLoaded(Ok(None)) => {}
+        Message::RecordingLoaded(Err(message)) => {
+            state.status = Some(StatusLine {
+                message,
+                success: false,
+            });
+        }
+        Message::ReplayTick => step_replay(state),
+        Message::ReplayPlayPause => {
+            if let Some(replay) = &mut state.replay {
+                replay.toggle_playing();
             }
         }
+        Message::ReplaySeek(index) => {
+            if let Some(replay) = &mut state.replay {
+                replay.seek(index as usize);
+                rebuild_replay(state);
+            }
+        }
+        Message::ReplaySpeedSelected(speed) => {
+            if let Some(replay) = &mut state.replay {
+                replay.speed = speed;
+            }
+        }
+        Message::CloseReplay => close_replay(state),
+        Message::Exported(result) => {
+            state.status = match result {
+                Ok(Some(path)) => Some(StatusLine {
+                    message: format!("Exported the process table to {}", path.display()),
+                    success: true,
+                }),
+                Ok(None) => None,
+                Err(message) => Some(StatusLine {
+                    message,
+                    success: false,
+                }),
+            };
+        }
         Message::ProcessTable(msg) => {
-            return crate::process_table::update(&mut state.process_table, msg)
+            let columns_changed = msg.changes_columns();
+            let slow_column_shown = matches!(
+                msg,
+                ProcessTableMessage::ToggleColumn(kind, true)
+                    if SlowColumns::among([kind]) != SlowColumns::default()
+            );
+            let selected = matches!(
+                msg,
+                ProcessTableMessage::RowSelected(_)
+                    | ProcessTableMessage::GroupSelected(_)
+                    | ProcessTableMessage::Navigate(_)
+                    | ProcessTableMessage::TypeAhead(_)
+            );
+            let task = crate::process_table::update(&mut state.process_table, msg)
                 .map(Message::ProcessTable);
+
+            if slow_column_shown {
+                refresh_process_table(state);
+            }
+            if columns_changed {
+                state.settings.columns = state.process_table.column_settings();
+                save_settings(state);
+            }
+            if selected {
+                update_details(state);
+            }
+            return task;
+        }
+        Message::ThemeSelected(theme) => {
+            state.theme_selected = Some(theme);
+            state.settings.theme = theme;
+            save_settings(state);
+        }
+        Message::DefaultViewSelected(view) => {
+            state.settings.default_view = view;
+            save_settings(state);
+        }
+        Message::UnitsSelected(units) => {
+            state.settings.units = units;
+            state.process_table.set_units(units);
+            save_settings(state);
+        }
+        Message::HistoryLengthSelected(HistoryLength(minutes)) => {
+            state.settings.history_minutes = minutes;
+            apply_retention(state);
+            save_settings(state);
+        }
+        Message::ProtectedProcessesChanged(input) => state.protected_input = input,
+        Message::ProtectedProcessesSubmitted => {
+            state.settings.protected_processes = state
+                .protected_input
+                .split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect();
+            state.protected_input = state.settings.protected_processes.join(", ");
+            save_settings(state);
+        }
+        Message::ProtectedPolicySelected(policy) => {
+            state.settings.protected_policy = policy;
+            save_settings(state);
+        }
+        Message::KeyPressed {
+            key,
+            physical_key,
+            modifiers,
+        } => return handle_key(state, &key, physical_key, modifiers),
+        Message::ShortcutChanged(command, input) => {
+            if let Ok(binding) = input.parse::<KeyBinding>() {
+                state.settings.keymap.set(command, binding);
+                save_settings(state);
+            }
+            state.shortcut_inputs.insert(command, input);
+        }
+        Message::OpenSaveFilter => state.save_filter_name = Some(String::new()),
+        Message::SaveFilterNameChanged(name) => state.save_filter_name = Some(name),
+        Message::SaveFilter => {
+            let name = state
+                .save_filter_name
+                .take()
+                .map(|name| name.trim().to_string())
+                .unwrap_or_default();
+            if !name.is_empty() {
+                let query = state.process_table.search_query.clone();
+                let filters = &mut state.settings.saved_filters;
+                match filters.iter_mut().find(|f| f.name == name) {
+                    Some(filter) => filter.query = query,
+                    None => filters.push(SavedFilter { name, query }),
+                }
+                save_settings(state);
+            }
+        }
+        Message::ApplySavedFilter(index) => {
+            if let Some(filter) = state.settings.saved_filters.get(index) {
+                // Clicking the active chip clears the search again.
+                let query = if state.process_table.search_query == filter.query {
+                    String::new()
+                } else {
+                    filter.query.clone()
+                };
+                return update(
+                    state,
+                    Message::ProcessTable(ProcessTableMessage::SearchChanged(query)),
+                );
+            }
+        }
+        Message::RemoveSavedFilter(index) => {
+            if index < state.settings.saved_filters.len() {
+                state.settings.saved_filters.remove(index);
+                save_settings(state);
+            }
+        }
+        Message::TogglePinSelected => toggle_pin_selected(state),
+        Message::PinInputChanged(input) => {
+            state.pin_error = Query::parse(&input).err();
+            state.pin_input = input;
+        }
+        Message::PinSubmitted => {
+            let pattern = state.pin_input.trim().to_string();
+            match Query::parse(&pattern) {
+                Ok(query) if !query.is_empty() => {
+                    if !state.settings.pinned_processes.contains(&pattern) {
+                        state.settings.pinned_processes.push(pattern);
+                    }
+                    state.pin_input.clear();
+                    state.pin_error = None;
+                    apply_pins(state);
+                }
+                Ok(_) => {}
+                Err(err) => state.pin_error = Some(err),
+            }
+        }
+        Message::RemovePin(index) => {
+            if index < state.settings.pinned_processes.len() {
+                state.settings.pinned_processes.remove(index);
+                apply_pins(state);
+            }
         }
-        Message::ThemeSelected(theme) => state.theme_selected = Some(theme),
     }
     Task::none()
 }
 
+/// Asks where to save an export and writes it there.
+async fn save_export(format: ExportFormat, contents: String) -> Result<Option<PathBuf>, String> {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .set_title("Export processes")
+        .set_file_name(format!("processes.{}", format.extension()))
+        .add_filter(format.to_string(), &[format.extension()])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let path = file.path().to_path_buf();
+    std::fs::write(&path, contents)
+        .map_err(|err| format!("Could not write {}: {err}", path.display()))?;
+    Ok(Some(path))
+}
+
+/// Unpins the selected processes if every one of them is pinned, and pins
+/// them by name otherwise.
+fn toggle_pin_selected(state: &mut State) {
+    let selected: Vec<&ProcessInfo> = state
+        .process_table
+        .rows
+        .iter()
+        .filter(|r| state.process_table.selection.contains(&r.pid))
+        .collect();
+    if selected.is_empty() {
+        return;
+    }
+
+    let pins = &mut state.settings.pinned_processes;
+    let is_pinned = |row: &ProcessInfo| state.process_table.pins.iter().any(|pin| pin.matches(row));
+    let message = if selected.iter().all(|row| is_pinned(row)) {
+        pins.retain(|pattern| {
+            Query::parse(pattern).map_or(true, |pin| !selected.iter().any(|row| pin.matches(row)))
+        });
+        format!("Unpinned {}", process_count(selected.len()))
+    } else {
+        for row in &selected {
+            let pattern = format!("name={}", quote(&row.name));
+            if !pins.contains(&pattern) {
+                pins.push(pattern);
+            }
+        }
+        format!("Pinned {}", process_count(selected.len()))
+    };
+
+    state.status = Some(StatusLine {
+        message,
+        success: true,
+    });
+    apply_pins(state);
+}
+
+fn process_count(count: usize) -> String {
+    match count {
+        1 => "1 process".to_string(),
+        n => format!("{n} processes"),
+    }
+}
+
+/// Pushes the pinned processes setting to the table and saves it.
+fn apply_pins(state: &mut State) {
+    state
+        .process_table
+        .set_pins(&state.settings.pinned_processes);
+    apply_filter(&mut state.process_table);
+    save_settings(state);
+}
+
+fn shortcut_inputs(settings: &Settings) -> BTreeMap<Command, String> {
+    Command::ALL
+        .into_iter()
+        .filter_map(|command| {
+            let binding = settings.keymap.binding(command)?;
+            Some((command, binding.to_string()))
+        })
+        .collect()
+}
+
+/// Runs the command bound to a key press. While a dialog is open only
+/// Cancel works, so keys can't act on the processes behind it. On the
+/// Processes view, other unmodified characters jump to a process by name.
+fn handle_key(
+    state: &mut State,
+    key: &Key,
+    physical_key: Physical,
+    modifiers: Modifiers,
+) -> Task<Message> {
+    let command = state.settings.keymap.command(key, physical_key, modifiers);
+    let dialog_open = state.confirmation.is_some()
+        || state.priority_dialog.is_some()
+        || state.affinity_dialog.is_some()
+        || state.save_filter_name.is_some()
+        || state.process_table.column_menu;
+    let on_processes = state.selected_view == SelectedView::Processes;
+
+    let Some(command) = command else {
+        if let Key::Character(typed) = key
+            && on_processes
+            && !dialog_open
+            && !modifiers.command()
+            && !modifiers.alt()
+            && let Some(c) = typed.chars().next()
+        {
+            return update(
+                state,
+                Message::ProcessTable(ProcessTableMessage::TypeAhead(c)),
+            );
+        }
+        return Task::none();
+    };
+    if dialog_open && command != Command::Cancel {
+        return Task::none();
+    }
+
+    let navigate = |navigation| Message::ProcessTable(ProcessTableMessage::Navigate(navigation));
+    let message = match command {
+        Command::MoveUp if on_processes => navigate(Navigation::Up),
+        Command::MoveDown if on_processes => navigate(Navigation::Down),
+        Command::PageUp if on_processes => navigate(Navigation::PageUp),
+        Command::PageDown if on_processes => navigate(Navigation::PageDown),
+        Command::Home if on_processes => navigate(Navigation::Home),
+        Command::End if on_processes => navigate(Navigation::End),
+        Command::SelectAll if on_processes => Message::ProcessTable(ProcessTableMessage::SelectAll),
+        Command::EndTask if on_processes => Message::EndTask,
+        Command::FocusSearch => {
+            state.selected_view = SelectedView::Processes;
+            return iced::widget::operation::focus(state.process_table.search.clone());
+        }
+        Command::ShowProcesses => Message::OpenProcesses,
+        Command::ShowPerformance => Message::OpenPerformance,
+        Command::ShowSettings => Message::OpenSettings,
+        Command::TogglePause => Message::TogglePause,
+        Command::Refresh => Message::RefreshNow,
+        Command::Cancel => Message::CancelAction,
+        _ => return Task::none(),
+    };
+    update(state, message)
+}
+
+/// Samples every metric once and pushes the results into the histories and
+/// the process table.
+fn refresh(state: &mut State) {
+    state.sampler.slow_columns = state.process_table.slow_columns();
+    let snapshot = state.sampler.sample(true);
+
+    apply_snapshot(state, &snapshot, Instant::now());
+    state.uptime = format_uptime(snapshot.uptime);
+    record_frame(state, &snapshot);
+
+    state.process_table.rows = snapshot.processes;
+    crate::process_table::apply_filter(&mut state.process_table);
+    update_details(state);
+}
+
+/// How far playback moves on per replay tick, before the speed is applied.
+const REPLAY_TICK: Duration = Duration::from_millis(100);
+
+/// Appends the tick just sampled to the open recording. A failed write
+/// stops the recording.
+fn record_frame(state: &mut State, snapshot: &SystemSnapshot) {
+    let Some(recorder) = &mut state.recorder else {
+        return;
+    };
+
+    if let Err(err) = recorder.append(snapshot.clone().into()) {
+        state.status = Some(StatusLine {
+            message: format!(
+                "Recording stopped, could not write {}: {err}",
+                recorder.path().display()
+            ),
+            success: false,
+        });
+        state.recorder = None;
+    }
+}
+
+fn stop_recording(state: &mut State) {
+    let Some(recorder) = state.recorder.take() else {
+        return;
+    };
+
+    let path = recorder.path().to_path_buf();
+    let frames = recorder.frames();
+    state.status = Some(match recorder.finish() {
+        Ok(()) => StatusLine {
+            message: format!("Saved {frames} samples to {}", path.display()),
+            success: true,
+        },
+        Err(err) => StatusLine {
+            message: format!("Could not finish {}: {err}", path.display()),
+            success: false,
+        },
+    });
+}
+
+async fn choose_recording_path() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Record session")
+        .set_file_name(format!("session.{}", session::EXTENSION))
+        .add_filter("Recording", &[session::EXTENSION])
+        .save_file()
+        .await
+        .map(|file| file.path().to_path_buf())
+}
+
+/// Asks for a recording and loads it.
+async fn open_recording() -> Result<Option<(PathBuf, Arc<Session>)>, String> {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .set_title("Open recording")
+        .add_filter("Recording", &[session::EXTENSION])
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let path = file.path().to_path_buf();
+    let session =
+        Session::load(&path).map_err(|err| format!("Could not open {}: {err}", path.display()))?;
+    Ok(Some((path, Arc::new(session))))
+}
+
+/// Swaps live data for a recording. No ticks are sampled while replaying,
+/// so a running recording is stopped first.
+fn start_replay(state: &mut State, path: PathBuf, session: Arc<Session>) {
+    stop_recording(state);
+    if session.truncated {
+        state.status = Some(StatusLine {
+            message: format!(
+                "{} ends part way through a sample, showing the samples before it",
+                path.display()
+            ),
+            success: false,
+        });
+    }
+    state.replay = Some(Replay::new(path, session));
+    rebuild_replay(state);
+}
+
+/// Goes back to live data. The charts start over, as the live samples
+/// from before the replay aren't kept.
+fn close_replay(state: &mut State) {
+    state.replay = None;
+    clear_histories(state);
+    refresh(state);
+}
+
+/// Moves playback on and feeds every frame passed into the charts.
+fn step_replay(state: &mut State) {
+    let Some(mut replay) = state.replay.take() else {
+        return;
+    };
+
+    let previous = replay.position();
+    replay.advance(REPLAY_TICK);
+    if replay.position() != previous {
+        for frame in &replay.frames()[previous + 1..=replay.position()] {
+            apply_snapshot(state, &frame.snapshot, replay.time_of(frame));
+        }
+        show_processes(state, &replay);
+    }
+    state.replay = Some(replay);
+}
+
+/// Refills the charts with the frames leading up to the current one, as
+/// after opening a recording or seeking.
+fn rebuild_replay(state: &mut State) {
+    let Some(replay) = state.replay.take() else {
+        return;
+    };
+
+    clear_histories(state);
+    let start = replay.window_start(state.settings.retention());
+    for frame in &replay.frames()[start..=replay.position()] {
+        apply_snapshot(state, &frame.snapshot, replay.time_of(frame));
+    }
+    show_processes(state, &replay);
+    state.replay = Some(replay);
+}
+
+fn clear_histories(state: &mut State) {
+    let retention = state.settings.retention();
+    state.cpu_history = History::new(retention);
+    state.memory_history = History::new(retention);
+    state.cores.clear();
+    state.disk_states.clear();
+    state.network_states.clear();
+}
+
+/// Feeds a live or recorded snapshot into the charts, leaving the process
+/// table alone.
+fn apply_snapshot(state: &mut State, snapshot: &SystemSnapshot, time: Instant) {
+    let retention = state.settings.retention();
+
+    state.cpu_usage = snapshot.cpu.usage;
+    state.cpu_history.push_at(time, snapshot.cpu.usage as f64);
+
+    state.used_memory = snapshot.memory.used;
+    state.total_memory = snapshot.memory.total;
+    state.memory_usage = snapshot.memory.usage_percent();
+    state.memory_history.push_at(time, state.memory_usage);
+
+    sampler::record_cores(&mut state.cores, &snapshot.cpu.cores, time, retention);
+    state.cpu_frequency = average_frequency(&state.cores);
+    state.num_of_cpus = state.cores.len() as i32;
+
+    sampler::record_disks(&mut state.disk_states, &snapshot.disks, time, retention);
+    sampler::record_networks(
+        &mut state.network_states,
+        &snapshot.networks,
+        time,
+        retention,
+    );
+}
+
+fn show_processes(state: &mut State, replay: &Replay) {
+    state.uptime = format_uptime(replay.frame().snapshot.uptime);
+    state.process_table.rows = replay.processes().unwrap_or_else(|err| {
+        state.status = Some(StatusLine {
+            message: format!(
+                "Could not read the processes from {}: {err}",
+                replay.path().display()
+            ),
+            success: false,
+        });
+        Vec::new()
+    });
+    crate::process_table::apply_filter(&mut state.process_table);
+    state.details = None;
+}
+
+/// Process actions would hit whichever process has a recorded PID now, so
+/// they are turned down while replaying.
+fn refuse_while_replaying(state: &mut State) -> bool {
+    if state.replay.is_some() {
+        state.status = Some(StatusLine {
+            message: "Process actions aren't available while replaying a recording".to_string(),
+            success: false,
+        });
+    }
+    state.replay.is_some()
+}
+
+fn refresh_process_table(state: &mut State) {
+    state.sampler.slow_columns = state.process_table.slow_columns();
+    state.process_table.rows = state.sampler.processes();
+    crate::process_table::apply_filter(&mut state.process_table);
+    update_details(state);
+}
+
+fn update_details(state: &mut State) {
+    state.details = match state.process_table.selected_pid {
+        Some(pid) if state.show_details && state.replay.is_none() => ProcessDetails::collect(
+            &mut state.sampler.sys,
+            &state.sampler.users,
+            &state.groups,
+            pid,
+        ),
+        _ => None,
+    };
+}
+
+/// Applies `action` to every selected process, where a selected app group
+/// stands for all of its processes. Actions that could end a process are
+/// confirmed once for the whole selection, and if any of it is protected
+/// the action is refused or asked about twice, depending on the settings.
+/// Suspending or resuming a protected process is always refused, as there
+/// is no confirmation to ask twice.
+fn request_action(state: &mut State, action: ProcessAction) {
+    if refuse_while_replaying(state) {
+        return;
+    }
+    let pids = state.process_table.selected_pids();
+    let Some(&first) = pids.first() else {
+        return;
+    };
+
+    // Group members that exited in the meantime are simply left out.
+    let targets: Vec<ProcessTarget> = pids
+        .into_iter()
+        .filter_map(|pid| ProcessTarget::capture(&mut state.sampler.sys, &state.sampler.users, pid))
+        .collect();
+    if targets.is_empty() {
+        state.status = Some(
+            ActionReport {
+                pid: first,
+                name: String::new(),
+                action,
+                outcome: ActionOutcome::AlreadyExited,
+            }
+            .into(),
+        );
+        return;
+    }
+
+    let protected = targets.iter().find_map(|target| {
+        protection(
+            &state.sampler.sys,
+            target.pid,
+            action,
+            &state.settings.protected_processes,
+        )
+        .map(|reason| (target, reason))
+    });
+    if let Some((target, reason)) = &protected
+        && (state.settings.protected_policy == ProtectedPolicy::Refuse
+            || !action.needs_confirmation())
+    {
+        state.status = Some(
+            ActionReport {
+                pid: target.pid,
+                name: target.name.clone(),
+                action,
+                outcome: ActionOutcome::Protected(reason.clone()),
+            }
+            .into(),
+        );
+        return;
+    }
+
+    if !action.needs_confirmation() {
+        let reports = targets
+            .iter()
+            .map(|target| {
+                state
+                    .process_control
+                    .send_checked(&mut state.sampler.sys, target, action)
+            })
+            .collect();
+        state.status = Some(BulkReport::from_reports(action, reports).into());
+        return;
+    }
+
+    let protection = protected.map(|(_, reason)| reason);
+    state.confirmation = Some(Confirmation {
+        targets,
+        action,
+        protection,
+        confirmed_once: false,
+    });
+}
+
+/// Opens the priority dialog for the selection, showing the current values
+/// of the focused process.
+fn open_priority_dialog(state: &mut State) {
+    if refuse_while_replaying(state) {
+        return;
+    }
+    let mut pids = state.process_table.selected_pids();
+    if let Some(focused) = state.process_table.selected_pid
+        && let Some(index) = pids.iter().position(|&pid| pid == focused)
+    {
+        pids.swap(0, index);
+    }
+
+    let targets: Vec<ProcessTarget> = pids
+        .into_iter()
+        .filter_map(|pid| ProcessTarget::capture(&mut state.sampler.sys, &state.sampler.users, pid))
+        .collect();
+    let Some(first) = targets.first() else {
+        return;
+    };
+    let pid = first.pid;
+
+    let nice = match priority::nice(pid) {
+        Ok(nice) => nice,
+        Err(err) => {
+            let mut report = BulkReport::new("Set priority");
+            report.push(pid, &first.name, err.into());
+            state.status = Some(report.into());
+            return;
+        }
+    };
+    let io = priority::io_priority(pid).ok().map(|io| io.effective(nice));
+
+    state.priority_dialog = Some(PriorityDialog {
+        targets,
+        nice,
+        initial_nice: nice,
+        io,
+        initial_io: io,
+    });
+}
+
+/// Applies whatever was changed in the priority dialog to every target
+/// whose PID still belongs to the same process.
+fn apply_priority(state: &mut State, dialog: PriorityDialog) {
+    let mut report = BulkReport::new("Set priority");
+
+    for target in &dialog.targets {
+        let pid = target.pid;
+        let mut result = target.check(&mut state.sampler.sys);
+        if result.is_ok() && dialog.nice != dialog.initial_nice {
+            result = priority::set_nice(pid, dialog.nice).map_err(ActionOutcome::from);
+        }
+        if result.is_ok()
+            && dialog.io != dialog.initial_io
+            && let Some(io) = dialog.io
+        {
+            result = priority::set_io_priority(pid, io).map_err(ActionOutcome::from);
+        }
+        report.push(
+            pid,
+            &target.name,
+            result.err().unwrap_or(ActionOutcome::Sent),
+        );
+    }
+
+    let changed = report
+        .results
+        .iter()
+        .any(|(_, _, outcome)| outcome.is_success());
+    state.status = Some(report.into());
+
+    if changed {
+        refresh_process_table(state);
+    }
+}
+
+fn open_affinity_dialog(state: &mut State) {
+    if refuse_while_replaying(state) {
+        return;
+    }
+    let Some(pid) = state.process_table.selected_pid else {
+        return;
+    };
+    let Some(target) = ProcessTarget::capture(&mut state.sampler.sys, &state.sampler.users, pid)
+    else {
+        return;
+    };
+
+    match affinity::affinity(pid, state.num_of_cpus as usize) {
+        Ok(cpus) => {
+            state.affinity_dialog = Some(AffinityDialog {
+                target,
+                cpus,
+                all_threads: true,
+            })
+        }
+        Err(err) => {
+            state.status = Some(StatusLine {
+                message: format!(
+                    "Set affinity — {} ({pid}): {}",
+                    target.name,
+                    ActionOutcome::from(err)
+                ),
+                success: false,
+            })
+        }
+    }
+}
+
+fn apply_affinity(state: &mut State, dialog: AffinityDialog) {
+    let pid = dialog.target.pid;
+
+    let result = dialog.target.check(&mut state.sampler.sys).and_then(|()| {
+        if dialog.all_threads {
+            affinity::set_affinity_all_threads(pid, &dialog.cpus)
+        } else {
+            affinity::set_affinity(pid, &dialog.cpus)
+        }
+        .map_err(ActionOutcome::from)
+    });
+
+    let outcome = result.err().unwrap_or(ActionOutcome::Sent);
+    state.status = Some(StatusLine {
+        message: format!("Set affinity — {} ({pid}): {outcome}", dialog.target.name),
+        success: outcome.is_success(),
+    });
+}
+
+/// Writes the settings to disk. Failures are shown on the Settings page
+/// rather than interrupting whatever the user was doing.
+fn save_settings(state: &mut State) {
+    state.settings_warnings = match state.settings.save() {
+        Ok(()) => Vec::new(),
+        Err(err) => vec![format!("Could not save settings: {err}")],
+    };
+}
+
+fn apply_retention(state: &mut State) {
+    let retention = state.settings.retention();
+
+    state.cpu_history.set_retention(retention);
+    state.memory_history.set_retention(retention);
+    for core in &mut state.cores {
+        core.usage_history.set_retention(retention);
+        core.frequency_history.set_retention(retention);
+    }
+    for disk in &mut state.disk_states {
+        disk.history.set_retention(retention);
+    }
+    for network in &mut state.network_states {
+        network.rx_history.set_retention(retention);
+        network.tx_history.set_retention(retention);
+    }
+}
+
 fn tail(data: &[f64], max: usize) -> &[f64] {
     if data.len() > max {
         &data[data.len() - max..]
@@ -190,10 +1388,22 @@ fn tail(data: &[f64], max: usize) -> &[f64] {
     }
 }
 
+/// Upper bound for charts whose values aren't percentages: the largest
+/// visible sample plus some headroom, never below `floor`.
+fn scale_max(series: &[&[f64]], floor: f64) -> f64 {
+    let peak = series
+        .iter()
+        .flat_map(|s| s.iter().copied())
+        .fold(0.0, f64::max);
+    (peak * 1.2).max(floor)
+}
+
 pub fn view(state: &State) -> Element<'_, Message, Theme> {
     let cpu_color = RGBColor(0, 255, 255);
     let mem_color = RGBColor(180, 0, 255);
     let disk_color = RGBColor(53, 189, 60);
+    let rx_color = RGBColor(255, 140, 0);
+    let tx_color = RGBColor(220, 80, 160);
 
     // Sidebar with icon buttons
     let processes_icon = iced::widget::image(state.processes_icon.clone())
@@ -296,16 +1506,20 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
 
     // Tab selector panel
     let cpu_thumb: Element<'_, Message, Theme> = ChartWidget::new(ThumbChart {
-        data: tail(&state.cpu_history, 60),
+        data: tail(state.cpu_history.values(), 60),
         color: cpu_color,
+        max_size: 100.0,
+        overlay: None,
     })
     .width(Length::Fixed(120.0))
     .height(Length::Fixed(50.0))
     .into();
 
     let mem_thumb: Element<'_, Message, Theme> = ChartWidget::new(ThumbChart {
-        data: tail(&state.memory_history, 60),
+        data: tail(state.memory_history.values(), 60),
         color: mem_color,
+        max_size: 100.0,
+        overlay: None,
     })
     .width(Length::Fixed(120.0))
     .height(Length::Fixed(50.0))
@@ -366,36 +1580,98 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
                 ..Default::default()
             });
         }
-        None => {}
-    }
+        None => {}
+    }
+
+    let mut tab_children: Vec<Element<'_, Message, Theme>> = vec![cpu_btn.into(), mem_btn.into()];
+
+    for disk in &state.disk_states {
+        let disk_thumb: Element<'_, Message, Theme> = ChartWidget::new(ThumbChart {
+            data: tail(disk.history.values(), 60),
+            color: disk_color,
+            max_size: 100.0,
+            overlay: None,
+        })
+        .width(Length::Fixed(120.0))
+        .height(Length::Fixed(50.0))
+        .into();
+
+        let disk_label = column![
+            text(&disk.name).wrapping(text::Wrapping::Glyph).size(16),
+            text(disk.mount_point.display().to_string())
+                .wrapping(text::Wrapping::Glyph)
+                .size(12),
+        ];
+
+        let mut disk_btn = button(
+            row![disk_thumb, disk_label]
+                .spacing(10)
+                .align_y(Alignment::Center),
+        )
+        .on_press(Message::SelectDisk(disk.mount_point.clone()))
+        .width(Length::Fill);
+
+        match state.theme_selected {
+            Some(Theme::Light) => {
+                disk_btn = disk_btn.style(|_: &Theme, status| button::Style {
+                    background: Some(Background::Color(match status {
+                        button::Status::Hovered => LIGHT_THEME_HOVER,
+                        _ => LIGHT_THEME_IDLE,
+                    })),
+                    text_color: Color::BLACK,
+                    ..Default::default()
+                });
+            }
+            Some(Theme::Dark) => {
+                disk_btn = disk_btn.style(|_: &Theme, status| button::Style {
+                    background: Some(Background::Color(match status {
+                        button::Status::Hovered => DARK_THEME_HOVER,
+                        _ => DARK_THEME_IDLE,
+                    })),
+                    text_color: Color::WHITE,
+                    ..Default::default()
+                });
+            }
+            None => {}
+        }
 
-    let mut tab_children: Vec<Element<'_, Message, Theme>> = vec![cpu_btn.into(), mem_btn.into()];
+        tab_children.push(disk_btn.into());
+    }
 
-    for disk in state.disks.list() {
-        let disk_name = disk.name().display().to_string();
+    for network in &state.network_states {
+        let rx = tail(network.rx_history.values(), 60);
+        let tx = tail(network.tx_history.values(), 60);
 
-        let disk_thumb: Element<'_, Message, Theme> = ChartWidget::new(ThumbChart {
-            data: tail(&state.disk_history, 60),
-            color: disk_color,
+        let network_thumb: Element<'_, Message, Theme> = ChartWidget::new(ThumbChart {
+            data: rx,
+            color: rx_color,
+            max_size: scale_max(&[rx, tx], 10.0),
+            overlay: Some((tx, tx_color)),
         })
         .width(Length::Fixed(120.0))
         .height(Length::Fixed(50.0))
         .into();
 
-        let mut disk_btn = button(
-            row![
-                disk_thumb,
-                text(disk_name).wrapping(text::Wrapping::Glyph).size(16)
-            ]
-            .spacing(10)
-            .align_y(Alignment::Center),
+        let network_label = column![
+            text(&network.name).wrapping(text::Wrapping::Glyph).size(16),
+            text(format!(
+                "S: {:.0} R: {:.0} KB/s",
+                network.transmitted, network.received
+            ))
+            .size(12),
+        ];
+
+        let mut network_btn = button(
+            row![network_thumb, network_label]
+                .spacing(10)
+                .align_y(Alignment::Center),
         )
-        .on_press(Message::SelectDisk)
+        .on_press(Message::SelectNetwork(network.name.clone()))
         .width(Length::Fill);
 
         match state.theme_selected {
             Some(Theme::Light) => {
-                disk_btn = disk_btn.style(|_: &Theme, status| button::Style {
+                network_btn = network_btn.style(|_: &Theme, status| button::Style {
                     background: Some(Background::Color(match status {
                         button::Status::Hovered => LIGHT_THEME_HOVER,
                         _ => LIGHT_THEME_IDLE,
@@ -405,7 +1681,7 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
                 });
             }
             Some(Theme::Dark) => {
-                disk_btn = disk_btn.style(|_: &Theme, status| button::Style {
+                network_btn = network_btn.style(|_: &Theme, status| button::Style {
                     background: Some(Background::Color(match status {
                         button::Status::Hovered => DARK_THEME_HOVER,
                         _ => DARK_THEME_IDLE,
@@ -417,14 +1693,14 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
             None => {}
         }
 
-        tab_children.push(disk_btn.into());
+        tab_children.push(network_btn.into());
     }
 
-    let tab_panel = container(
+    let tab_panel = container(scrollable(
         iced::widget::Column::with_children(tab_children)
             .spacing(10)
             .padding(10),
-    )
+    ))
     .width(220);
 
     // Main content area
@@ -465,36 +1741,189 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
                 None => {}
             }
 
-            let search_input = text_input("Search processes...", &state.process_table.search_query)
-                .on_input(|s| Message::ProcessTable(ProcessTableMessage::SearchChanged(s)))
-                .width(200);
+            let search_input = text_input(
+                "Search, e.g. cpu>20 mem>500MB user:root",
+                &state.process_table.search_query,
+            )
+            .id(state.process_table.search.clone())
+            .on_input(|s| Message::ProcessTable(ProcessTableMessage::SearchChanged(s)))
+            .width(300);
+            let search_input =
+                column![search_input].push(state.process_table.query_error.as_ref().map(|err| {
+                    text(err.to_string())
+                        .size(12)
+                        .color(Color::from_rgb(0.9, 0.3, 0.3))
+                }));
+
+            let mut save_search = button(text("Save search")).style(action_button_style);
+            if !state.process_table.query.is_empty() && state.process_table.query_error.is_none() {
+                save_search = save_search.on_press(Message::OpenSaveFilter);
+            }
+
+            let export = pick_list(
+                ExportFormat::ALL,
+                None::<ExportFormat>,
+                Message::ExportSelected,
+            )
+            .placeholder("Export");
+
+            let view_mode = pick_list(
+                ProcessViewMode::ALL,
+                Some(state.process_table.view_mode),
+                |mode| Message::ProcessTable(ProcessTableMessage::ViewModeSelected(mode)),
+            );
+
+            let actions = pick_list(
+                ProcessAction::MENU,
+                None::<ProcessAction>,
+                Message::ProcessActionSelected,
+            )
+            .placeholder("Actions");
+
+            let signals = pick_list(
+                sysinfo::SUPPORTED_SIGNALS,
+                None::<Signal>,
+                Message::SignalSelected,
+            )
+            .placeholder("Send signal");
+
+            let set_priority = button(text("Set priority"))
+                .on_press(Message::OpenPriorityDialog)
+                .style(action_button_style);
+
+            let set_affinity = button(text("Set affinity"))
+                .on_press(Message::OpenAffinityDialog)
+                .style(action_button_style);
+
+            let details = button(text(if state.show_details {
+                "Hide details"
+            } else {
+                "Details"
+            }))
+            .on_press(Message::ToggleDetails)
+            .style(action_button_style);
 
             let space_on_top = container(
-                row![end_btn, Space::new().width(Length::Fill), search_input]
-                    .align_y(Alignment::Center),
+                row![
+                    end_btn,
+                    Space::new().width(10),
+                    actions,
+                    Space::new().width(10),
+                    signals,
+                    Space::new().width(10),
+                    set_priority,
+                    Space::new().width(10),
+                    set_affinity,
+                    Space::new().width(10),
+                    details,
+                    Space::new().width(Length::Fill),
+                    export,
+                    Space::new().width(10),
+                    view_mode,
+                    Space::new().width(10),
+                    search_input,
+                    Space::new().width(10),
+                    save_search
+                ]
+                .align_y(Alignment::Center),
             );
 
+            let selected = state.process_table.selection.len();
+            let mut copy_info = button(text("Copy info")).style(action_button_style);
+            let mut copy_rows = button(text("Copy rows")).style(action_button_style);
+            let mut clear_selection = button(text("Clear")).style(action_button_style);
+            let all_pinned = state
+                .process_table
+                .rows
+                .iter()
+                .filter(|r| state.process_table.selection.contains(&r.pid))
+                .all(|r| state.process_table.pins.iter().any(|pin| pin.matches(r)));
+            let mut pin = button(text(if selected > 0 && all_pinned {
+                "Unpin"
+            } else {
+                "Pin"
+            }))
+            .style(action_button_style);
+            if selected > 0 {
+                pin = pin.on_press(Message::TogglePinSelected);
+                copy_info = copy_info.on_press(Message::CopySelection);
+                copy_rows = copy_rows.on_press(Message::CopyRows);
+                clear_selection = clear_selection
+                    .on_press(Message::ProcessTable(ProcessTableMessage::ClearSelection));
+            }
+            let selection_bar = row![
+                button(text("Select all"))
+                    .on_press(Message::ProcessTable(ProcessTableMessage::SelectAll))
+                    .style(action_button_style),
+                clear_selection,
+                copy_info,
+                copy_rows,
+                pin,
+                text(match selected {
+                    0 => "Ctrl+click or Shift+click to select several processes".to_string(),
+                    1 => "1 process selected".to_string(),
+                    n => format!("{n} processes selected"),
+                })
+                .size(14),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center);
+
+            let report = state.status.as_ref().map(|status| {
+                let color = if status.success {
+                    Color::from_rgb(0.3, 0.75, 0.35)
+                } else {
+                    Color::from_rgb(0.9, 0.3, 0.3)
+                };
+                text(&status.message).size(14).color(color)
+            });
+
+            let table = crate::process_table::view(&state.process_table).map(Message::ProcessTable);
+            let table: Element<'_, Message, Theme> = if state.show_details {
+                row![table, details_panel(state)].spacing(10).into()
+            } else {
+                table
+            };
+
             column![
                 space_on_top,
-                crate::process_table::view(&state.process_table).map(Message::ProcessTable),
+                selection_bar,
+                filter_chips(state),
+                report,
+                table
             ]
             .spacing(10)
             .padding(20)
             .into()
         }
-        SelectedView::Performance => match state.selected_tab {
+        SelectedView::Performance => match &state.selected_tab {
             SelectedTab::Cpu => {
-                let chart: Element<'_, Message, Theme> = ChartWidget::new(DetailChart {
-                    data: tail(&state.cpu_history, 120),
-                    color: cpu_color,
-                    y_label: "CPU %",
-                    max_size: 100.0,
-                })
-                .width(Length::Fill)
-                .height(Length::Fixed(300.0))
-                .into();
+                let chart: Element<'_, Message, Theme> = match state.cpu_graph_mode {
+                    CpuGraphMode::Overall => ChartWidget::new(DetailChart {
+                        data: &state.cpu_history,
+                        color: cpu_color,
+                        y_label: "CPU %",
+                        max_size: 100.0,
+                        overlay: None,
+                    })
+                    .width(Length::Fill)
+                    .height(Length::Fixed(300.0))
+                    .into(),
+                    CpuGraphMode::LogicalProcessors => core_grid(&state.cores, cpu_color),
+                };
+
+                let graph_mode = row![
+                    text("Change graph to: ").size(18),
+                    pick_list(
+                        CpuGraphMode::ALL,
+                        Some(state.cpu_graph_mode),
+                        Message::CpuGraphModeSelected
+                    ),
+                ]
+                .align_y(Alignment::Center);
 
                 column![
+                    graph_mode,
                     chart,
                     text(format!("CPU usage: {:.1}%", state.cpu_usage)).size(18),
                     text(format!("CPU frequency: {:.2} GHz", state.cpu_frequency)).size(18),
@@ -507,10 +1936,11 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
             }
             SelectedTab::Memory => {
                 let chart: Element<'_, Message, Theme> = ChartWidget::new(DetailChart {
-                    data: tail(&state.memory_history, 120),
+                    data: &state.memory_history,
                     color: mem_color,
                     y_label: "Memory %",
                     max_size: 100.0,
+                    overlay: None,
                 })
                 .width(Length::Fill)
                 .height(Length::Fixed(300.0))
@@ -520,34 +1950,13 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
                     chart,
                     text(format!("Memory usage: {:.1}%", state.memory_usage)).size(18),
                     text(format!(
-                        "Used memory: {:.1} GB",
-                        bytes_to_gb(state.sys.used_memory())
+                        "Used memory: {}",
+                        format_bytes(state.used_memory, state.settings.units)
                     ))
                     .size(18),
-                    text(format!("Total memory: {:.1} GB", state.total_mem)).size(18),
-                ]
-                .spacing(10)
-                .padding(20)
-                .into()
-            }
-            SelectedTab::Disk => {
-                let chart: Element<'_, Message, Theme> = ChartWidget::new(DetailChart {
-                    data: tail(&state.disk_history, 120),
-                    color: disk_color,
-                    y_label: "MB/s",
-                    max_size: 100.0,
-                })
-                .width(Length::Fill)
-                .height(Length::Fixed(300.0))
-                .into();
-
-                column![
-                    chart,
-                    text(format!("Disk usage: {:.2} MB/s", state.disk_usage)).size(18),
-                    text(format!("Total storage: {:.2} GB", state.disk_size)).size(18),
                     text(format!(
-                        "Available storage: {:.2} GB",
-                        state.disk_available_space
+                        "Total memory: {}",
+                        format_bytes(state.total_memory, state.settings.units)
                     ))
                     .size(18),
                 ]
@@ -555,6 +1964,107 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
                 .padding(20)
                 .into()
             }
+            SelectedTab::Disk(mount_point) => match find_disk(&state.disk_states, mount_point) {
+                Some(disk) => {
+                    let chart: Element<'_, Message, Theme> = ChartWidget::new(DetailChart {
+                        data: &disk.history,
+                        color: disk_color,
+                        y_label: "MB/s",
+                        max_size: 100.0,
+                        overlay: None,
+                    })
+                    .width(Length::Fill)
+                    .height(Length::Fixed(300.0))
+                    .into();
+
+                    column![
+                        chart,
+                        text(&disk.name).size(22),
+                        text(format!("Disk usage: {:.2} MB/s", disk.usage)).size(18),
+                        text(format!(
+                            "Total storage: {}",
+                            format_bytes(disk.total_space, state.settings.units)
+                        ))
+                        .size(18),
+                        text(format!(
+                            "Available storage: {}",
+                            format_bytes(disk.available_space, state.settings.units)
+                        ))
+                        .size(18),
+                        text(format!("Mount point: {}", disk.mount_point.display())).size(18),
+                        text(format!("File system: {}", disk.file_system)).size(18),
+                        text(format!(
+                            "Removable: {}",
+                            if disk.is_removable { "Yes" } else { "No" }
+                        ))
+                        .size(18),
+                    ]
+                    .spacing(10)
+                    .padding(20)
+                    .into()
+                }
+                None => column![
+                    text(format!("{} is no longer available", mount_point.display())).size(18)
+                ]
+                .padding(20)
+                .into(),
+            },
+            SelectedTab::Network(name) => match find_network(&state.network_states, name) {
+                Some(network) => {
+                    let rx = &network.rx_history;
+                    let tx = &network.tx_history;
+
+                    let chart: Element<'_, Message, Theme> = ChartWidget::new(DetailChart {
+                        data: rx,
+                        color: rx_color,
+                        y_label: "KB/s",
+                        max_size: scale_max(&[&[rx.peak(), tx.peak()]], 10.0),
+                        overlay: Some((tx, tx_color)),
+                    })
+                    .width(Length::Fill)
+                    .height(Length::Fixed(300.0))
+                    .into();
+
+                    let ip_addresses = if network.ip_addresses.is_empty() {
+                        "None".to_string()
+                    } else {
+                        network.ip_addresses.join(", ")
+                    };
+
+                    column![
+                        chart,
+                        text(&network.name).size(22),
+                        text(format!("Receive: {:.1} KB/s", network.received))
+                            .size(18)
+                            .color(Color::from_rgb8(rx_color.0, rx_color.1, rx_color.2)),
+                        text(format!("Send: {:.1} KB/s", network.transmitted))
+                            .size(18)
+                            .color(Color::from_rgb8(tx_color.0, tx_color.1, tx_color.2)),
+                        text(format!(
+                            "Total received: {} ({} packets, {} errors)",
+                            format_bytes(network.total_received, state.settings.units),
+                            network.packets_received,
+                            network.errors_received
+                        ))
+                        .size(18),
+                        text(format!(
+                            "Total sent: {} ({} packets, {} errors)",
+                            format_bytes(network.total_transmitted, state.settings.units),
+                            network.packets_transmitted,
+                            network.errors_transmitted
+                        ))
+                        .size(18),
+                        text(format!("MAC address: {}", network.mac_address)).size(18),
+                        text(format!("IP addresses: {ip_addresses}")).size(18),
+                    ]
+                    .spacing(10)
+                    .padding(20)
+                    .into()
+                }
+                None => column![text(format!("{name} is no longer available")).size(18)]
+                    .padding(20)
+                    .into(),
+            },
         },
         SelectedView::Settings => {
             let themes = pick_list(Theme::ALL, state.theme_selected, Message::ThemeSelected)
@@ -563,13 +2073,187 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
             let theme_choose = row![text("Theme: ").size(18), Space::new().width(10), themes]
                 .align_y(iced::Center);
 
-            column![theme_choose]
-                .width(Length::Fill)
-                .align_x(iced::Center)
-                .into()
+            let default_view = row![
+                text("Open on launch: ").size(18),
+                Space::new().width(10),
+                pick_list(
+                    DefaultView::ALL,
+                    Some(state.settings.default_view),
+                    Message::DefaultViewSelected
+                ),
+            ]
+            .align_y(iced::Center);
+
+            let units = row![
+                text("Units: ").size(18),
+                Space::new().width(10),
+                pick_list(
+                    Units::ALL,
+                    Some(state.settings.units),
+                    Message::UnitsSelected
+                ),
+            ]
+            .align_y(iced::Center);
+
+            let update_speed = if state.paused {
+                UpdateSpeed::Paused
+            } else {
+                UpdateSpeed::Every(state.settings.refresh_interval_ms)
+            };
+
+            let update_speed = row![
+                text("Update speed: ").size(18),
+                Space::new().width(10),
+                pick_list(
+                    UpdateSpeed::ALL,
+                    Some(update_speed),
+                    Message::UpdateSpeedSelected
+                ),
+                Space::new().width(10),
+                button(text("Refresh now"))
+                    .on_press(Message::RefreshNow)
+                    .style(action_button_style),
+            ]
+            .align_y(iced::Center);
+
+            let mut record = button(text(if state.recorder.is_some() {
+                "Stop recording"
+            } else {
+                "Start recording"
+            }))
+            .style(action_button_style);
+            if state.recorder.is_some() {
+                record = record.on_press(Message::StopRecording);
+            } else if state.replay.is_none() {
+                record = record.on_press(Message::StartRecording);
+            }
+            let recording = row![
+                text("Recording: ").size(18),
+                Space::new().width(10),
+                record,
+                Space::new().width(10),
+                button(text("Open recording"))
+                    .on_press(Message::OpenRecording)
+                    .style(action_button_style),
+            ]
+            .align_y(iced::Center);
+
+            let history_length = row![
+                text("Graph history: ").size(18),
+                Space::new().width(10),
+                pick_list(
+                    HistoryLength::ALL,
+                    Some(HistoryLength(state.settings.history_minutes)),
+                    Message::HistoryLengthSelected
+                ),
+            ]
+            .align_y(iced::Center);
+
+            let protected_processes = row![
+                text("Protected processes: ").size(18),
+                Space::new().width(10),
+                text_input("Comma separated names", &state.protected_input)
+                    .on_input(Message::ProtectedProcessesChanged)
+                    .on_submit(Message::ProtectedProcessesSubmitted)
+                    .width(300),
+            ]
+            .align_y(iced::Center);
+
+            let protected_policy = row![
+                text("When ending a protected process: ").size(18),
+                Space::new().width(10),
+                pick_list(
+                    ProtectedPolicy::ALL,
+                    Some(state.settings.protected_policy),
+                    Message::ProtectedPolicySelected
+                ),
+            ]
+            .align_y(iced::Center);
+
+            let pins =
+                state
+                    .settings
+                    .pinned_processes
+                    .iter()
+                    .enumerate()
+                    .map(|(index, pattern)| {
+                        row![
+                            text(pattern).size(14).width(300),
+                            button(text("Remove"))
+                                .on_press(Message::RemovePin(index))
+                                .style(action_button_style),
+                        ]
+                        .spacing(10)
+                        .align_y(iced::Center)
+                        .into()
+                    });
+            let pinned_processes = column![
+                row![
+                    text("Pinned processes: ").size(18),
+                    Space::new().width(10),
+                    text_input("Name or query, e.g. cmd~nginx", &state.pin_input)
+                        .on_input(Message::PinInputChanged)
+                        .on_submit(Message::PinSubmitted)
+                        .width(300),
+                ]
+                .align_y(iced::Center),
+                state.pin_error.as_ref().map(|err| {
+                    text(err.to_string())
+                        .size(12)
+                        .color(Color::from_rgb(0.9, 0.3, 0.3))
+                }),
+                iced::widget::Column::with_children(pins).spacing(6),
+            ]
+            .spacing(6)
+            .align_x(iced::Center);
+
+            let mut settings_column = column![
+                theme_choose,
+                default_view,
+                update_speed,
+                recording,
+                units,
+                history_length,
+                protected_processes,
+                protected_policy,
+                pinned_processes,
+                text("Keyboard shortcuts").size(18),
+                shortcuts(state),
+            ]
+            .spacing(10)
+            .width(Length::Fill)
+            .align_x(iced::Center);
+
+            for warning in &state.settings_warnings {
+                settings_column = settings_column.push(text(warning).size(14));
+            }
+
+            scrollable(settings_column.padding([0, 20])).into()
         }
     };
 
+    let banners: Vec<Element<'_, Message, Theme>> = [
+        state.replay.as_ref().map(replay_controls),
+        state.recorder.as_ref().map(recording_banner),
+        (state.paused && state.replay.is_none())
+            .then(|| text(paused_hint(&state.settings)).size(14).into()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let main_content: Element<'_, Message, Theme> = if banners.is_empty() {
+        main_content
+    } else {
+        column![
+            iced::widget::Column::with_children(banners)
+                .spacing(5)
+                .padding([5, 20]),
+            main_content,
+        ]
+        .into()
+    };
+
     let mut children: Vec<Element<'_, Message, Theme>> = vec![sidebar.into()];
 
     if state.selected_view == SelectedView::Performance {
@@ -583,11 +2267,674 @@ pub fn view(state: &State) -> Element<'_, Message, Theme> {
             .into(),
     );
 
-    iced::widget::Row::with_children(children)
+    let content = iced::widget::Row::with_children(children).height(Length::Fill);
+
+    if let Some(confirmation) = &state.confirmation {
+        modal(content, confirmation_dialog(confirmation))
+    } else if let Some(dialog) = &state.priority_dialog {
+        modal(content, priority_dialog(dialog))
+    } else if let Some(dialog) = &state.affinity_dialog {
+        modal(content, affinity_dialog(dialog))
+    } else if let Some(name) = &state.save_filter_name {
+        modal(
+            content,
+            save_filter_dialog(name, &state.process_table.search_query),
+        )
+    } else if state.process_table.column_menu && state.selected_view == SelectedView::Processes {
+        modal(content, column_menu(&state.process_table))
+    } else {
+        content.into()
+    }
+}
+
+/// One text input per command, with the parse error under any that
+/// doesn't hold a valid shortcut.
+fn shortcuts(state: &State) -> Element<'_, Message, Theme> {
+    let rows = Command::ALL.into_iter().map(|command| {
+        let input = state
+            .shortcut_inputs
+            .get(&command)
+            .map_or("", String::as_str);
+        let editor = row![
+            text(command.to_string()).size(14).width(180),
+            text_input("Unbound", input)
+                .on_input(move |input| Message::ShortcutChanged(command, input))
+                .width(150),
+        ]
+        .align_y(iced::Center);
+
+        match input.parse::<KeyBinding>() {
+            Err(err) if !input.is_empty() => column![editor, text(err).size(12)].into(),
+            _ => editor.into(),
+        }
+    });
+
+    iced::widget::Column::with_children(rows).spacing(6).into()
+}
+
+/// Play/pause, seek and speed controls shown above every view while a
+/// recording is replayed.
+fn replay_controls(replay: &Replay) -> Element<'_, Message, Theme> {
+    let session = replay.session();
+    let frame = replay.frame();
+    let start = session.frames[0].elapsed();
+    let name = replay.path().file_name().map_or_else(
+        || replay.path().display().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+
+    row![
+        text(format!("Replaying {name}")).size(14),
+        button(text(if replay.playing { "Pause" } else { "Play" }))
+            .on_press(Message::ReplayPlayPause)
+            .style(action_button_style),
+        slider(
+            0..=(replay.frames().len() - 1) as u32,
+            replay.position() as u32,
+            Message::ReplaySeek
+        )
+        .width(Length::Fill),
+        text(format!(
+            "{} / {}",
+            format_duration((frame.elapsed() - start).as_secs()),
+            format_duration(session.duration().as_secs())
+        ))
+        .size(14),
+        text(format_timestamp(session.timestamp(frame))).size(14),
+        pick_list(
+            ReplaySpeed::ALL,
+            Some(replay.speed),
+            Message::ReplaySpeedSelected
+        ),
+        button(text("Back to live"))
+            .on_press(Message::CloseReplay)
+            .style(action_button_style),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+fn recording_banner(recorder: &Recorder) -> Element<'_, Message, Theme> {
+    row![
+        text(format!(
+            "Recording to {} ({} samples)",
+            recorder.path().display(),
+            recorder.frames()
+        ))
+        .size(14),
+        button(text("Stop recording"))
+            .on_press(Message::StopRecording)
+            .style(action_button_style),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Reminds how to get out of the paused state with the current bindings.
+fn paused_hint(settings: &Settings) -> String {
+    let binding = |command| {
+        settings
+            .keymap
+            .binding(command)
+            .map_or_else(|| "the toolbar".to_string(), |binding| binding.to_string())
+    };
+    format!(
+        "Updates paused. Press {} to resume or {} to refresh once.",
+        binding(Command::TogglePause),
+        binding(Command::Refresh)
+    )
+}
+
+/// Opened by right-clicking a column header. Shown columns come first in
+/// table order and can be moved; hidden ones follow.
+fn column_menu(table: &ProcessTableState) -> Element<'_, Message, Theme> {
+    let shown = table.columns.iter().enumerate().map(|(index, col)| {
+        let kind = col.kind();
+        let mut left = button(text("◀").size(12)).style(action_button_style);
+        if index > 0 {
+            left = left.on_press(Message::ProcessTable(ProcessTableMessage::MoveColumnLeft(
+                kind,
+            )));
+        }
+        let mut right = button(text("▶").size(12)).style(action_button_style);
+        if index + 1 < table.columns.len() {
+            right = right.on_press(Message::ProcessTable(ProcessTableMessage::MoveColumnRight(
+                kind,
+            )));
+        }
+
+        let mut toggle = checkbox(true).label(kind.label()).width(Length::Fill);
+        if table.columns.len() > 1 {
+            toggle = toggle.on_toggle(move |visible| {
+                Message::ProcessTable(ProcessTableMessage::ToggleColumn(kind, visible))
+            });
+        }
+        row![toggle, left, right]
+            .spacing(6)
+            .align_y(Alignment::Center)
+            .into()
+    });
+    let hidden = table.hidden_columns.iter().map(|col| {
+        let kind = col.kind;
+        checkbox(false)
+            .label(kind.label())
+            .on_toggle(move |visible| {
+                Message::ProcessTable(ProcessTableMessage::ToggleColumn(kind, visible))
+            })
+            .into()
+    });
+    let entries: Vec<Element<'_, Message, Theme>> = shown.chain(hidden).collect();
+
+    let content = column![
+        text("Columns").size(20),
+        container(scrollable(
+            iced::widget::Column::with_children(entries).spacing(6)
+        ))
+        .max_height(420),
+        row![
+            Space::new().width(Length::Fill),
+            button(text("Done"))
+                .on_press(Message::ProcessTable(ProcessTableMessage::CloseColumnMenu))
+                .style(action_button_style),
+        ],
+    ]
+    .spacing(10);
+
+    dialog_container(content)
+}
+
+fn affinity_dialog(dialog: &AffinityDialog) -> Element<'_, Message, Theme> {
+    const PER_ROW: usize = 4;
+
+    let rows = dialog
+        .cpus
+        .chunks(PER_ROW)
+        .enumerate()
+        .map(|(chunk, cpus)| {
+            let cells = cpus.iter().enumerate().map(|(i, &allowed)| {
+                let cpu = chunk * PER_ROW + i;
+                checkbox(allowed)
+                    .label(format!("CPU {cpu}"))
+                    .on_toggle(move |allowed| Message::AffinityCpuToggled(cpu, allowed))
+                    .width(Length::FillPortion(1))
+                    .into()
+            });
+            let mut cells: Vec<Element<'_, Message, Theme>> = cells.collect();
+            while cells.len() < PER_ROW {
+                cells.push(Space::new().width(Length::FillPortion(1)).into());
+            }
+            iced::widget::Row::with_children(cells).spacing(6).into()
+        });
+
+    let all_selected = dialog.cpus.iter().all(|&allowed| allowed);
+    let none_selected = !dialog.cpus.contains(&true);
+
+    let mut apply = button(text("Apply")).style(action_button_style);
+    if !none_selected {
+        apply = apply.on_press(Message::ApplyAffinity);
+    }
+
+    let content = column![
+        text(format!(
+            "Set affinity — {} ({})",
+            dialog.target.name, dialog.target.pid
+        ))
+        .size(20),
+        checkbox(all_selected)
+            .label("All processors")
+            .on_toggle(Message::AffinityAllToggled),
+        container(scrollable(
+            iced::widget::Column::with_children(rows).spacing(6)
+        ))
+        .max_height(300),
+        checkbox(dialog.all_threads)
+            .label("Apply to all threads")
+            .on_toggle(Message::AffinityThreadsToggled),
+        row![
+            Space::new().width(Length::Fill),
+            button(text("Cancel"))
+                .on_press(Message::CancelAction)
+                .style(action_button_style),
+            apply,
+        ]
+        .spacing(10),
+    ]
+    .spacing(10);
+
+    dialog_container(content)
+}
+
+fn priority_dialog(dialog: &PriorityDialog) -> Element<'_, Message, Theme> {
+    let title = match dialog.targets.as_slice() {
+        [target] => format!("Set priority — {} ({})", target.name, target.pid),
+        targets => format!("Set priority — {} processes", targets.len()),
+    };
+
+    let mut content = column![
+        text(title).size(20),
+        row![
+            text("Priority: ").width(120),
+            pick_list(
+                PriorityClass::ALL,
+                Some(PriorityClass::from_nice(dialog.nice)),
+                Message::PriorityClassSelected
+            ),
+        ]
+        .align_y(Alignment::Center),
+        row![
+            text(format!("Nice: {}", dialog.nice)).width(120),
+            slider(MIN_NICE..=MAX_NICE, dialog.nice, Message::NiceChanged),
+        ]
+        .align_y(Alignment::Center),
+    ]
+    .spacing(10);
+
+    if let Some(io) = dialog.io {
+        content = content.push(
+            row![
+                text("I/O class: ").width(120),
+                pick_list(IoClass::ALL, Some(io.class), Message::IoClassSelected),
+            ]
+            .align_y(Alignment::Center),
+        );
+        if io.class != IoClass::Idle {
+            content = content.push(
+                row![
+                    text(format!("I/O level: {}", io.level)).width(120),
+                    slider(0..=MAX_IO_LEVEL, io.level, Message::IoLevelChanged),
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+    }
+
+    content = content.push(
+        text("Raising priority or using the realtime I/O class usually needs root.").size(12),
+    );
+    content = content.push(
+        row![
+            Space::new().width(Length::Fill),
+            button(text("Cancel"))
+                .on_press(Message::CancelAction)
+                .style(action_button_style),
+            button(text("Apply"))
+                .on_press(Message::ApplyPriority)
+                .style(action_button_style),
+        ]
+        .spacing(10),
+    );
+
+    dialog_container(content)
+}
+
+fn confirmation_dialog(confirmation: &Confirmation) -> Element<'_, Message, Theme> {
+    let target = &confirmation.targets[0];
+    let user = if target.user.is_empty() {
+        "unknown"
+    } else {
+        &target.user
+    };
+
+    let mut dialog = column![
+        text(format!("{}?", confirmation.action)).size(20),
+        text(format!("Name: {}", target.name)),
+    ]
+    .spacing(8);
+
+    dialog = if let [target] = confirmation.targets.as_slice() {
+        dialog.push(text(format!("PID: {}", target.pid)))
+    } else {
+        let pids: Vec<String> = confirmation
+            .targets
+            .iter()
+            .map(|target| target.pid.to_string())
+            .collect();
+        dialog.push(text(format!(
+            "{} processes: {}",
+            pids.len(),
+            pids.join(", ")
+        )))
+    };
+    dialog = dialog
+        .push(text(format!("User: {user}")))
+        .push(text(format!("Command line: {}", target.command_line)));
+
+    if let Some(reason) = &confirmation.protection {
+        let warning = if confirmation.confirmed_once {
+            format!("Protected: {reason}. Are you absolutely sure?")
+        } else {
+            format!("Protected: {reason}.")
+        };
+        dialog = dialog.push(text(warning).color(Color::from_rgb(0.9, 0.3, 0.3)));
+    }
+
+    let confirm_label = if confirmation.protection.is_some() && !confirmation.confirmed_once {
+        "Continue"
+    } else {
+        "Confirm"
+    };
+
+    dialog = dialog.push(
+        row![
+            Space::new().width(Length::Fill),
+            button(text("Cancel"))
+                .on_press(Message::CancelAction)
+                .style(action_button_style),
+            button(text(confirm_label))
+                .on_press(Message::ConfirmAction)
+                .style(action_button_style),
+        ]
+        .spacing(10),
+    );
+
+    dialog_container(dialog)
+}
+
+fn save_filter_dialog<'a>(name: &'a str, query: &'a str) -> Element<'a, Message, Theme> {
+    let mut save = button(text("Save")).style(action_button_style);
+    if !name.trim().is_empty() {
+        save = save.on_press(Message::SaveFilter);
+    }
+
+    let dialog = column![
+        text("Save search").size(20),
+        text(query).size(13),
+        text_input("Name", name)
+            .on_input(Message::SaveFilterNameChanged)
+            .on_submit(Message::SaveFilter),
+        row![
+            Space::new().width(Length::Fill),
+            button(text("Cancel"))
+                .on_press(Message::CancelAction)
+                .style(action_button_style),
+            save,
+        ]
+        .spacing(10),
+    ]
+    .spacing(12);
+
+    dialog_container(dialog)
+}
+
+/// Saved searches as chips. The one matching the current search is
+/// highlighted; clicking it again clears the search.
+fn filter_chips(state: &State) -> Option<Element<'_, Message, Theme>> {
+    if state.settings.saved_filters.is_empty() {
+        return None;
+    }
+
+    let chips = state
+        .settings
+        .saved_filters
+        .iter()
+        .enumerate()
+        .map(|(index, filter)| {
+            let active = filter.query == state.process_table.search_query;
+            row![
+                button(text(&filter.name).size(13))
+                    .on_press(Message::ApplySavedFilter(index))
+                    .style(move |theme: &Theme, status| chip_style(theme, status, active)),
+                button(text("×").size(13))
+                    .on_press(Message::RemoveSavedFilter(index))
+                    .style(move |theme: &Theme, status| chip_style(theme, status, active)),
+            ]
+            .into()
+        });
+
+    Some(
+        iced::widget::Row::with_children(chips)
+            .spacing(8)
+            .wrap()
+            .into(),
+    )
+}
+
+fn chip_style(theme: &Theme, status: button::Status, active: bool) -> button::Style {
+    let palette = theme.palette();
+    let style = action_button_style(theme, status);
+    if !active {
+        return style;
+    }
+    button::Style {
+        background: Some(Background::Color(palette.accent_fill_color_default)),
+        text_color: palette.text_on_accent_fill_color_primary,
+        ..style
+    }
+}
+
+fn dialog_container<'a>(
+    content: impl Into<Element<'a, Message, Theme>>,
+) -> Element<'a, Message, Theme> {
+    container(content)
+        .width(480)
+        .padding(20)
+        .style(|theme: &Theme| {
+            let palette = theme.palette();
+            container::Style {
+                background: Some(Background::Color(
+                    palette.solid_background_fill_color_tertiary,
+                )),
+                border: iced::Border::default()
+                    .rounded(8)
+                    .width(1)
+                    .color(palette.surface_stroke_color_flyout),
+                ..Default::default()
+            }
+        })
+        .into()
+}
+
+/// Shows `dialog` centered over a dimmed `base`. Clicking outside the
+/// dialog cancels it.
+fn modal<'a>(
+    base: impl Into<Element<'a, Message, Theme>>,
+    dialog: Element<'a, Message, Theme>,
+) -> Element<'a, Message, Theme> {
+    stack![
+        base.into(),
+        opaque(
+            mouse_area(
+                center(opaque(dialog)).style(|theme: &Theme| container::Style {
+                    background: Some(Background::Color(theme.palette().smoke_fill_color_default)),
+                    ..Default::default()
+                })
+            )
+            .on_press(Message::CancelAction)
+        )
+    ]
+    .into()
+}
+
+/// Side panel with everything known about the selected process.
+fn details_panel(state: &State) -> Element<'_, Message, Theme> {
+    let units = state.settings.units;
+    let header = row![
+        text("Details").size(18),
+        Space::new().width(Length::Fill),
+        button(text("✕"))
+            .on_press(Message::ToggleDetails)
+            .style(action_button_style),
+    ]
+    .align_y(Alignment::Center);
+
+    let mut content = column![header].spacing(6);
+
+    let Some(details) = &state.details else {
+        let hint = if state.process_table.selected_pid.is_some() {
+            "The process has exited."
+        } else {
+            "Select a process to see its details."
+        };
+        content = content.push(text(hint).size(13));
+        return container(content).width(360).into();
+    };
+
+    let or_unknown = |value: Option<String>| value.unwrap_or_else(|| "—".to_string());
+    let path = |path: &Option<PathBuf>| path.as_ref().map(|p| p.display().to_string());
+
+    let fields = [
+        ("Name", details.name.clone()),
+        ("PID", details.pid.to_string()),
+        (
+            "Parent",
+            or_unknown(
+                details
+                    .parent
+                    .as_ref()
+                    .map(|(pid, name)| format!("{name} ({pid})")),
+            ),
+        ),
+        ("User", or_unknown(details.user.clone())),
+        ("Group", or_unknown(details.group.clone())),
+        ("Status", details.status.clone()),
+        ("Started", format_timestamp(details.start_time)),
+        ("Running for", format_duration(details.run_time)),
+        (
+            "Threads",
+            or_unknown(details.threads.map(|t| t.to_string())),
+        ),
+        (
+            "Resident memory",
+            format_bytes(details.resident_memory, units),
+        ),
+        (
+            "Virtual memory",
+            format_bytes(details.virtual_memory, units),
+        ),
+        ("Disk read", format_bytes(details.total_read_bytes, units)),
+        (
+            "Disk written",
+            format_bytes(details.total_written_bytes, units),
+        ),
+        ("Executable", or_unknown(path(&details.exe))),
+        ("Working directory", or_unknown(path(&details.cwd))),
+        ("Command line", details.command_line.clone()),
+    ];
+
+    for (label, value) in fields {
+        content =
+            content.push(row![text(label).size(13).width(130), text(value).size(13)].spacing(6));
+    }
+
+    content = content.push(text("Environment").size(15));
+    if details.environment.is_empty() {
+        content = content.push(text("Not available").size(12));
+    }
+    for var in &details.environment {
+        content = content.push(text(var.clone()).size(11));
+    }
+
+    container(scrollable(content.padding([0, 10])))
+        .width(360)
         .height(Length::Fill)
         .into()
 }
 
-pub fn subscription(_state: &State) -> Subscription<Message> {
-    iced::time::every(Duration::from_secs(1)).map(|_| Message::Tick)
+/// Style for plain text buttons, matching the sidebar and tab buttons.
+fn action_button_style(theme: &Theme, status: button::Status) -> button::Style {
+    let (hover, idle, text_color) = match theme {
+        Theme::Light => (LIGHT_THEME_HOVER, LIGHT_THEME_IDLE, Color::BLACK),
+        Theme::Dark => (DARK_THEME_HOVER, DARK_THEME_IDLE, Color::WHITE),
+    };
+
+    button::Style {
+        background: Some(Background::Color(match status {
+            button::Status::Hovered => hover,
+            _ => idle,
+        })),
+        text_color,
+        ..Default::default()
+    }
+}
+
+/// One small chart per logical CPU, laid out in a roughly square grid.
+fn core_grid(cores: &[CoreState], color: RGBColor) -> Element<'_, Message, Theme> {
+    let per_row = (cores.len() as f64).sqrt().ceil().max(1.0) as usize;
+
+    let rows = cores.chunks(per_row).map(|chunk| {
+        let cells = chunk.iter().map(|core| {
+            let chart: Element<'_, Message, Theme> = ChartWidget::new(ThumbChart {
+                data: tail(core.usage_history.values(), 60),
+                color,
+                max_size: 100.0,
+                overlay: None,
+            })
+            .width(Length::Fill)
+            .height(Length::Fixed(60.0))
+            .into();
+
+            column![
+                text(format!(
+                    "{}  {:.0}%  {:.2} GHz",
+                    core.name, core.usage, core.frequency
+                ))
+                .size(12),
+                chart,
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(1))
+            .into()
+        });
+
+        let mut cells: Vec<Element<'_, Message, Theme>> = cells.collect();
+        // Pad the last row so its cells line up with the rows above.
+        while cells.len() < per_row {
+            cells.push(Space::new().width(Length::FillPortion(1)).into());
+        }
+
+        iced::widget::Row::with_children(cells).spacing(6).into()
+    });
+
+    container(scrollable(
+        iced::widget::Column::with_children(rows).spacing(6),
+    ))
+    .width(Length::Fill)
+    .height(Length::Fixed(300.0))
+    .into()
+}
+
+pub fn subscription(state: &State) -> Subscription<Message> {
+    let mut subscriptions = vec![keyboard::listen().filter_map(hotkey)];
+
+    match &state.replay {
+        Some(replay) if replay.playing => {
+            subscriptions.push(iced::time::every(REPLAY_TICK).map(|_| Message::ReplayTick));
+        }
+        Some(_) => {}
+        None if !state.paused => {
+            subscriptions
+                .push(iced::time::every(state.settings.refresh_interval()).map(|_| Message::Tick));
+        }
+        None => {}
+    }
+    if state.process_control.has_pending() {
+        subscriptions
+            .push(iced::time::every(Duration::from_millis(250)).map(|_| Message::CheckEscalations));
+    }
+
+    Subscription::batch(subscriptions)
+}
+
+/// Key presses no widget captured are resolved against the keymap in
+/// `update`. Modifier changes are passed on to the process table for
+/// Ctrl/Shift-clicks.
+fn hotkey(event: keyboard::Event) -> Option<Message> {
+    match event {
+        keyboard::Event::KeyPressed {
+            key,
+            physical_key,
+            modifiers,
+            ..
+        } => Some(Message::KeyPressed {
+            key,
+            physical_key,
+            modifiers,
+        }),
+        keyboard::Event::ModifiersChanged(modifiers) => Some(Message::ProcessTable(
+            ProcessTableMessage::ModifiersChanged(modifiers),
+        )),
+        _ => None,
+    }
 }
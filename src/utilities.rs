@@ -1,9 +1,13 @@
+use std::time::Duration;
+
 use iced::Color;
 
-pub const LIGHT_THEME_HOVER: Color = Color::from_rgb(0.68627451, 0.70196078, 0.74509804);
-pub const DARK_THEME_HOVER: Color = Color::from_rgb(0.59215686, 0.60784314, 0.64313725);
-pub const LIGHT_THEME_IDLE: Color = Color::from_rgb(0.82745098, 0.84705882, 0.89019608);
-pub const DARK_THEME_IDLE: Color = Color::from_rgb(0.53333333, 0.54117647, 0.56470588);
+use crate::settings::Units;
+
+pub const LIGHT_THEME_HOVER: Color = Color::from_rgb(0.6862745, 0.7019608, 0.74509805);
+pub const DARK_THEME_HOVER: Color = Color::from_rgb(0.5921569, 0.60784316, 0.6431373);
+pub const LIGHT_THEME_IDLE: Color = Color::from_rgb(0.827451, 0.84705883, 0.8901961);
+pub const DARK_THEME_IDLE: Color = Color::from_rgb(0.5333333, 0.5411765, 0.5647059);
 
 pub fn bytes_to_gb(bytes: u64) -> f32 {
     bytes as f32 / 1_000_000_000.0
@@ -23,6 +27,73 @@ pub fn format_uptime(seconds: u64) -> String {
     format!("{hours}:{minutes}    Total in secs: {seconds}")
 }
 
+/// Formats a duration as `H:MM:SS`, with a day count once it gets that
+/// long.
+pub fn format_duration(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let hours = (seconds % 86_400) / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let seconds = seconds % 60;
+
+    if days > 0 {
+        format!("{days}d {hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    }
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+pub fn format_timestamp(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let time = seconds % 86_400;
+
+    // Howard Hinnant's days-to-civil algorithm.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        time / 3600,
+        (time % 3600) / 60,
+        time % 60
+    )
+}
+
+/// Formats a byte count as MB or GB (MiB or GiB for binary units) with one
+/// decimal place.
+pub fn format_bytes(bytes: u64, units: Units) -> String {
+    let (base, mega, giga) = match units {
+        Units::Binary => (1024.0, "MiB", "GiB"),
+        Units::Decimal => (1000.0, "MB", "GB"),
+    };
+
+    let mb = bytes as f64 / (base * base);
+    if mb >= base {
+        format!("{:.1} {giga}", mb / base)
+    } else {
+        format!("{:.1} {mega}", mb)
+    }
+}
+
+/// Converts a counter delta into a per-second rate. Returns 0 when no time
+/// has passed, e.g. for two samples taken in the same instant.
+pub fn per_second(delta: u64, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        delta as f64 / seconds
+    } else {
+        0.0
+    }
+}
+
 pub fn mhz_to_ghz(mhz: u64) -> f32 {
     mhz as f32 / 1000.0
 }
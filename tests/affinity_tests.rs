@@ -0,0 +1,44 @@
+#![cfg(target_os = "linux")]
+
+use task_manager::affinity::{affinity, set_affinity, set_affinity_all_threads, threads};
+
+/// Logical CPU count, as the app gets it.
+fn cpus() -> usize {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_cpu_all();
+    sys.cpus().len()
+}
+
+#[test]
+fn test_pin_child_to_one_cpu() {
+    let cpus = cpus();
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+
+    let current = affinity(pid, cpus).unwrap();
+    assert_eq!(current.len(), cpus);
+    let first = current.iter().position(|&allowed| allowed).unwrap();
+
+    let mut mask = vec![false; cpus];
+    mask[first] = true;
+    set_affinity_all_threads(pid, &mask).unwrap();
+    assert_eq!(affinity(pid, cpus).unwrap(), mask);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_empty_mask_is_rejected() {
+    let err = set_affinity(std::process::id(), &[false, false]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_threads_include_main_thread() {
+    let pid = std::process::id();
+    assert!(threads(pid).unwrap().contains(&pid));
+}
@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+
+use task_manager::app_groups::build_groups;
+use task_manager::process_table::{
+    ProcessColumnKind, ProcessInfo, ProcessTableMessage, ProcessTableState, ProcessViewMode,
+    SortOrder, apply_filter, update,
+};
+
+mod common;
+
+use common::process;
+
+fn sample_rows() -> Vec<ProcessInfo> {
+    vec![
+        ProcessInfo {
+            exe: Some("/usr/lib/firefox/firefox".to_string()),
+            ..process(12, "firefox", 5.0)
+        },
+        ProcessInfo {
+            exe: Some("/usr/sbin/sshd".to_string()),
+            ..process(30, "sshd", 1.0)
+        },
+        ProcessInfo {
+            exe: Some("/usr/lib/firefox/firefox".to_string()),
+            ..process(11, "Web Content", 20.0)
+        },
+        process(40, "kworker", 0.0),
+        process(41, "kworker", 0.5),
+    ]
+}
+
+fn build(expanded: &HashSet<String>) -> Vec<ProcessInfo> {
+    build_groups(
+        &sample_rows(),
+        expanded,
+        ProcessColumnKind::CpuUsage,
+        SortOrder::Descending,
+        false,
+    )
+}
+
+#[test]
+fn test_same_executable_is_grouped() {
+    let rows = build(&HashSet::new());
+    assert_eq!(rows.len(), 3);
+
+    let firefox = rows[0].group.as_ref().unwrap();
+    assert_eq!(firefox.key, "/usr/lib/firefox/firefox");
+    assert_eq!(firefox.members, vec![11, 12]);
+    assert_eq!(rows[0].name, "Web Content (2)");
+    assert_eq!(rows[0].cpu_usage, 25.0);
+    assert_eq!(rows[0].memory_bytes, 200);
+    assert_eq!(rows[0].disk_read_rate, 20);
+}
+
+#[test]
+fn test_unknown_executable_groups_by_name() {
+    let rows = build(&HashSet::new());
+    let kworker = rows
+        .iter()
+        .find_map(|r| r.group.as_ref().filter(|g| g.key == "kworker"))
+        .unwrap();
+    assert_eq!(kworker.members, vec![40, 41]);
+}
+
+#[test]
+fn test_single_process_apps_stay_plain() {
+    let rows = build(&HashSet::new());
+    let sshd = rows.iter().find(|r| r.pid == 30).unwrap();
+    assert!(sshd.group.is_none());
+    assert!(sshd.tree.is_none());
+}
+
+#[test]
+fn test_expanded_group_lists_sorted_members() {
+    let expanded = HashSet::from(["/usr/lib/firefox/firefox".to_string()]);
+    let rows = build(&expanded);
+    let pids: Vec<u32> = rows.iter().take(3).map(|r| r.pid).collect();
+    assert_eq!(pids, vec![11, 11, 12]);
+    assert!(rows[0].tree.as_ref().unwrap().expanded);
+    assert_eq!(rows[1].tree.as_ref().unwrap().depth, 1);
+    assert!(rows[1].group.is_none());
+}
+
+#[test]
+fn test_selecting_a_group_selects_all_members() {
+    let mut state = ProcessTableState {
+        rows: sample_rows(),
+        ..Default::default()
+    };
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::ViewModeSelected(ProcessViewMode::Apps),
+    );
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::GroupSelected("/usr/lib/firefox/firefox".to_string()),
+    );
+    assert_eq!(state.selected_pid, Some(11));
+    assert_eq!(state.selected_pids(), vec![11, 12]);
+
+    let _ = update(&mut state, ProcessTableMessage::RowSelected(30));
+    assert_eq!(state.selected_pids(), vec![30]);
+}
+
+#[test]
+fn test_group_selection_survives_switching_to_list() {
+    let mut state = ProcessTableState {
+        rows: sample_rows(),
+        view_mode: ProcessViewMode::Apps,
+        ..Default::default()
+    };
+    apply_filter(&mut state);
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::GroupSelected("kworker".to_string()),
+    );
+    assert_eq!(state.selected_pids(), vec![40, 41]);
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::ViewModeSelected(ProcessViewMode::List),
+    );
+    assert_eq!(state.selected_pids(), vec![40, 41]);
+}
@@ -0,0 +1,180 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use task_manager::cli::{Cli, CliCommand, OutputFormat, snapshot_report, top_report};
+use task_manager::export::text_table;
+use task_manager::process_table::{ProcessColumnKind, ProcessInfo};
+use task_manager::sampler::{CoreSample, CpuSnapshot, DiskSample, MemorySnapshot, SystemSnapshot};
+use task_manager::settings::Units;
+
+mod common;
+
+use common::process;
+
+fn top(args: &[&str]) -> task_manager::cli::TopArgs {
+    let args = std::iter::once("task-manager")
+        .chain(["top"])
+        .chain(args.iter().copied());
+    match Cli::try_parse_from(args).unwrap().command {
+        Some(CliCommand::Top(args)) => args,
+        other => panic!("expected top, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_no_subcommand_opens_the_window() {
+    assert!(
+        Cli::try_parse_from(["task-manager"])
+            .unwrap()
+            .command
+            .is_none()
+    );
+}
+
+#[test]
+fn test_top_arguments() {
+    let args = top(&["--sort", "mem", "-n", "5", "--json", "-c", "pid,name,cmd"]);
+    assert_eq!(args.sort, ProcessColumnKind::MemoryUsage);
+    assert_eq!(args.limit, 5);
+    assert_eq!(args.output.format(), OutputFormat::Json);
+    assert_eq!(
+        args.columns,
+        [
+            ProcessColumnKind::Pid,
+            ProcessColumnKind::Name,
+            ProcessColumnKind::CommandLine
+        ]
+    );
+
+    let args = top(&[]);
+    assert_eq!(args.sort, ProcessColumnKind::CpuUsage);
+    assert_eq!(args.limit, 20);
+    assert_eq!(args.output.format(), OutputFormat::Table);
+    assert!(args.output.interval.is_none());
+
+    let parse = |args: &[&str]| Cli::try_parse_from(["task-manager", "top"].iter().chain(args));
+    assert!(parse(&["--sort", "bogus"]).is_err());
+    assert!(parse(&["--query", "cpu>"]).is_err());
+    assert!(parse(&["--interval", "0"]).is_err());
+    assert!(
+        parse(&["--count", "3"]).is_err(),
+        "--count needs --interval"
+    );
+    assert!(parse(&["--json", "--csv"]).is_err());
+}
+
+#[test]
+fn test_column_names_accept_keys_and_query_fields() {
+    assert_eq!("mem".parse(), Ok(ProcessColumnKind::MemoryUsage));
+    assert_eq!("memory_bytes".parse(), Ok(ProcessColumnKind::MemoryUsage));
+    assert_eq!("PPID".parse(), Ok(ProcessColumnKind::ParentPid));
+    assert_eq!("fd".parse(), Ok(ProcessColumnKind::OpenFiles));
+    assert!("nope".parse::<ProcessColumnKind>().is_err());
+}
+
+#[test]
+fn test_top_report_sorts_filters_and_limits() {
+    let rows = [
+        ProcessInfo {
+            memory_bytes: 10_000_000,
+            ..process(1, "init", 0.5)
+        },
+        ProcessInfo {
+            memory_bytes: 900_000_000,
+            ..process(2, "cargo", 80.0)
+        },
+        ProcessInfo {
+            memory_bytes: 2_000_000_000,
+            ..process(3, "rustc", 40.0)
+        },
+    ];
+
+    let report = top_report(
+        &rows,
+        &top(&["-n", "2", "-c", "pid,name"]),
+        Units::Binary,
+        false,
+    );
+    assert_eq!(report, "PID  Name\n  2  cargo\n  3  rustc\n");
+
+    let report = top_report(
+        &rows,
+        &top(&["--sort", "mem", "-r", "-c", "name", "--csv"]),
+        Units::Binary,
+        false,
+    );
+    assert_eq!(report, "name\r\ninit\r\ncargo\r\nrustc\r\n");
+
+    let report = top_report(
+        &rows,
+        &top(&["-q", "name~^r", "-c", "pid", "--json"]),
+        Units::Binary,
+        true,
+    );
+    assert_eq!(report, "[{\"pid\":3}]\n");
+}
+
+#[test]
+fn test_snapshot_report_formats() {
+    let snapshot = SystemSnapshot {
+        cpu: CpuSnapshot {
+            usage: 12.5,
+            cores: vec![CoreSample {
+                name: "cpu0".to_string(),
+                usage: 12.5,
+                frequency: 3.0,
+            }],
+        },
+        memory: MemorySnapshot {
+            used: 2_000_000_000,
+            total: 8_000_000_000,
+        },
+        uptime: 3_661,
+        disks: vec![DiskSample {
+            name: "sda".to_string(),
+            mount_point: PathBuf::from("/"),
+            file_system: "ext4".to_string(),
+            is_removable: false,
+            total_space: 100,
+            available_space: 40,
+            usage: 1.5,
+        }],
+        ..Default::default()
+    };
+
+    let json: serde_json::Value = serde_json::from_str(&snapshot_report(
+        &snapshot,
+        OutputFormat::Json,
+        Units::Decimal,
+        1_700_000_000,
+        false,
+    ))
+    .unwrap();
+    assert_eq!(json["timestamp"], 1_700_000_000);
+    assert_eq!(json["memory"]["usage_percent"], 25.0);
+    assert_eq!(json["disks"][0]["mount_point"], "/");
+    assert_eq!(json["cpu"]["cores"][0]["frequency_ghz"], 3.0);
+
+    let csv = snapshot_report(&snapshot, OutputFormat::Csv, Units::Decimal, 0, false);
+    assert!(csv.starts_with("metric,value\r\n"));
+    assert!(csv.contains("\r\ncpu.usage_percent,12.5\r\n"));
+    assert!(csv.contains("\r\ndisks.0.throughput_mb_s,1.5\r\n"));
+
+    let table = snapshot_report(&snapshot, OutputFormat::Table, Units::Decimal, 0, false);
+    assert!(table.starts_with("CPU     12.5% at 3.00 GHz, 1 logical processors\n"));
+    assert!(table.contains("Uptime  1:01:01\n"));
+    assert!(table.contains("sda   /      ext4"));
+}
+
+#[test]
+fn test_text_table_alignment() {
+    let table = text_table(
+        &["Name".to_string(), "Size".to_string()],
+        &[
+            vec!["a".to_string(), "1".to_string()],
+            vec!["longer".to_string(), "100".to_string()],
+        ],
+        &[false, true],
+    );
+    assert_eq!(table, "Name    Size\na          1\nlonger   100\n");
+}
@@ -0,0 +1,33 @@
+//! Fixtures shared by the process table tests.
+#![allow(dead_code)]
+
+use task_manager::process_table::{
+    ProcessColumnKind, ProcessInfo, ProcessTableState, apply_filter,
+};
+
+/// A process with the fields most tests look at, and a little memory and
+/// disk use for totals to add up. Other fields are set with struct update
+/// syntax, as in `ProcessInfo { parent_pid: Some(1), ..process(2, "sh", 0.0) }`.
+pub fn process(pid: u32, name: &str, cpu_usage: f32) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        cpu_usage,
+        memory_bytes: 100,
+        disk_read_rate: 10,
+        command_line: format!("/usr/bin/{name}"),
+        ..Default::default()
+    }
+}
+
+/// A table showing `rows`, sorted by `column` in its usual order.
+pub fn state(rows: Vec<ProcessInfo>, column: ProcessColumnKind) -> ProcessTableState {
+    let mut state = ProcessTableState {
+        rows,
+        sort_column: column,
+        sort_order: column.default_order(),
+        ..Default::default()
+    };
+    apply_filter(&mut state);
+    state
+}
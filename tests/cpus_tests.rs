@@ -0,0 +1,26 @@
+use task_manager::cpus::{CoreState, average_frequency};
+use task_manager::history::Retention;
+
+#[test]
+fn test_core_record_keeps_usage_and_frequency_history() {
+    let mut core = CoreState::new("cpu0".to_string(), Retention::default());
+    core.record(25.0, 3.2);
+    core.record(75.0, 3.6);
+    assert_eq!(core.usage, 75.0);
+    assert_eq!(core.usage_history.values(), vec![25.0, 75.0]);
+    assert_eq!(core.frequency_history.len(), 2);
+}
+
+#[test]
+fn test_average_frequency() {
+    let mut a = CoreState::new("cpu0".to_string(), Retention::default());
+    let mut b = CoreState::new("cpu1".to_string(), Retention::default());
+    a.record(0.0, 2.0);
+    b.record(0.0, 4.0);
+    assert_eq!(average_frequency(&[a, b]), 3.0);
+}
+
+#[test]
+fn test_average_frequency_no_cores() {
+    assert_eq!(average_frequency(&[]), 0.0);
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use task_manager::disks::DiskState;
+use task_manager::history::Retention;
+
+fn disk() -> DiskState {
+    DiskState::new(
+        "disk0".to_string(),
+        PathBuf::from("/"),
+        Retention::default(),
+    )
+}
+
+#[test]
+fn test_first_sample_only_primes_counters() {
+    let mut disk = disk();
+    assert_eq!(
+        disk.record_at(Instant::now(), 5_000_000_000, 1_000_000_000),
+        0.0
+    );
+    assert_eq!(disk.history.values(), vec![0.0]);
+}
+
+#[test]
+fn test_record_uses_delta_since_previous_sample() {
+    let start = Instant::now();
+    let mut disk = disk();
+    disk.record_at(start, 1_000_000, 2_000_000);
+    assert_eq!(
+        disk.record_at(start + Duration::from_secs(1), 3_000_000, 2_500_000),
+        2.5
+    );
+    assert_eq!(disk.usage, 2.5);
+    assert_eq!(disk.history.values(), vec![0.0, 2.5]);
+}
+
+#[test]
+fn test_record_divides_by_elapsed_time() {
+    let start = Instant::now();
+    let mut disk = disk();
+    disk.record_at(start, 0, 0);
+    assert_eq!(
+        disk.record_at(start + Duration::from_millis(500), 1_000_000, 0),
+        2.0
+    );
+    assert_eq!(
+        disk.record_at(start + Duration::from_millis(2500), 5_000_000, 0),
+        2.0
+    );
+}
+
+#[test]
+fn test_record_saturates_when_counters_reset() {
+    let start = Instant::now();
+    let mut disk = disk();
+    disk.record_at(start, 10_000_000, 10_000_000);
+    assert_eq!(
+        disk.record_at(start + Duration::from_secs(1), 1_000_000, 0),
+        0.0
+    );
+    assert_eq!(
+        disk.record_at(start + Duration::from_secs(2), 2_000_000, 0),
+        1.0
+    );
+}
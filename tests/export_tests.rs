@@ -0,0 +1,92 @@
+use task_manager::export::{ExportFormat, export, to_csv, to_json, to_tsv};
+use task_manager::process_table::{ProcessColumnKind, ProcessInfo};
+use task_manager::settings::Units;
+
+const COLUMNS: [ProcessColumnKind; 4] = [
+    ProcessColumnKind::Pid,
+    ProcessColumnKind::Name,
+    ProcessColumnKind::MemoryUsage,
+    ProcessColumnKind::User,
+];
+
+fn rows() -> Vec<ProcessInfo> {
+    vec![
+        ProcessInfo {
+            pid: 42,
+            name: "cargo".to_string(),
+            memory_bytes: 3 * 1024 * 1024,
+            user: Some("alice".to_string()),
+            ..Default::default()
+        },
+        ProcessInfo {
+            pid: 7,
+            name: "odd, \"quoted\"\tname".to_string(),
+            memory_bytes: 512,
+            user: None,
+            ..Default::default()
+        },
+    ]
+}
+
+#[test]
+fn test_csv_has_raw_and_formatted_columns() {
+    let csv = to_csv(&COLUMNS, &rows(), Units::Binary);
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+    assert_eq!(
+        lines[0],
+        "pid,name,memory_bytes,memory_bytes_formatted,user"
+    );
+    assert_eq!(lines[1], "42,cargo,3145728,3.0 MiB,alice");
+    assert_eq!(lines[2], "7,\"odd, \"\"quoted\"\"\tname\",512,0.0 MiB,");
+    assert_eq!(lines[3], "");
+}
+
+#[test]
+fn test_json_keeps_numbers_and_nulls() {
+    let json = to_json(&COLUMNS, &rows(), Units::Decimal);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let first = &value[0];
+    assert_eq!(first["pid"], 42);
+    assert_eq!(first["memory_bytes"], 3_145_728);
+    assert_eq!(first["memory_bytes_formatted"], "3.1 MB");
+    assert_eq!(first["user"], "alice");
+    assert!(value[1]["user"].is_null());
+
+    let keys: Vec<&String> = first.as_object().unwrap().keys().collect();
+    assert_eq!(
+        keys,
+        [
+            "pid",
+            "name",
+            "memory_bytes",
+            "memory_bytes_formatted",
+            "user"
+        ]
+    );
+}
+
+#[test]
+fn test_export_dispatches_on_format() {
+    let rows = rows();
+    assert_eq!(
+        export(ExportFormat::Csv, &COLUMNS, &rows, Units::Binary),
+        to_csv(&COLUMNS, &rows, Units::Binary)
+    );
+    assert_eq!(
+        export(ExportFormat::Json, &COLUMNS, &rows, Units::Binary),
+        to_json(&COLUMNS, &rows, Units::Binary)
+    );
+}
+
+#[test]
+fn test_tsv_uses_labels_and_shown_values() {
+    let rows = rows();
+    let selected: Vec<&ProcessInfo> = rows.iter().collect();
+    let tsv = to_tsv(&COLUMNS, &selected, Units::Binary);
+    assert_eq!(
+        tsv,
+        "PID\tName\tMemory\tUser\n\
+         42\tcargo\t3.0 MiB\talice\n\
+         7\todd, \"quoted\" name\t0.0 MiB\t—"
+    );
+}
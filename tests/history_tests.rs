@@ -0,0 +1,82 @@
+use std::time::{Duration, Instant};
+
+use task_manager::history::{History, Retention};
+
+fn retention() -> Retention {
+    Retention {
+        recent: Duration::from_secs(10),
+        total: Duration::from_secs(60),
+        bucket_width: Duration::from_secs(5),
+    }
+}
+
+fn fill(history: &mut History, start: Instant, seconds: u64) {
+    for i in 0..seconds {
+        history.push_at(start + Duration::from_secs(i), i as f64);
+    }
+}
+
+#[test]
+fn test_recent_window_is_bounded() {
+    let start = Instant::now();
+    let mut history = History::new(retention());
+    fill(&mut history, start, 100);
+
+    assert_eq!(history.len(), 11);
+    assert_eq!(history.values().first(), Some(&89.0));
+    assert_eq!(history.latest(), Some(99.0));
+}
+
+#[test]
+fn test_older_samples_are_downsampled() {
+    let start = Instant::now();
+    let mut history = History::new(retention());
+    fill(&mut history, start, 20);
+
+    let buckets: Vec<_> = history.buckets().collect();
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0].count(), 5);
+    assert_eq!(buckets[0].min, 0.0);
+    assert_eq!(buckets[0].max, 4.0);
+    assert_eq!(buckets[0].avg(), 2.0);
+    assert_eq!(buckets[1].count(), 4);
+}
+
+#[test]
+fn test_buckets_expire_after_total_retention() {
+    let start = Instant::now();
+    let mut history = History::new(retention());
+    fill(&mut history, start, 1000);
+
+    let bucketed: usize = history.buckets().map(|b| b.count()).sum();
+    assert!(bucketed <= 60);
+    assert_eq!(history.len(), 11);
+}
+
+#[test]
+fn test_shrinking_retention_evicts_immediately() {
+    let start = Instant::now();
+    let mut history = History::new(retention());
+    fill(&mut history, start, 10);
+
+    history.set_retention(Retention {
+        recent: Duration::from_secs(2),
+        ..retention()
+    });
+    assert_eq!(history.values(), &[7.0, 8.0, 9.0]);
+}
+
+#[test]
+fn test_series_puts_bucket_averages_before_recent_samples() {
+    let start = Instant::now();
+    let mut history = History::new(retention());
+    fill(&mut history, start, 20);
+
+    let series: Vec<_> = history.series().collect();
+    assert_eq!(series.len(), 2 + 11);
+    assert_eq!(series[0], (start + Duration::from_secs(2), 2.0));
+    assert_eq!(series[2], (start + Duration::from_secs(9), 9.0));
+    assert!(series.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(history.times().len(), history.values().len());
+    assert_eq!(history.peak(), 19.0);
+}
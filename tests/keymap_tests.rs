@@ -0,0 +1,98 @@
+use iced::keyboard::key::{Code, Named, Physical};
+use iced::keyboard::{Key, Modifiers};
+use task_manager::keymap::{BindingKey, Command, KeyBinding, Keymap};
+
+fn character(c: &str) -> Key {
+    Key::Character(c.into())
+}
+
+fn physical(code: Code) -> Physical {
+    Physical::Code(code)
+}
+
+#[test]
+fn test_parse_and_display_bindings() {
+    let binding: KeyBinding = "ctrl + shift + f".parse().unwrap();
+    assert_eq!(binding.key, BindingKey::Character('f'));
+    assert!(binding.ctrl && binding.shift && !binding.alt);
+    assert_eq!(binding.to_string(), "Ctrl+Shift+F");
+
+    let binding: KeyBinding = "pagedown".parse().unwrap();
+    assert_eq!(binding.key, BindingKey::Named(Named::PageDown));
+    assert_eq!(binding.to_string(), "PageDown");
+}
+
+#[test]
+fn test_parse_rejects_bad_bindings() {
+    assert!("".parse::<KeyBinding>().is_err());
+    assert!("Ctrl+".parse::<KeyBinding>().is_err());
+    assert!("Hyper+F".parse::<KeyBinding>().is_err());
+    assert!("Space bar".parse::<KeyBinding>().is_err());
+}
+
+#[test]
+fn test_default_keymap_resolves_commands() {
+    let keymap = Keymap::default();
+    assert_eq!(
+        keymap.command(
+            &Key::Named(Named::Delete),
+            physical(Code::Delete),
+            Modifiers::empty()
+        ),
+        Some(Command::EndTask)
+    );
+    assert_eq!(
+        keymap.command(&character("f"), physical(Code::KeyF), Modifiers::COMMAND),
+        Some(Command::FocusSearch)
+    );
+    assert_eq!(
+        keymap.command(&character("2"), physical(Code::Digit2), Modifiers::COMMAND),
+        Some(Command::ShowPerformance)
+    );
+    // Without Ctrl, a letter is left for type-ahead.
+    assert_eq!(
+        keymap.command(&character("f"), physical(Code::KeyF), Modifiers::empty()),
+        None
+    );
+}
+
+#[test]
+fn test_movement_also_matches_with_shift() {
+    let keymap = Keymap::default();
+    assert_eq!(
+        keymap.command(
+            &Key::Named(Named::ArrowDown),
+            physical(Code::ArrowDown),
+            Modifiers::SHIFT
+        ),
+        Some(Command::MoveDown)
+    );
+    assert_eq!(
+        keymap.command(
+            &Key::Named(Named::Delete),
+            physical(Code::Delete),
+            Modifiers::SHIFT
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_characters_match_by_layout_independent_key() {
+    let binding: KeyBinding = "Ctrl+F".parse().unwrap();
+    // A non-Latin layout reports another character for the same key.
+    assert!(binding.matches(&character("а"), physical(Code::KeyF), Modifiers::COMMAND));
+    assert!(!binding.matches(&character("f"), physical(Code::KeyF), Modifiers::ALT));
+}
+
+#[test]
+fn test_conflicts() {
+    let mut keymap = Keymap::default();
+    assert!(keymap.conflicts().is_empty());
+
+    keymap.set(Command::Refresh, "Delete".parse().unwrap());
+    assert_eq!(
+        keymap.conflicts(),
+        vec![(Command::EndTask, Command::Refresh)]
+    );
+}
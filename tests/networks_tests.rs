@@ -0,0 +1,25 @@
+use std::time::{Duration, Instant};
+
+use task_manager::history::Retention;
+use task_manager::networks::NetworkState;
+
+#[test]
+fn test_first_sample_only_primes_counters() {
+    let mut network = NetworkState::new("eth0".to_string(), Retention::default());
+    network.record_at(Instant::now(), 10_000_000, 2_000_000);
+    assert_eq!(network.received, 0.0);
+    assert_eq!(network.transmitted, 0.0);
+    assert_eq!(network.total_received, 10_000_000);
+}
+
+#[test]
+fn test_record_tracks_rx_and_tx_separately() {
+    let start = Instant::now();
+    let mut network = NetworkState::new("eth0".to_string(), Retention::default());
+    network.record_at(start, 1_000, 1_000);
+    network.record_at(start + Duration::from_secs(2), 11_000, 4_000);
+    assert_eq!(network.received, 5.0);
+    assert_eq!(network.transmitted, 1.5);
+    assert_eq!(network.rx_history.values(), vec![0.0, 5.0]);
+    assert_eq!(network.tx_history.values(), vec![0.0, 1.5]);
+}
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+use task_manager::process_table::{
+    ProcessColumnKind, ProcessTableMessage, ProcessTableState, ProcessViewMode, apply_filter,
+    update,
+};
+
+mod common;
+
+use common::{process, state};
+
+fn pinned(pins: &[&str]) -> ProcessTableState {
+    let mut state = state(
+        vec![
+            process(1, "systemd", 1.0),
+            process(2, "sshd", 0.0),
+            process(3, "cargo", 90.0),
+            process(4, "nginx", 5.0),
+            process(5, "firefox", 40.0),
+        ],
+        ProcessColumnKind::CpuUsage,
+    );
+    let pins: Vec<String> = pins.iter().map(|pin| pin.to_string()).collect();
+    state.set_pins(&pins);
+    apply_filter(&mut state);
+    state
+}
+
+fn pids(state: &ProcessTableState) -> Vec<u32> {
+    state.filtered_rows.iter().map(|r| r.pid).collect()
+}
+
+#[test]
+fn test_pinned_rows_come_first_in_sort_order() {
+    let mut state = pinned(&["name=sshd", "cmd~nginx$"]);
+    // Sorted by CPU, highest first, with the pins on top.
+    assert_eq!(pids(&state), vec![4, 2, 3, 5, 1]);
+    assert!(state.filtered_rows[0].pinned && state.filtered_rows[1].pinned);
+    assert!(!state.filtered_rows[2].pinned);
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SortBy(ProcessColumnKind::Pid),
+    );
+    assert_eq!(pids(&state), vec![2, 4, 1, 3, 5]);
+}
+
+#[test]
+fn test_pins_still_respect_the_search() {
+    let mut state = pinned(&["name=sshd"]);
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SearchChanged("cpu>2".to_string()),
+    );
+    assert_eq!(pids(&state), vec![3, 5, 4]);
+}
+
+#[test]
+fn test_unparsable_pins_are_skipped() {
+    let state = pinned(&["name~\"(\"", "nginx"]);
+    assert_eq!(state.pins.len(), 1);
+    assert_eq!(pids(&state)[0], 4);
+}
+
+#[test]
+fn test_pinned_group_moves_with_its_members() {
+    let mut state = ProcessTableState {
+        rows: vec![
+            process(1, "systemd", 1.0),
+            process(10, "worker", 0.0),
+            process(11, "worker", 0.0),
+            process(20, "cargo", 90.0),
+        ],
+        view_mode: ProcessViewMode::Apps,
+        expanded_groups: HashSet::from(["worker".to_string()]),
+        ..Default::default()
+    };
+    state.set_pins(&["pid=11".to_string()]);
+    apply_filter(&mut state);
+
+    let names: Vec<&str> = state
+        .filtered_rows
+        .iter()
+        .map(|r| r.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["worker (2)", "worker", "worker", "cargo", "systemd"]
+    );
+    assert!(state.filtered_rows[0].pinned);
+}
@@ -0,0 +1,61 @@
+use task_manager::priority::{IoClass, IoPriority, PriorityClass};
+
+#[test]
+fn test_priority_classes_round_trip() {
+    for class in PriorityClass::ALL {
+        assert_eq!(PriorityClass::from_nice(class.nice()), class);
+    }
+    assert_eq!(PriorityClass::from_nice(3), PriorityClass::Normal);
+    assert_eq!(PriorityClass::from_nice(-20), PriorityClass::High);
+}
+
+#[test]
+fn test_io_priority_display() {
+    let be = IoPriority {
+        class: IoClass::BestEffort,
+        level: 4,
+    };
+    assert_eq!(be.to_string(), "be/4");
+    let idle = IoPriority {
+        class: IoClass::Idle,
+        level: 0,
+    };
+    assert_eq!(idle.to_string(), "idle");
+}
+
+#[test]
+fn test_unset_io_class_follows_nice() {
+    let none = IoPriority {
+        class: IoClass::None,
+        level: 0,
+    };
+    assert_eq!(none.effective(0).to_string(), "be/4");
+    assert_eq!(none.effective(19).to_string(), "be/7");
+    assert_eq!(none.effective(-20).to_string(), "be/0");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_renice_and_ionice_child() {
+    use task_manager::priority::{io_priority, nice, set_io_priority, set_nice};
+
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+
+    // Lowering priority never needs privileges.
+    set_nice(pid, 15).unwrap();
+    assert_eq!(nice(pid).unwrap(), 15);
+
+    let idle = IoPriority {
+        class: IoClass::Idle,
+        level: 0,
+    };
+    set_io_priority(pid, idle).unwrap();
+    assert_eq!(io_priority(pid).unwrap().class, IoClass::Idle);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
@@ -0,0 +1,383 @@
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, Signal, System, UpdateKind, Users};
+use task_manager::process_actions::{
+    ActionOutcome, ActionReport, BulkReport, ProcessAction, ProcessControl, ProcessTarget,
+    protection,
+};
+
+fn spawn_sleep() -> Child {
+    let child = Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .expect("failed to spawn sleep");
+
+    // `spawn` can return while the exec is still being set up, before the
+    // command line is readable.
+    let pid = Pid::from_u32(child.id());
+    let mut sys = System::new();
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing().with_cmd(UpdateKind::Always),
+        );
+        if sys.process(pid).is_some_and(|p| !p.cmd().is_empty()) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    child
+}
+
+#[test]
+fn test_action_signals() {
+    assert_eq!(ProcessAction::Terminate.signal(), Signal::Term);
+    assert_eq!(ProcessAction::Suspend.signal(), Signal::Stop);
+    assert_eq!(ProcessAction::Resume.signal(), Signal::Continue);
+    assert_eq!(ProcessAction::Signal(Signal::User1).signal(), Signal::User1);
+}
+
+#[test]
+fn test_missing_process_reports_already_exited() {
+    let mut sys = System::new();
+    let mut control = ProcessControl::default();
+
+    let report = control.send(&mut sys, u32::MAX - 1, ProcessAction::Kill);
+    assert_eq!(report.outcome, ActionOutcome::AlreadyExited);
+    assert!(!control.has_pending());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_kill_sends_signal() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut child = spawn_sleep();
+    let mut sys = System::new();
+    let mut control = ProcessControl::default();
+
+    let report = control.send(&mut sys, child.id(), ProcessAction::Kill);
+    assert_eq!(report.outcome, ActionOutcome::Sent);
+    assert_eq!(report.name, "sleep");
+    assert!(!control.has_pending());
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.signal(), Some(9));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_terminate_is_escalated_after_timeout() {
+    let mut child = spawn_sleep();
+    let mut sys = System::new();
+    let mut control = ProcessControl::new(Duration::from_secs(5));
+    let start = Instant::now();
+
+    // Suspend first so SIGTERM stays pending and the process outlives it.
+    let report = control.send_at(&mut sys, child.id(), ProcessAction::Suspend, start);
+    assert!(report.outcome.is_success());
+    let report = control.send_at(&mut sys, child.id(), ProcessAction::Terminate, start);
+    assert!(report.outcome.is_success());
+    assert!(control.has_pending());
+
+    assert!(control.poll_at(&mut sys, start).is_empty());
+    assert!(control.has_pending());
+
+    let reports = control.poll_at(&mut sys, start + Duration::from_secs(6));
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].action, ProcessAction::Kill);
+    assert_eq!(reports[0].outcome, ActionOutcome::Sent);
+    assert!(!control.has_pending());
+
+    child.wait().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exited_process_is_not_escalated() {
+    let mut child = spawn_sleep();
+    let mut sys = System::new();
+    let mut control = ProcessControl::new(Duration::from_secs(5));
+    let start = Instant::now();
+
+    let report = control.send_at(&mut sys, child.id(), ProcessAction::Terminate, start);
+    assert!(report.outcome.is_success());
+    child.wait().unwrap();
+
+    assert!(
+        control
+            .poll_at(&mut sys, start + Duration::from_secs(6))
+            .is_empty()
+    );
+    assert!(!control.has_pending());
+}
+
+/// Spawns `sh` running `script` and waits until it has forked `children`
+/// processes.
+#[cfg(unix)]
+fn spawn_tree(script: &str, children: usize) -> (Child, System) {
+    use task_manager::process_actions::descendants;
+
+    let child = Command::new("sh")
+        .args(["-c", script])
+        .spawn()
+        .expect("failed to spawn sh");
+
+    let mut sys = System::new();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+        if descendants(&sys, child.id()).len() > children {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    (child, sys)
+}
+
+#[cfg(unix)]
+#[test]
+fn test_descendants_lists_children_before_parent() {
+    use task_manager::process_actions::descendants;
+
+    let (mut child, mut sys) = spawn_tree("sleep 30 & sleep 30 & wait", 2);
+    let order = descendants(&sys, child.id());
+    assert_eq!(order.len(), 3);
+    assert_eq!(*order.last().unwrap(), child.id());
+
+    let mut control = ProcessControl::default();
+    control.send(&mut sys, child.id(), ProcessAction::EndTree);
+    child.wait().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_end_tree_kills_processes_ignoring_sigterm() {
+    let (mut child, mut sys) = spawn_tree("trap '' TERM; sleep 30 & wait", 1);
+    let mut control = ProcessControl::new(Duration::from_secs(5));
+    let start = Instant::now();
+
+    let report = control.send_at(&mut sys, child.id(), ProcessAction::EndTree, start);
+    assert_eq!(report.action, ProcessAction::EndTree);
+    assert!(report.outcome.is_success());
+
+    // SIGTERM is ignored, so the first poll after the timeout has to kill.
+    assert!(
+        control
+            .poll_at(&mut sys, start + Duration::from_secs(6))
+            .is_empty()
+    );
+    assert!(control.has_pending());
+    child.wait().unwrap();
+
+    let mut reports = Vec::new();
+    let mut now = start + Duration::from_secs(8);
+    while reports.is_empty() && control.has_pending() {
+        std::thread::sleep(Duration::from_millis(50));
+        reports = control.poll_at(&mut sys, now);
+        now += Duration::from_secs(2);
+    }
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].outcome, ActionOutcome::Sent);
+}
+
+#[test]
+fn test_survivors_are_listed() {
+    let outcome = ActionOutcome::Survived(vec![12, 34]);
+    assert!(!outcome.is_success());
+    assert_eq!(outcome.to_string(), "still running: 12, 34");
+}
+
+#[test]
+fn test_only_destructive_actions_need_confirmation() {
+    assert!(ProcessAction::Terminate.needs_confirmation());
+    assert!(ProcessAction::EndTree.needs_confirmation());
+    assert!(ProcessAction::Signal(Signal::User1).needs_confirmation());
+    assert!(!ProcessAction::Suspend.needs_confirmation());
+    assert!(!ProcessAction::Resume.needs_confirmation());
+}
+
+#[test]
+fn test_init_and_self_are_always_protected() {
+    let mut sys = System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+    assert!(protection(&sys, 1, ProcessAction::Kill, &[]).is_some());
+    assert!(protection(&sys, std::process::id(), ProcessAction::Kill, &[]).is_some());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_protected_names_and_trees() {
+    let (mut child, mut sys) = spawn_tree("sleep 30 & wait", 1);
+    let names = vec!["sleep".to_string()];
+
+    assert!(protection(&sys, child.id(), ProcessAction::Kill, &names).is_none());
+    let reason = protection(&sys, child.id(), ProcessAction::EndTree, &names).unwrap();
+    assert!(reason.contains("sleep"), "{reason}");
+
+    let mut control = ProcessControl::default();
+    control.send(&mut sys, child.id(), ProcessAction::EndTree);
+    child.wait().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_capture_and_checked_send() {
+    let mut child = spawn_sleep();
+    let mut sys = System::new();
+    let users = Users::new_with_refreshed_list();
+    let mut control = ProcessControl::default();
+
+    let target = ProcessTarget::capture(&mut sys, &users, child.id()).unwrap();
+    assert_eq!(target.name, "sleep");
+    assert_eq!(target.command_line, "sleep 30");
+
+    let reused = ProcessTarget {
+        start_time: target.start_time + 1,
+        ..target.clone()
+    };
+    let report = control.send_checked(&mut sys, &reused, ProcessAction::Kill);
+    assert_eq!(report.outcome, ActionOutcome::PidReused);
+
+    let report = control.send_checked(&mut sys, &target, ProcessAction::Kill);
+    assert_eq!(report.outcome, ActionOutcome::Sent);
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_bulk_report_lists_failures() {
+    let report = |pid, outcome| ActionReport {
+        pid,
+        name: "chrome".to_string(),
+        action: ProcessAction::Terminate,
+        outcome,
+    };
+    let bulk = BulkReport::from_reports(
+        ProcessAction::Terminate,
+        vec![
+            report(41, ActionOutcome::Sent),
+            report(42, ActionOutcome::PermissionDenied),
+            report(43, ActionOutcome::Sent),
+        ],
+    );
+    assert!(!bulk.is_success());
+    assert_eq!(
+        bulk.to_string(),
+        format!(
+            "{} — 3 processes: 2 done; chrome (42): {}",
+            ProcessAction::Terminate,
+            ActionOutcome::PermissionDenied
+        )
+    );
+}
+
+#[test]
+fn test_bulk_report_with_one_result_reads_like_an_action_report() {
+    let mut bulk = BulkReport::new("Set priority");
+    bulk.push(42, "chrome", ActionOutcome::Sent);
+    assert!(bulk.is_success());
+    assert_eq!(
+        bulk.to_string(),
+        format!("Set priority — chrome (42): {}", ActionOutcome::Sent)
+    );
+}
+
+#[test]
+fn test_escalations_polled_together_are_all_reported() {
+    let bulk = BulkReport::from_escalations(vec![
+        ActionReport {
+            pid: 41,
+            name: "sleep".to_string(),
+            action: ProcessAction::Kill,
+            outcome: ActionOutcome::Sent,
+        },
+        ActionReport {
+            pid: 42,
+            name: "sleep".to_string(),
+            action: ProcessAction::Kill,
+            outcome: ActionOutcome::Sent,
+        },
+        ActionReport {
+            pid: 50,
+            name: "sh".to_string(),
+            action: ProcessAction::EndTree,
+            outcome: ActionOutcome::Survived(vec![51]),
+        },
+    ]);
+    assert_eq!(
+        bulk.to_string(),
+        format!(
+            "{}, {} — 3 processes: 2 done; sh (50): still running: 51",
+            ProcessAction::Kill,
+            ProcessAction::EndTree
+        )
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_tree_and_kill_due_together_are_both_reported() {
+    use task_manager::process_actions::{KILL_GRACE, descendants};
+
+    let (mut tree, mut sys) = spawn_tree("trap '' TERM; sleep 30 & wait", 1);
+    let members = descendants(&sys, tree.id());
+    // `exec` keeps the ignored SIGTERM, so only the kill ends it.
+    let (mut single, _) = spawn_tree("trap '' TERM; exec sleep 30", 0);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        sys.refresh_processes(ProcessesToUpdate::All, true);
+        if sys
+            .process(Pid::from_u32(single.id()))
+            .is_some_and(|p| p.name() == "sleep")
+        {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    let mut control = ProcessControl::new(Duration::from_secs(5));
+    let start = Instant::now();
+
+    control.send_at(&mut sys, tree.id(), ProcessAction::EndTree, start);
+    // The tree is force-killed at 6 s and checked again after the grace
+    // period, just when the other process's SIGTERM runs out.
+    let terminated = start + Duration::from_secs(1) + KILL_GRACE;
+    let report = control.send_at(&mut sys, single.id(), ProcessAction::Terminate, terminated);
+    assert!(report.outcome.is_success());
+
+    let forced = start + Duration::from_secs(6);
+    assert!(control.poll_at(&mut sys, forced).is_empty());
+    tree.wait().unwrap();
+
+    // Wait until the orphaned members are gone before the due poll.
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        sys.refresh_processes(ProcessesToUpdate::All, true);
+        let running = members.iter().any(|&pid| {
+            sys.process(Pid::from_u32(pid))
+                .is_some_and(|p| p.status() != sysinfo::ProcessStatus::Zombie)
+        });
+        if !running {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    let reports = control.poll_at(&mut sys, forced + KILL_GRACE);
+    assert_eq!(reports.len(), 2);
+    assert!(reports.iter().any(|r| r.pid == single.id()
+        && r.action == ProcessAction::Kill
+        && r.outcome == ActionOutcome::Sent));
+    assert!(reports.iter().any(|r| r.pid == tree.id()
+        && r.action == ProcessAction::EndTree
+        && r.outcome == ActionOutcome::Sent));
+    assert!(!control.has_pending());
+
+    let status = BulkReport::from_escalations(reports).to_string();
+    assert!(status.contains(&ProcessAction::Kill.to_string()));
+    assert!(status.contains(&ProcessAction::EndTree.to_string()));
+    single.wait().unwrap();
+}
@@ -0,0 +1,59 @@
+use std::process::Command;
+
+use sysinfo::{Groups, System, Users};
+use task_manager::process_details::ProcessDetails;
+
+fn collect(pid: u32) -> Option<ProcessDetails> {
+    let mut sys = System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    ProcessDetails::collect(
+        &mut sys,
+        &Users::new_with_refreshed_list(),
+        &Groups::new_with_refreshed_list(),
+        pid,
+    )
+}
+
+#[test]
+fn test_missing_process_has_no_details() {
+    assert!(collect(u32::MAX - 1).is_none());
+}
+
+#[test]
+fn test_details_of_own_process() {
+    let details = collect(std::process::id()).unwrap();
+    assert_eq!(details.pid, std::process::id());
+    assert_eq!(details.exe, std::env::current_exe().ok());
+    assert!(details.resident_memory > 0);
+    assert!(details.virtual_memory >= details.resident_memory);
+    assert!(details.threads.is_none_or(|threads| threads >= 1));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_details_of_child() {
+    let dir = std::env::temp_dir();
+    let mut child = Command::new("sleep")
+        .arg("30")
+        .current_dir(&dir)
+        .env("TASK_MANAGER_TEST", "1")
+        .spawn()
+        .unwrap();
+
+    let details = collect(child.id()).unwrap();
+    assert_eq!(details.name, "sleep");
+    assert_eq!(details.command_line, "sleep 30");
+    assert_eq!(details.cwd, dir.canonicalize().ok());
+    assert!(
+        details
+            .environment
+            .contains(&"TASK_MANAGER_TEST=1".to_string())
+    );
+    assert_eq!(
+        details.parent.as_ref().map(|(pid, _)| *pid),
+        Some(std::process::id())
+    );
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
@@ -0,0 +1,251 @@
+use sysinfo::{Pid, ProcessesToUpdate, System, Users};
+use task_manager::process_table::{
+    ProcessColumnKind, ProcessInfo, ProcessTableMessage, ProcessTableState, SlowColumns, SortOrder,
+    apply_filter, collect_processes, sort_processes, update,
+};
+
+fn process(pid: u32, name: &str, cpu_usage: f32, memory_bytes: u64) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        cpu_usage,
+        memory_bytes,
+        ..Default::default()
+    }
+}
+
+fn pids(rows: &[ProcessInfo]) -> Vec<u32> {
+    rows.iter().map(|r| r.pid).collect()
+}
+
+fn sample_rows() -> Vec<ProcessInfo> {
+    vec![
+        process(30, "cargo", 5.0, 300),
+        process(10, "Bash", 50.0, 100),
+        process(20, "zsh", 5.0, 200),
+    ]
+}
+
+#[test]
+fn test_sort_by_cpu_descending_breaks_ties_by_pid() {
+    let mut rows = sample_rows();
+    sort_processes(
+        &mut rows,
+        ProcessColumnKind::CpuUsage,
+        SortOrder::Descending,
+    );
+    assert_eq!(pids(&rows), vec![10, 20, 30]);
+}
+
+#[test]
+fn test_sort_by_name_is_case_insensitive() {
+    let mut rows = sample_rows();
+    sort_processes(&mut rows, ProcessColumnKind::Name, SortOrder::Ascending);
+    assert_eq!(pids(&rows), vec![10, 30, 20]);
+}
+
+#[test]
+fn test_sort_by_memory_ascending() {
+    let mut rows = sample_rows();
+    sort_processes(
+        &mut rows,
+        ProcessColumnKind::MemoryUsage,
+        SortOrder::Ascending,
+    );
+    assert_eq!(pids(&rows), vec![10, 20, 30]);
+}
+
+#[test]
+fn test_sort_by_clicking_same_column_toggles_order() {
+    let mut state = ProcessTableState {
+        rows: sample_rows(),
+        ..Default::default()
+    };
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SortBy(ProcessColumnKind::Pid),
+    );
+    assert_eq!(state.sort_order, SortOrder::Ascending);
+    assert_eq!(pids(&state.filtered_rows), vec![10, 20, 30]);
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SortBy(ProcessColumnKind::Pid),
+    );
+    assert_eq!(state.sort_order, SortOrder::Descending);
+    assert_eq!(pids(&state.filtered_rows), vec![30, 20, 10]);
+}
+
+#[test]
+fn test_sort_is_preserved_when_filtering() {
+    let mut state = ProcessTableState {
+        rows: sample_rows(),
+        ..Default::default()
+    };
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SortBy(ProcessColumnKind::MemoryUsage),
+    );
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SearchChanged("sh".to_string()),
+    );
+    assert_eq!(pids(&state.filtered_rows), vec![20, 10]);
+
+    state.rows = sample_rows();
+    apply_filter(&mut state);
+    assert_eq!(pids(&state.filtered_rows), vec![20, 10]);
+}
+
+fn visible_kinds(state: &ProcessTableState) -> Vec<ProcessColumnKind> {
+    state.columns.iter().map(|c| c.kind()).collect()
+}
+
+#[test]
+fn test_toggle_column_appends_and_hides() {
+    let mut state = ProcessTableState::default();
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::ToggleColumn(ProcessColumnKind::User, true),
+    );
+    assert_eq!(visible_kinds(&state).last(), Some(&ProcessColumnKind::User));
+    assert!(
+        !state
+            .hidden_columns
+            .iter()
+            .any(|c| c.kind == ProcessColumnKind::User)
+    );
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::ToggleColumn(ProcessColumnKind::Pid, false),
+    );
+    assert!(!state.is_visible(ProcessColumnKind::Pid));
+    let settings = state.column_settings();
+    assert_eq!(settings.len(), ProcessColumnKind::ALL.len());
+    assert!(
+        settings
+            .iter()
+            .any(|c| c.kind == ProcessColumnKind::Pid && !c.visible)
+    );
+}
+
+#[test]
+fn test_last_visible_column_cannot_be_hidden() {
+    let mut state = ProcessTableState::default();
+    for kind in visible_kinds(&state) {
+        let _ = update(&mut state, ProcessTableMessage::ToggleColumn(kind, false));
+    }
+    assert_eq!(state.columns.len(), 1);
+}
+
+#[test]
+fn test_move_column_stops_at_edges() {
+    let mut state = ProcessTableState::default();
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::MoveColumnRight(ProcessColumnKind::Pid),
+    );
+    assert_eq!(
+        &visible_kinds(&state)[..2],
+        &[ProcessColumnKind::Name, ProcessColumnKind::Pid]
+    );
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::MoveColumnLeft(ProcessColumnKind::Name),
+    );
+    assert_eq!(visible_kinds(&state)[0], ProcessColumnKind::Name);
+
+    let last = *visible_kinds(&state).last().unwrap();
+    let before = visible_kinds(&state);
+    let _ = update(&mut state, ProcessTableMessage::MoveColumnRight(last));
+    assert_eq!(visible_kinds(&state), before);
+}
+
+#[test]
+fn test_sort_by_optional_columns() {
+    let mut rows = vec![
+        ProcessInfo {
+            pid: 1,
+            user: Some("root".to_string()),
+            disk_write_rate: 10,
+            ..Default::default()
+        },
+        ProcessInfo {
+            pid: 2,
+            user: None,
+            disk_write_rate: 30,
+            ..Default::default()
+        },
+        ProcessInfo {
+            pid: 3,
+            user: Some("alice".to_string()),
+            disk_write_rate: 20,
+            ..Default::default()
+        },
+    ];
+
+    sort_processes(&mut rows, ProcessColumnKind::User, SortOrder::Ascending);
+    assert_eq!(pids(&rows), vec![2, 3, 1]);
+
+    sort_processes(
+        &mut rows,
+        ProcessColumnKind::DiskWrite,
+        SortOrder::Descending,
+    );
+    assert_eq!(pids(&rows), vec![2, 3, 1]);
+}
+
+#[test]
+fn test_collected_rows_skip_threads() {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    let users = Users::new_with_refreshed_list();
+
+    let slow = SlowColumns {
+        open_files: true,
+        priorities: false,
+    };
+    let rows = collect_processes(&sys, &users, slow);
+    let own = rows
+        .iter()
+        .find(|r| r.pid == std::process::id())
+        .expect("own process is listed");
+    assert!(own.threads.unwrap_or(1) >= 1);
+    assert!(own.open_files.is_some_and(|files| files > 0));
+    assert_eq!(own.nice, None);
+    assert!(rows.iter().all(|r| {
+        sys.process(Pid::from_u32(r.pid))
+            .unwrap()
+            .thread_kind()
+            .is_none()
+    }));
+}
+
+#[test]
+fn test_priorities_are_read_only_while_shown() {
+    let mut state = ProcessTableState::default();
+    assert_eq!(state.slow_columns(), SlowColumns::default());
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::ToggleColumn(ProcessColumnKind::IoPriority, true),
+    );
+    assert_eq!(
+        state.slow_columns(),
+        SlowColumns {
+            open_files: false,
+            priorities: true,
+        }
+    );
+
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    let users = Users::new_with_refreshed_list();
+    let rows = collect_processes(&sys, &users, state.slow_columns());
+    let own = rows.iter().find(|r| r.pid == std::process::id()).unwrap();
+    assert!(own.nice.is_some());
+    assert_eq!(own.open_files, None);
+}
@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use task_manager::process_table::{
+    ProcessColumnKind, ProcessInfo, ProcessTableMessage, ProcessTableState, ProcessViewMode,
+    SortOrder, update,
+};
+use task_manager::process_tree::build_tree;
+
+mod common;
+
+use common::process;
+
+// init(1) ─┬─ shell(10) ── cargo(11) ── rustc(12)
+//          └─ sshd(20)
+fn sample_rows() -> Vec<ProcessInfo> {
+    vec![
+        ProcessInfo {
+            parent_pid: Some(11),
+            ..process(12, "rustc", 40.0)
+        },
+        ProcessInfo {
+            parent_pid: Some(1),
+            ..process(20, "sshd", 1.0)
+        },
+        process(1, "init", 0.5),
+        ProcessInfo {
+            parent_pid: Some(10),
+            ..process(11, "cargo", 5.0)
+        },
+        ProcessInfo {
+            parent_pid: Some(1),
+            ..process(10, "shell", 0.0)
+        },
+    ]
+}
+
+fn layout(rows: &[ProcessInfo]) -> Vec<(u32, usize)> {
+    rows.iter()
+        .map(|r| (r.pid, r.tree.as_ref().unwrap().depth))
+        .collect()
+}
+
+#[test]
+fn test_children_follow_their_parent() {
+    let tree = build_tree(
+        &sample_rows(),
+        &HashSet::new(),
+        ProcessColumnKind::Pid,
+        SortOrder::Ascending,
+        None,
+    );
+    assert_eq!(
+        layout(&tree),
+        vec![(1, 0), (10, 1), (11, 2), (12, 3), (20, 1)]
+    );
+}
+
+#[test]
+fn test_siblings_use_the_table_sort() {
+    let tree = build_tree(
+        &sample_rows(),
+        &HashSet::new(),
+        ProcessColumnKind::CpuUsage,
+        SortOrder::Descending,
+        None,
+    );
+    assert_eq!(layout(&tree)[1], (20, 1));
+}
+
+#[test]
+fn test_subtree_totals() {
+    let tree = build_tree(
+        &sample_rows(),
+        &HashSet::new(),
+        ProcessColumnKind::Pid,
+        SortOrder::Ascending,
+        None,
+    );
+    let shell = tree.iter().find(|r| r.pid == 10).unwrap();
+    let node = shell.tree.as_ref().unwrap();
+    assert_eq!(node.subtree_cpu, 45.0);
+    assert_eq!(node.subtree_memory, 300);
+    assert!(node.has_children);
+}
+
+#[test]
+fn test_collapsed_process_hides_descendants() {
+    let collapsed = HashSet::from([10]);
+    let tree = build_tree(
+        &sample_rows(),
+        &collapsed,
+        ProcessColumnKind::Pid,
+        SortOrder::Ascending,
+        None,
+    );
+    assert_eq!(layout(&tree), vec![(1, 0), (10, 1), (20, 1)]);
+    assert!(!tree[1].tree.as_ref().unwrap().expanded);
+}
+
+#[test]
+fn test_search_keeps_ancestors_of_matches() {
+    let collapsed = HashSet::from([10]);
+    let matches = |r: &ProcessInfo| r.name == "rustc";
+    let tree = build_tree(
+        &sample_rows(),
+        &collapsed,
+        ProcessColumnKind::Pid,
+        SortOrder::Ascending,
+        Some(&matches),
+    );
+    assert_eq!(layout(&tree), vec![(1, 0), (10, 1), (11, 2), (12, 3)]);
+}
+
+#[test]
+fn test_parent_cycle_does_not_hang() {
+    let rows = vec![
+        ProcessInfo {
+            parent_pid: Some(3),
+            ..process(2, "a", 0.0)
+        },
+        ProcessInfo {
+            parent_pid: Some(2),
+            ..process(3, "b", 0.0)
+        },
+    ];
+    let tree = build_tree(
+        &rows,
+        &HashSet::new(),
+        ProcessColumnKind::Pid,
+        SortOrder::Ascending,
+        None,
+    );
+    assert_eq!(layout(&tree), vec![(2, 0), (3, 1)]);
+
+    let matches = |_: &ProcessInfo| true;
+    let tree = build_tree(
+        &rows,
+        &HashSet::new(),
+        ProcessColumnKind::Pid,
+        SortOrder::Ascending,
+        Some(&matches),
+    );
+    assert_eq!(layout(&tree), vec![(2, 0), (3, 1)]);
+}
+
+#[test]
+fn test_tree_mode_toggle_expanded() {
+    let mut state = ProcessTableState {
+        rows: sample_rows(),
+        ..Default::default()
+    };
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::ViewModeSelected(ProcessViewMode::Tree),
+    );
+    assert_eq!(state.filtered_rows.len(), 5);
+
+    let _ = update(&mut state, ProcessTableMessage::ToggleExpanded(1));
+    assert_eq!(state.filtered_rows.len(), 1);
+
+    let _ = update(&mut state, ProcessTableMessage::ToggleExpanded(1));
+    assert_eq!(state.filtered_rows.len(), 5);
+}
@@ -0,0 +1,181 @@
+use task_manager::process_table::{
+    ProcessColumnKind, ProcessInfo, ProcessTableMessage, ProcessTableState, update,
+};
+use task_manager::query::{Query, parse_bytes, quote};
+
+const MIB: u64 = 1024 * 1024;
+
+fn rows() -> Vec<ProcessInfo> {
+    vec![
+        ProcessInfo {
+            pid: 1,
+            name: "systemd".to_string(),
+            user: Some("root".to_string()),
+            cpu_usage: 0.5,
+            memory_bytes: 12 * MIB,
+            command_line: "/sbin/init splash".to_string(),
+            ..Default::default()
+        },
+        ProcessInfo {
+            pid: 200,
+            name: "cargo".to_string(),
+            user: Some("alice".to_string()),
+            cpu_usage: 85.0,
+            memory_bytes: 700 * MIB,
+            disk_read_rate: 2_000_000,
+            command_line: "cargo build --release".to_string(),
+            ..Default::default()
+        },
+        ProcessInfo {
+            pid: 300,
+            name: "rust-analyzer".to_string(),
+            user: Some("alice".to_string()),
+            cpu_usage: 25.0,
+            memory_bytes: 1500 * MIB,
+            command_line: "rust-analyzer".to_string(),
+            ..Default::default()
+        },
+        ProcessInfo {
+            pid: 400,
+            name: "kworker/0:1".to_string(),
+            user: None,
+            ..Default::default()
+        },
+    ]
+}
+
+fn matching(query: &str) -> Vec<u32> {
+    let query = Query::parse(query).unwrap_or_else(|err| panic!("{query}: {err}"));
+    rows()
+        .iter()
+        .filter(|row| query.matches(row))
+        .map(|row| row.pid)
+        .collect()
+}
+
+#[test]
+fn test_plain_words_match_name_or_pid() {
+    assert_eq!(matching(""), vec![1, 200, 300, 400]);
+    assert_eq!(matching("CARGO"), vec![200]);
+    assert_eq!(matching("30"), vec![300]);
+    assert_eq!(matching("\"rust-ana\""), vec![300]);
+}
+
+#[test]
+fn test_numeric_comparisons() {
+    assert_eq!(matching("cpu>20"), vec![200, 300]);
+    assert_eq!(matching("cpu>=25%"), vec![200, 300]);
+    assert_eq!(matching("cpu<1 pid!=400"), vec![1]);
+    assert_eq!(matching("pid=200"), vec![200]);
+}
+
+#[test]
+fn test_memory_comparisons_understand_units() {
+    assert_eq!(matching("mem>500MB"), vec![200, 300]);
+    assert_eq!(matching("mem>1GB"), vec![300]);
+    assert_eq!(matching("mem>=1500MiB"), vec![300]);
+    assert_eq!(matching("mem<1G mem>10M"), vec![1, 200]);
+    assert_eq!(matching("read>1.5mb"), vec![200]);
+
+    assert_eq!(parse_bytes("2KiB"), Some(2048.0));
+    assert_eq!(parse_bytes("2 KB"), Some(2000.0));
+    assert_eq!(parse_bytes("512"), Some(512.0));
+    assert_eq!(parse_bytes("5 parsecs"), None);
+}
+
+#[test]
+fn test_text_fields() {
+    assert_eq!(matching("user:root"), vec![1]);
+    assert_eq!(matching("user=ALICE cpu>50"), vec![200]);
+    assert_eq!(matching("cmd:release"), vec![200]);
+    // Processes without a known user match neither `=` nor `!=`.
+    assert_eq!(matching("user!=root"), vec![200, 300]);
+}
+
+#[test]
+fn test_regex() {
+    assert_eq!(matching("name~\"^cargo\""), vec![200]);
+    assert_eq!(matching("name~^r"), vec![300]);
+    assert_eq!(matching(r#"cmd~"--\w+$""#), vec![200]);
+}
+
+#[test]
+fn test_boolean_operators() {
+    assert_eq!(matching("user:root OR cpu>50"), vec![1, 200]);
+    assert_eq!(matching("user:alice AND NOT cargo"), vec![300]);
+    assert_eq!(matching("!user:alice"), vec![1, 400]);
+    assert_eq!(matching("(cargo || systemd) && mem>100MB"), vec![200]);
+    // AND binds tighter than OR.
+    assert_eq!(matching("systemd or cargo cpu>90"), vec![1]);
+}
+
+#[test]
+fn test_parse_errors_point_at_the_problem() {
+    let err = Query::parse("cpu>20 colour:red").unwrap_err();
+    assert_eq!(err.message, "unknown field `colour`");
+    assert_eq!(err.position, 7);
+
+    let err = Query::parse("mem>lots").unwrap_err();
+    assert!(err.message.contains("`lots` is not a number"), "{err}");
+    assert_eq!(err.position, 4);
+
+    let err = Query::parse("name~\"(\"").unwrap_err();
+    assert!(err.message.starts_with("invalid regex"), "{err}");
+
+    let err = Query::parse("user>root").unwrap_err();
+    assert_eq!(err.position, 4);
+
+    assert_eq!(Query::parse("(cargo").unwrap_err().message, "unclosed `(`");
+    assert_eq!(Query::parse("cargo)").unwrap_err().message, "unmatched `)`");
+    assert_eq!(
+        Query::parse("name:\"car").unwrap_err().message,
+        "unclosed quote"
+    );
+    assert_eq!(
+        Query::parse("cargo OR").unwrap_err().message,
+        "expected a search term"
+    );
+    assert_eq!(
+        Query::parse("cpu>").unwrap_err().message,
+        "`cpu>` needs a value"
+    );
+}
+
+#[test]
+fn test_invalid_query_keeps_previous_filter() {
+    let mut state = ProcessTableState {
+        rows: rows(),
+        ..Default::default()
+    };
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SortBy(ProcessColumnKind::Pid),
+    );
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SearchChanged("user:alice".to_string()),
+    );
+    let pids: Vec<u32> = state.filtered_rows.iter().map(|r| r.pid).collect();
+    assert_eq!(pids, vec![200, 300]);
+    assert!(state.query_error.is_none());
+
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SearchChanged("user:alice AND (".to_string()),
+    );
+    let pids: Vec<u32> = state.filtered_rows.iter().map(|r| r.pid).collect();
+    assert_eq!(pids, vec![200, 300]);
+    assert!(state.query_error.is_some());
+}
+
+#[test]
+fn test_quote_round_trips() {
+    let name = r#"odd "name" (with \ stuff)"#;
+    let query = Query::parse(&format!("name={}", quote(name))).unwrap();
+    let row = ProcessInfo {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    assert!(query.matches(&row));
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use task_manager::process_table::ProcessInfo;
+use task_manager::sampler::{
+    CoreSample, CpuSnapshot, MemorySnapshot, Sampler, SystemSnapshot, disk_rates,
+};
+use task_manager::session::Frame;
+use task_manager::utilities::mhz_to_ghz;
+
+#[test]
+fn test_sample_measures_since_previous_sample() {
+    let mut sampler = Sampler::new();
+    sampler.wait_until_ready();
+
+    let first = sampler.sample(false);
+    let interval = first.interval.unwrap();
+    assert!(interval >= sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    assert_eq!(first.cpu.cores.len(), sampler.sys.cpus().len());
+    assert!(first.memory.total > 0);
+    assert!(first.memory.used <= first.memory.total);
+    assert!(first.processes.is_empty());
+
+    std::thread::sleep(Duration::from_millis(20));
+    let second = sampler.sample(true);
+    assert!(second.interval.unwrap() >= Duration::from_millis(20));
+    let pid = std::process::id();
+    assert!(second.processes.iter().any(|p| p.pid == pid));
+    assert!(second.processes.iter().all(|p| p.open_files.is_none()));
+    assert!(second.disks.iter().all(|d| d.usage >= 0.0));
+}
+
+#[test]
+fn test_core_frequencies_follow_each_sample() {
+    let mut sampler = Sampler::new();
+    sampler.wait_until_ready();
+    let snapshot = sampler.sample(false);
+    for (core, cpu) in snapshot.cpu.cores.iter().zip(sampler.sys.cpus()) {
+        assert_eq!(core.name, cpu.name());
+        assert_eq!(core.frequency, mhz_to_ghz(cpu.frequency()));
+    }
+}
+
+#[test]
+fn test_current_reads_nothing_new() {
+    let sampler = Sampler::new();
+    let current = sampler.current();
+    assert_eq!(current.interval, None);
+    assert_eq!(current.cpu.cores.len(), sampler.sys.cpus().len());
+    assert!(current.processes.is_empty());
+}
+
+#[test]
+fn test_cpu_and_memory_summaries() {
+    assert_eq!(CpuSnapshot::default().frequency(), 0.0);
+    let core = |frequency| CoreSample {
+        name: String::new(),
+        usage: 0.0,
+        frequency,
+    };
+    let cpu = CpuSnapshot {
+        usage: 0.0,
+        cores: vec![core(2.0), core(4.0)],
+    };
+    assert_eq!(cpu.frequency(), 3.0);
+
+    assert_eq!(MemorySnapshot::default().usage_percent(), 0.0);
+    let memory = MemorySnapshot {
+        used: 1_000,
+        total: 4_000,
+    };
+    assert_eq!(memory.usage_percent(), 25.0);
+}
+
+#[test]
+fn test_frames_keep_the_recorded_layout() {
+    let line = r#"{"elapsed_ms":500,"cpu_usage":12.5,"cores":[],"used_memory":1,"total_memory":2,"uptime":3,"disks":[],"networks":[],"processes":[]}"#;
+    let frame: Frame = serde_json::from_str(line).unwrap();
+    assert_eq!(frame.elapsed_ms, 500);
+    assert_eq!(frame.snapshot.cpu.usage, 12.5);
+    assert_eq!(frame.snapshot.memory.total, 2);
+
+    let written = serde_json::to_value(Frame::from(SystemSnapshot {
+        interval: Some(Duration::from_secs(1)),
+        ..frame.snapshot
+    }))
+    .unwrap();
+    let expected: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(written["cpu_usage"], expected["cpu_usage"]);
+    assert_eq!(written["used_memory"], expected["used_memory"]);
+    assert!(written.get("interval").is_none());
+    assert!(written.get("cpu").is_none());
+}
+
+#[test]
+fn test_process_disk_io_becomes_per_second() {
+    let mut processes = vec![ProcessInfo {
+        disk_read_rate: 3_000,
+        disk_write_rate: 500,
+        ..Default::default()
+    }];
+    disk_rates(&mut processes, Duration::from_millis(500));
+    assert_eq!(processes[0].disk_read_rate, 6_000);
+    assert_eq!(processes[0].disk_write_rate, 1_000);
+
+    disk_rates(&mut processes, Duration::ZERO);
+    assert_eq!(processes[0].disk_read_rate, 0);
+}
@@ -0,0 +1,211 @@
+use iced::keyboard::Modifiers;
+use task_manager::process_table::{
+    Navigation, ProcessColumnKind, ProcessInfo, ProcessTableMessage, ProcessTableState,
+    ProcessViewMode, apply_filter, update,
+};
+
+mod common;
+
+use common::{process, state};
+
+/// Rows sorted by PID: 10, 20, 30, 40, 50.
+fn sleepers() -> ProcessTableState {
+    state(
+        [10, 20, 30, 40, 50]
+            .into_iter()
+            .map(|pid| process(pid, "sleep", 0.0))
+            .collect(),
+        ProcessColumnKind::Pid,
+    )
+}
+
+fn click(state: &mut ProcessTableState, pid: u32, modifiers: Modifiers) {
+    let _ = update(state, ProcessTableMessage::ModifiersChanged(modifiers));
+    let _ = update(state, ProcessTableMessage::RowSelected(pid));
+}
+
+#[test]
+fn test_plain_click_replaces_selection() {
+    let mut state = sleepers();
+    click(&mut state, 20, Modifiers::empty());
+    click(&mut state, 40, Modifiers::empty());
+    assert_eq!(state.selected_pids(), vec![40]);
+    assert_eq!(state.selected_pid, Some(40));
+}
+
+#[test]
+fn test_ctrl_click_toggles() {
+    let mut state = sleepers();
+    click(&mut state, 20, Modifiers::empty());
+    click(&mut state, 40, Modifiers::COMMAND);
+    assert_eq!(state.selected_pids(), vec![20, 40]);
+
+    click(&mut state, 20, Modifiers::COMMAND);
+    assert_eq!(state.selected_pids(), vec![40]);
+}
+
+#[test]
+fn test_shift_click_selects_range_from_anchor() {
+    let mut state = sleepers();
+    click(&mut state, 40, Modifiers::empty());
+    click(&mut state, 20, Modifiers::SHIFT);
+    assert_eq!(state.selected_pids(), vec![20, 30, 40]);
+
+    // The anchor stays put, so the range can be shrunk or flipped.
+    click(&mut state, 50, Modifiers::SHIFT);
+    assert_eq!(state.selected_pids(), vec![40, 50]);
+}
+
+#[test]
+fn test_ctrl_shift_click_adds_range() {
+    let mut state = sleepers();
+    click(&mut state, 10, Modifiers::empty());
+    click(&mut state, 40, Modifiers::COMMAND);
+    click(&mut state, 50, Modifiers::COMMAND | Modifiers::SHIFT);
+    assert_eq!(state.selected_pids(), vec![10, 40, 50]);
+}
+
+#[test]
+fn test_select_all_takes_only_filtered_rows() {
+    let mut state = sleepers();
+    state.rows.push(process(60, "cargo", 0.0));
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SearchChanged("sleep".to_string()),
+    );
+    let _ = update(&mut state, ProcessTableMessage::SelectAll);
+    assert_eq!(state.selected_pids(), vec![10, 20, 30, 40, 50]);
+
+    let _ = update(&mut state, ProcessTableMessage::ClearSelection);
+    assert!(state.selected_pids().is_empty());
+    assert_eq!(state.selected_pid, None);
+}
+
+#[test]
+fn test_select_all_in_tree_skips_context_ancestors() {
+    let mut state = sleepers();
+    // 10 is a shell running 20, which runs the match 60.
+    state.rows[1].parent_pid = Some(10);
+    state.rows.push(ProcessInfo {
+        parent_pid: Some(20),
+        ..process(60, "cargo", 0.0)
+    });
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::ViewModeSelected(ProcessViewMode::Tree),
+    );
+    let _ = update(
+        &mut state,
+        ProcessTableMessage::SearchChanged("cargo".to_string()),
+    );
+    let shown: Vec<u32> = state.filtered_rows.iter().map(|r| r.pid).collect();
+    assert_eq!(shown, vec![10, 20, 60]);
+
+    let _ = update(&mut state, ProcessTableMessage::SelectAll);
+    assert_eq!(state.selected_pids(), vec![60]);
+}
+
+#[test]
+fn test_exited_processes_leave_the_selection() {
+    let mut state = sleepers();
+    let _ = update(&mut state, ProcessTableMessage::SelectAll);
+    state.rows.retain(|r| r.pid != 30);
+    apply_filter(&mut state);
+    assert_eq!(state.selected_pids(), vec![10, 20, 40, 50]);
+}
+
+#[test]
+fn test_selection_info_lists_selected_processes() {
+    let mut state = sleepers();
+    click(&mut state, 20, Modifiers::empty());
+    click(&mut state, 30, Modifiers::COMMAND);
+    let info = state.selection_info();
+    assert_eq!(info.lines().count(), 2);
+    assert!(info.starts_with("sleep (PID 20)"));
+}
+
+fn navigate(state: &mut ProcessTableState, navigation: Navigation, modifiers: Modifiers) {
+    let _ = update(state, ProcessTableMessage::ModifiersChanged(modifiers));
+    let _ = update(state, ProcessTableMessage::Navigate(navigation));
+}
+
+#[test]
+fn test_arrow_keys_move_from_focused_row() {
+    let mut state = sleepers();
+    navigate(&mut state, Navigation::Down, Modifiers::empty());
+    assert_eq!(state.selected_pids(), vec![10]);
+
+    click(&mut state, 30, Modifiers::empty());
+    navigate(&mut state, Navigation::Down, Modifiers::empty());
+    assert_eq!(state.selected_pids(), vec![40]);
+    navigate(&mut state, Navigation::Up, Modifiers::empty());
+    navigate(&mut state, Navigation::Up, Modifiers::empty());
+    assert_eq!(state.selected_pids(), vec![20]);
+    assert_eq!(state.selected_pid, Some(20));
+}
+
+#[test]
+fn test_navigation_stops_at_the_ends() {
+    let mut state = sleepers();
+    navigate(&mut state, Navigation::End, Modifiers::empty());
+    navigate(&mut state, Navigation::Down, Modifiers::empty());
+    assert_eq!(state.selected_pids(), vec![50]);
+
+    navigate(&mut state, Navigation::PageUp, Modifiers::empty());
+    assert_eq!(state.selected_pids(), vec![10]);
+    navigate(&mut state, Navigation::PageDown, Modifiers::empty());
+    assert_eq!(state.selected_pids(), vec![50]);
+    navigate(&mut state, Navigation::Home, Modifiers::empty());
+    assert_eq!(state.selected_pids(), vec![10]);
+}
+
+#[test]
+fn test_shift_arrows_extend_selection() {
+    let mut state = sleepers();
+    click(&mut state, 20, Modifiers::empty());
+    navigate(&mut state, Navigation::Down, Modifiers::SHIFT);
+    navigate(&mut state, Navigation::Down, Modifiers::SHIFT);
+    assert_eq!(state.selected_pids(), vec![20, 30, 40]);
+
+    navigate(&mut state, Navigation::Up, Modifiers::SHIFT);
+    assert_eq!(state.selected_pids(), vec![20, 30]);
+}
+
+#[test]
+fn test_type_ahead_jumps_by_name() {
+    let mut state = state(
+        vec![
+            process(1, "bash", 0.0),
+            process(2, "cargo", 0.0),
+            process(3, "chrome", 0.0),
+            process(4, "code", 0.0),
+        ],
+        ProcessColumnKind::Pid,
+    );
+
+    let mut type_char = |c| {
+        let _ = update(&mut state, ProcessTableMessage::TypeAhead(c));
+        state.selected_pids()
+    };
+    assert_eq!(type_char('c'), vec![2]);
+    assert_eq!(type_char('o'), vec![4]);
+}
+
+#[test]
+fn test_type_ahead_repeated_letter_cycles() {
+    let mut state = state(
+        vec![
+            process(1, "cargo", 0.0),
+            process(2, "bash", 0.0),
+            process(3, "chrome", 0.0),
+        ],
+        ProcessColumnKind::Pid,
+    );
+
+    let _ = update(&mut state, ProcessTableMessage::TypeAhead('c'));
+    assert_eq!(state.selected_pids(), vec![1]);
+    // Let the prefix expire so the next letter starts over.
+    state.type_ahead_at = None;
+    let _ = update(&mut state, ProcessTableMessage::TypeAhead('C'));
+    assert_eq!(state.selected_pids(), vec![3]);
+}
@@ -0,0 +1,261 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use task_manager::disks::DiskState;
+use task_manager::history::Retention;
+use task_manager::process_table::ProcessInfo;
+use task_manager::sampler::{
+    CpuSnapshot, DiskSample, MemorySnapshot, SystemSnapshot, record_disks,
+};
+use task_manager::session::{
+    FLUSH_FRAMES, Frame, Recorder, Replay, ReplaySpeed, Session, SessionError, VERSION,
+};
+
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("task-manager-session-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+fn frame(cpu_usage: f32) -> Frame {
+    SystemSnapshot {
+        cpu: CpuSnapshot {
+            usage: cpu_usage,
+            cores: Vec::new(),
+        },
+        memory: MemorySnapshot {
+            used: 4_000,
+            total: 8_000,
+        },
+        processes: vec![ProcessInfo {
+            pid: 42,
+            name: "cargo".to_string(),
+            cpu_usage,
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+    .into()
+}
+
+fn session(elapsed_ms: &[u64]) -> Arc<Session> {
+    let path = temp_path(&format!("replay-{}.tmsession", elapsed_ms.len()));
+    let mut recorder = Recorder::create(&path).unwrap();
+    for (i, ms) in elapsed_ms.iter().enumerate() {
+        recorder
+            .append_at(Duration::from_millis(*ms), frame(i as f32))
+            .unwrap();
+    }
+    recorder.finish().unwrap();
+    let session = Session::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    Arc::new(session)
+}
+
+#[test]
+fn test_recording_round_trip() {
+    let path = temp_path("round-trip.tmsession");
+    let mut recorder = Recorder::create(&path).unwrap();
+    recorder
+        .append_at(Duration::from_millis(0), frame(10.0))
+        .unwrap();
+    recorder
+        .append_at(Duration::from_millis(1000), frame(20.0))
+        .unwrap();
+    assert_eq!(recorder.frames(), 2);
+    recorder.finish().unwrap();
+
+    let session = Session::load(&path).unwrap();
+    assert_eq!(session.header.version, VERSION);
+    assert!(!session.truncated);
+    assert_eq!(session.frames.len(), 2);
+    assert_eq!(session.frames[1].elapsed_ms, 1000);
+    assert_eq!(session.frames[1].snapshot.cpu.usage, 20.0);
+    assert_eq!(session.processes(1).unwrap()[0].name, "cargo");
+    assert_eq!(session.duration(), Duration::from_secs(1));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_process_lists_are_read_on_demand() {
+    let path = temp_path("on-demand.tmsession");
+    let mut recorder = Recorder::create(&path).unwrap();
+    let count = FLUSH_FRAMES * 2 + 5;
+    for i in 0..count {
+        recorder
+            .append_at(Duration::from_secs(i as u64), frame(i as f32))
+            .unwrap();
+    }
+    recorder.finish().unwrap();
+
+    let session = Session::load(&path).unwrap();
+    assert_eq!(session.frames.len(), count);
+    assert!(
+        session
+            .frames
+            .iter()
+            .all(|f| f.snapshot.processes.is_empty())
+    );
+    for index in [0, FLUSH_FRAMES - 1, FLUSH_FRAMES, count - 1] {
+        let processes = session.processes(index).unwrap();
+        assert_eq!(processes[0].cpu_usage, index as f32);
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_crashed_recording_keeps_flushed_frames() {
+    let path = temp_path("crashed.tmsession");
+    let mut recorder = Recorder::create(&path).unwrap();
+    for i in 0..FLUSH_FRAMES + 3 {
+        recorder
+            .append_at(Duration::from_secs(i as u64), frame(i as f32))
+            .unwrap();
+    }
+    // Dropped without finishing, as when the app is killed.
+    drop(recorder);
+
+    let session = Session::load(&path).unwrap();
+    assert_eq!(session.frames.len(), FLUSH_FRAMES);
+    let last = session.processes(FLUSH_FRAMES - 1).unwrap();
+    assert_eq!(last[0].cpu_usage, (FLUSH_FRAMES - 1) as f32);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_unfinished_recording_loads_complete_frames() {
+    let path = temp_path("unfinished.tmsession");
+    let mut recorder = Recorder::create(&path).unwrap();
+    recorder.append_at(Duration::ZERO, frame(10.0)).unwrap();
+    recorder
+        .append_at(Duration::from_millis(500), frame(20.0))
+        .unwrap();
+    recorder.finish().unwrap();
+
+    // Drop the gzip trailer, as if the app died while recording.
+    let bytes = std::fs::read(&path).unwrap();
+    let session = Session::read(&bytes[..bytes.len() - 8]).unwrap();
+    assert!(session.truncated);
+    assert_eq!(session.frames.len(), 2);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_rejects_other_files_and_newer_versions() {
+    assert!(matches!(
+        Session::read(&b"not a recording"[..]),
+        Err(SessionError::NotASession)
+    ));
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    writeln!(
+        encoder,
+        r#"{{"format":"task-manager-session","version":{},"started":0}}"#,
+        VERSION + 1
+    )
+    .unwrap();
+    let bytes = encoder.finish().unwrap();
+    assert!(matches!(
+        Session::read(&bytes[..]),
+        Err(SessionError::UnsupportedVersion(v)) if v == VERSION + 1
+    ));
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    writeln!(
+        encoder,
+        r#"{{"format":"task-manager-session","version":{VERSION},"started":0}}"#
+    )
+    .unwrap();
+    let bytes = encoder.finish().unwrap();
+    assert!(matches!(
+        Session::read(&bytes[..]),
+        Err(SessionError::Empty)
+    ));
+}
+
+#[test]
+fn test_replay_advances_at_speed_and_stops_at_end() {
+    let mut replay = Replay::new(PathBuf::from("x"), session(&[0, 1000, 2000, 3000]));
+    assert_eq!(replay.position(), 0);
+
+    // Nothing moves while paused.
+    replay.advance(Duration::from_secs(5));
+    assert_eq!(replay.position(), 0);
+
+    replay.toggle_playing();
+    replay.speed = ReplaySpeed(2.0);
+    replay.advance(Duration::from_millis(400));
+    assert_eq!(replay.position(), 0);
+    replay.advance(Duration::from_millis(100));
+    assert_eq!(replay.position(), 1);
+
+    replay.advance(Duration::from_secs(10));
+    assert_eq!(replay.position(), 3);
+    assert!(!replay.playing);
+
+    // Playing at the end starts over.
+    replay.toggle_playing();
+    assert_eq!(replay.position(), 0);
+    assert!(replay.playing);
+}
+
+#[test]
+fn test_replay_seek_and_chart_window() {
+    let mut replay = Replay::new(PathBuf::from("x"), session(&[0, 1000, 2000, 3000]));
+    replay.seek(99);
+    assert_eq!(replay.position(), 3);
+    assert_eq!(replay.frame().snapshot.cpu.usage, 3.0);
+
+    let retention = Retention {
+        recent: Duration::from_secs(1),
+        total: Duration::from_secs(2),
+        bucket_width: Duration::from_secs(1),
+    };
+    assert_eq!(replay.window_start(retention), 1);
+    replay.seek(0);
+    assert_eq!(replay.window_start(retention), 0);
+}
+
+#[test]
+fn test_replay_disks_follows_recorded_list() {
+    let sample = |mount: &str, usage| DiskSample {
+        name: mount.to_string(),
+        mount_point: PathBuf::from(mount),
+        file_system: "ext4".to_string(),
+        is_removable: false,
+        total_space: 100,
+        available_space: 40,
+        usage,
+    };
+    let retention = Retention::default();
+    let start = Instant::now();
+    let mut states: Vec<DiskState> = Vec::new();
+
+    record_disks(&mut states, &[sample("/", 1.5)], start, retention);
+    record_disks(
+        &mut states,
+        &[sample("/", 2.5), sample("/home", 0.5)],
+        start + Duration::from_secs(1),
+        retention,
+    );
+    assert_eq!(states.len(), 2);
+    assert_eq!(states[0].history.values(), &[1.5, 2.5]);
+    assert_eq!(states[1].usage, 0.5);
+
+    record_disks(
+        &mut states,
+        &[sample("/home", 0.0)],
+        start + Duration::from_secs(2),
+        retention,
+    );
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].mount_point, PathBuf::from("/home"));
+}
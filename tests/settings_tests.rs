@@ -0,0 +1,214 @@
+use task_manager::keymap::{Command, Keymap};
+use task_manager::process_table::ProcessColumnKind;
+use task_manager::settings::{
+    DefaultView, MIN_REFRESH_INTERVAL_MS, ProtectedPolicy, SavedFilter, Settings, Units,
+};
+use task_manager::theme::Theme;
+
+#[test]
+fn test_parse_full_file() {
+    let (settings, warnings) = Settings::parse(
+        r#"
+        theme = "Light"
+        refresh_interval_ms = 2000
+        default_view = "Performance"
+        history_minutes = 15
+        units = "Decimal"
+
+        [[columns]]
+        kind = "Name"
+        width = 300.0
+        visible = true
+
+        [[columns]]
+        kind = "Pid"
+        width = 60.0
+        visible = false
+        "#,
+    );
+
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(settings.theme, Theme::Light);
+    assert_eq!(settings.refresh_interval_ms, 2000);
+    assert_eq!(settings.default_view, DefaultView::Performance);
+    assert_eq!(settings.history_minutes, 15);
+    assert_eq!(settings.units, Units::Decimal);
+    assert_eq!(settings.columns[0].kind, ProcessColumnKind::Name);
+    assert_eq!(settings.columns[0].width, 300.0);
+    assert!(!settings.columns[1].visible);
+    // Columns missing from the file are appended with their defaults.
+    assert_eq!(settings.columns.len(), ProcessColumnKind::ALL.len());
+}
+
+#[test]
+fn test_parse_invalid_toml_falls_back_to_defaults() {
+    let (settings, warnings) = Settings::parse("theme = ");
+    assert_eq!(settings, Settings::default());
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn test_parse_bad_field_only_resets_that_field() {
+    let (settings, warnings) = Settings::parse(
+        r#"
+        theme = "Purple"
+        history_minutes = 30
+        "#,
+    );
+    assert_eq!(settings.theme, Settings::default().theme);
+    assert_eq!(settings.history_minutes, 30);
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn test_parse_clamps_out_of_range_values() {
+    let (settings, warnings) = Settings::parse(
+        r#"
+        refresh_interval_ms = 1
+        history_minutes = 0
+        "#,
+    );
+    assert_eq!(settings.refresh_interval_ms, MIN_REFRESH_INTERVAL_MS);
+    assert_eq!(settings.history_minutes, 1);
+    assert_eq!(warnings.len(), 2);
+}
+
+#[test]
+fn test_parse_requires_a_visible_column() {
+    let mut source = String::new();
+    for kind in ["Pid", "Name", "CpuUsage", "MemoryUsage", "DiskUsage"] {
+        source.push_str(&format!(
+            "[[columns]]\nkind = \"{kind}\"\nwidth = 100.0\nvisible = false\n"
+        ));
+    }
+
+    let (settings, warnings) = Settings::parse(&source);
+    assert_eq!(settings.columns, Settings::default().columns);
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn test_save_and_reload_round_trip() {
+    let path = std::env::temp_dir()
+        .join(format!("task-manager-test-{}", std::process::id()))
+        .join("config.toml");
+
+    let settings = Settings {
+        theme: Theme::Light,
+        history_minutes: 60,
+        ..Settings::default()
+    };
+    settings.save_to(&path).unwrap();
+
+    let (loaded, warnings) = Settings::parse(&std::fs::read_to_string(&path).unwrap());
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(loaded, settings);
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_parse_protected_processes() {
+    let source = r#"
+protected_processes = [" sshd ", "sshd", "", "Xorg"]
+protected_policy = "Refuse"
+"#;
+
+    let (settings, warnings) = Settings::parse(source);
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(settings.protected_processes, vec!["sshd", "Xorg"]);
+    assert_eq!(settings.protected_policy, ProtectedPolicy::Refuse);
+}
+
+#[test]
+fn test_parse_appends_optional_columns_hidden() {
+    let source = r#"
+[[columns]]
+kind = "Name"
+width = 200.0
+visible = true
+"#;
+
+    let (settings, warnings) = Settings::parse(source);
+    assert!(warnings.is_empty(), "{warnings:?}");
+    let nice = settings
+        .columns
+        .iter()
+        .find(|c| c.kind == ProcessColumnKind::Nice)
+        .unwrap();
+    assert!(!nice.visible);
+    let pid = settings
+        .columns
+        .iter()
+        .find(|c| c.kind == ProcessColumnKind::Pid)
+        .unwrap();
+    assert!(pid.visible);
+}
+
+#[test]
+fn test_parse_keymap_overrides_single_commands() {
+    let source = r#"
+[keymap]
+end_task = "Ctrl+Shift+Delete"
+focus_search = "Ctrl+Q+W"
+jump_around = "J"
+"#;
+
+    let (settings, warnings) = Settings::parse(source);
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+    assert_eq!(
+        settings.keymap.binding(Command::EndTask),
+        Some("Ctrl+Shift+Delete".parse().unwrap())
+    );
+    assert_eq!(
+        settings.keymap.binding(Command::FocusSearch),
+        Keymap::default().binding(Command::FocusSearch)
+    );
+    assert_eq!(
+        settings.keymap.binding(Command::Refresh),
+        Keymap::default().binding(Command::Refresh)
+    );
+}
+
+#[test]
+fn test_parse_warns_about_conflicting_shortcuts() {
+    let source = r#"
+[keymap]
+refresh = "Ctrl+P"
+"#;
+
+    let (_, warnings) = Settings::parse(source);
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(warnings[0].contains("Ctrl+P"), "{warnings:?}");
+}
+
+#[test]
+fn test_parse_saved_filters_and_pins() {
+    let source = r#"
+pinned_processes = ["name=sshd", " cmd~nginx ", "name=sshd", "", "cpu>"]
+
+[[saved_filters]]
+name = "Busy"
+query = "cpu>20"
+
+[[saved_filters]]
+name = "  "
+query = "mem>1GB"
+
+[[saved_filters]]
+name = "Busy"
+query = "cpu>50"
+"#;
+
+    let (settings, warnings) = Settings::parse(source);
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(warnings[0].contains("cpu>"), "{warnings:?}");
+    assert_eq!(
+        settings.saved_filters,
+        vec![SavedFilter {
+            name: "Busy".to_string(),
+            query: "cpu>20".to_string(),
+        }]
+    );
+    assert_eq!(settings.pinned_processes, vec!["name=sshd", "cmd~nginx"]);
+}
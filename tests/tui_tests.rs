@@ -0,0 +1,110 @@
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use task_manager::settings::Settings;
+use task_manager::tui::{App, Mode, TuiView};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn screen(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+    terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect()
+}
+
+#[test]
+fn test_draws_both_views() {
+    let mut app = App::new(Settings::default());
+    assert_eq!(app.view, TuiView::Processes);
+    assert!(!app.table.rows.is_empty());
+    let processes = screen(&mut app);
+    assert!(processes.contains("PID"));
+    assert!(processes.contains("processes"));
+
+    app.handle_key(key(KeyCode::Tab));
+    assert_eq!(app.view, TuiView::Performance);
+    let performance = screen(&mut app);
+    assert!(performance.contains("CPU"));
+    assert!(performance.contains("Memory"));
+    assert!(performance.contains("Disk"));
+}
+
+#[test]
+fn test_search_filters_live_and_escape_clears() {
+    let mut app = App::new(Settings::default());
+    app.handle_key(key(KeyCode::Char('/')));
+    assert_eq!(app.mode, Mode::Search);
+    for c in "zzz-no-such-process".chars() {
+        app.handle_key(key(KeyCode::Char(c)));
+    }
+    assert_eq!(app.table.search_query, "zzz-no-such-process");
+    assert!(app.table.filtered_rows.is_empty());
+
+    // Letters go to the search box, not to the shortcuts.
+    assert!(!app.quit);
+
+    app.handle_key(key(KeyCode::Esc));
+    assert_eq!(app.mode, Mode::Normal);
+    assert!(app.table.search_query.is_empty());
+    assert_eq!(app.table.filtered_rows.len(), app.table.rows.len());
+}
+
+#[test]
+fn test_sort_keys_cycle_columns_and_reverse() {
+    let mut app = App::new(Settings::default());
+    let columns = app.table.visible_columns();
+    let first = app.table.sort_column;
+    let index = columns.iter().position(|c| *c == first).unwrap();
+
+    app.handle_key(key(KeyCode::Char('s')));
+    assert_eq!(app.table.sort_column, columns[(index + 1) % columns.len()]);
+    app.handle_key(key(KeyCode::Char('S')));
+    assert_eq!(app.table.sort_column, first);
+
+    let order = app.table.sort_order;
+    app.handle_key(key(KeyCode::Char('r')));
+    assert_ne!(app.table.sort_order, order);
+}
+
+#[test]
+fn test_end_task_asks_first_and_can_be_cancelled() {
+    let mut app = App::new(Settings::default());
+    app.handle_key(key(KeyCode::Char('e')));
+    assert_eq!(app.mode, Mode::Normal, "nothing is selected yet");
+
+    // Select our own process so nothing else is touched.
+    let pid = std::process::id();
+    app.handle_key(key(KeyCode::Char('/')));
+    for c in format!("pid:{pid}").chars() {
+        app.handle_key(key(KeyCode::Char(c)));
+    }
+    app.handle_key(key(KeyCode::Enter));
+    app.handle_key(key(KeyCode::Home));
+    assert_eq!(app.table.selected_pids().len(), 1);
+
+    app.handle_key(key(KeyCode::Delete));
+    assert!(matches!(app.mode, Mode::Confirm { .. }));
+    assert!(screen(&mut app).contains("[y/n]"));
+    app.handle_key(key(KeyCode::Char('n')));
+    assert_eq!(app.mode, Mode::Normal);
+    assert_eq!(app.table.selected_pids(), vec![pid]);
+}
+
+#[test]
+fn test_quit_keys() {
+    let mut app = App::new(Settings::default());
+    app.handle_key(key(KeyCode::Char('q')));
+    assert!(app.quit);
+
+    let mut app = App::new(Settings::default());
+    app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    assert!(app.quit);
+}
@@ -1,4 +1,10 @@
-use task_manager::utilities::{bytes_to_gb, format_uptime, memory_usage_percent, mhz_to_ghz};
+use std::time::Duration;
+
+use task_manager::settings::Units;
+use task_manager::utilities::{
+    bytes_to_gb, format_bytes, format_duration, format_timestamp, format_uptime,
+    memory_usage_percent, mhz_to_ghz, per_second,
+};
 
 #[test]
 fn test_bytes_to_gb() {
@@ -65,3 +71,40 @@ fn test_mhz_to_ghz() {
     assert_eq!(mhz_to_ghz(3500), 3.5);
     assert_eq!(mhz_to_ghz(2400), 2.4);
 }
+
+#[test]
+fn test_format_bytes_binary() {
+    assert_eq!(format_bytes(0, Units::Binary), "0.0 MiB");
+    assert_eq!(format_bytes(512 * 1024 * 1024, Units::Binary), "512.0 MiB");
+    assert_eq!(
+        format_bytes(3 * 1024 * 1024 * 1024, Units::Binary),
+        "3.0 GiB"
+    );
+}
+
+#[test]
+fn test_format_bytes_decimal() {
+    assert_eq!(format_bytes(500_000_000, Units::Decimal), "500.0 MB");
+    assert_eq!(format_bytes(1_500_000_000, Units::Decimal), "1.5 GB");
+}
+
+#[test]
+fn test_per_second() {
+    assert_eq!(per_second(1000, Duration::from_secs(2)), 500.0);
+    assert_eq!(per_second(1000, Duration::from_millis(250)), 4000.0);
+    assert_eq!(per_second(1000, Duration::ZERO), 0.0);
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(59), "0:00:59");
+    assert_eq!(format_duration(3_723), "1:02:03");
+    assert_eq!(format_duration(90_061), "1d 1:01:01");
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00:00 UTC");
+    assert_eq!(format_timestamp(1_790_000_000), "2026-09-21 14:13:20 UTC");
+}